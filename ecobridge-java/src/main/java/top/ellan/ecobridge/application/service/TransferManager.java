@@ -82,6 +82,8 @@ public class TransferManager {
     private static final VarHandle VH_TCTX_SCORE;
     private static final VarHandle VH_TCTX_VELOCITY;
     private static final VarHandle VH_RCFG_V_THRESHOLD;
+    private static final VarHandle VH_RCFG_V_PENALTY;
+    private static final VarHandle VH_RCFG_V_WARNING;
 
     static {
         try {
@@ -100,6 +102,8 @@ public class TransferManager {
 
             var regLayout = NativeBridge.Layouts.REGULATOR_CONFIG;
             VH_RCFG_V_THRESHOLD = regLayout.varHandle(MemoryLayout.PathElement.groupElement("velocity_threshold"));
+            VH_RCFG_V_PENALTY = regLayout.varHandle(MemoryLayout.PathElement.groupElement("velocity_penalty_coef"));
+            VH_RCFG_V_WARNING = regLayout.varHandle(MemoryLayout.PathElement.groupElement("warning_velocity_ratio"));
 
         } catch (Exception e) {
             throw new RuntimeException("CRITICAL: TransferManager 内存布局初始化失败", e);
@@ -250,6 +254,7 @@ public class TransferManager {
 
         boolean canBypassBlock = sender.isOp() || sender.hasPermission(BYPASS_BLOCK_PERMISSION);
         if (audit.isBlocked() && !canBypassBlock) {
+            NativeBridge.logBlockedTransfer(audit.warningCode());
             handleBlocked(sender, audit.warningCode());
             return;
         }
@@ -413,6 +418,9 @@ public class TransferManager {
         cfg.set(JAVA_DOUBLE, 80, section != null ? section.getDouble("veteran-hours", 100.0) : 100.0);
         
         VH_RCFG_V_THRESHOLD.set(cfg, 0L, vThreshold);
+        // 0 沿用默认系数 0.05，负数关闭频率惩罚税
+        VH_RCFG_V_PENALTY.set(cfg, 0L, section != null ? section.getDouble("velocity-penalty-coef", 0.05) : 0.05);
+        VH_RCFG_V_WARNING.set(cfg, 0L, section != null ? section.getDouble("warning-velocity-ratio", 0.7) : 0.7);
     }
 
     private void handleBlocked(Player sender, int code) {
@@ -11,6 +11,8 @@ public enum EconStatus {
     NULL_POINTER(1),
     INVALID_LENGTH(2),
     INVALID_VALUE(3),
+    /** 幂等追加命中近期窗口：记录已存在，未重复写入 (非错误) */
+    DUPLICATE(4),
     NUMERIC_OVERFLOW(10),
     INTERNAL_ERROR(100),
     PANIC(101),
@@ -38,11 +40,18 @@ public enum EconStatus {
         return this == OK;
     }
 
+    /**
+     * 是否为需要处理的错误。DUPLICATE 表示重放被安全跳过，不视为错误。
+     */
+    public boolean isError() {
+        return this != OK && this != DUPLICATE;
+    }
+
     /**
      * 核心校验逻辑：如果状态不正常，根据严重程度执行决策
      */
     public void check(String context) {
-        if (isOk()) return;
+        if (!isError()) return;
 
         String msg = "[Native Error] " + context + " failed with status: " + this.name();
         
@@ -33,7 +33,7 @@ public class NativeBridge {
     private enum BridgeState { UNINITIALIZED, RUNNING, SHUTTING_DOWN, CLOSED }
     private static final AtomicReference<BridgeState> STATE = new AtomicReference<>(BridgeState.UNINITIALIZED);
 
-    private static final int EXPECTED_ABI_VERSION = 0x0009_0000;
+    private static final int EXPECTED_ABI_VERSION = 0x000A_0000;
     private static volatile Arena sharedArena;
     
     // 生命周期锁
@@ -113,6 +113,7 @@ public class NativeBridge {
     private static volatile MethodHandle computePriceMH;
     private static volatile MethodHandle calculateEpsilonMH;
     private static volatile MethodHandle checkTransferMH;
+    private static volatile MethodHandle logBlockedTransferMH;
     private static volatile MethodHandle computePidMH;
     private static volatile MethodHandle resetPidMH;
     private static volatile MethodHandle calcInflationMH;
@@ -276,6 +277,7 @@ public class NativeBridge {
         computePriceBoundedMH = bind(linker, "ecobridge_compute_price_bounded", FunctionDescriptor.of(JAVA_INT, JAVA_DOUBLE, JAVA_DOUBLE, JAVA_DOUBLE, JAVA_DOUBLE, JAVA_DOUBLE, JAVA_DOUBLE, ADDRESS), Linker.Option.critical(true));
         
         checkTransferMH = bind(linker, "ecobridge_compute_transfer_check", FunctionDescriptor.of(JAVA_INT, ADDRESS, ADDRESS, ADDRESS));
+        logBlockedTransferMH = bind(linker, "ecobridge_log_blocked_transfer", FunctionDescriptor.of(JAVA_INT, JAVA_INT));
         computePidMH = bind(linker, "ecobridge_compute_pid_adjustment", FunctionDescriptor.of(JAVA_INT, ADDRESS, JAVA_DOUBLE, JAVA_DOUBLE, JAVA_DOUBLE, JAVA_DOUBLE, JAVA_DOUBLE, ADDRESS));
         resetPidMH = bind(linker, "ecobridge_reset_pid_state", FunctionDescriptor.of(JAVA_INT, ADDRESS));
         computeBatchPricesMH = bind(linker, "ecobridge_compute_batch_prices", FunctionDescriptor.of(JAVA_INT, JAVA_LONG, JAVA_DOUBLE, ADDRESS, ADDRESS, ADDRESS, ADDRESS, ADDRESS));
@@ -352,7 +354,7 @@ public class NativeBridge {
         initThreadingMH = null; getAbiVersionMH = null; initDBMH = null; getVersionMH = null;
         getHealthStatsMH = null; shutdownDBMH = null; pushToDuckDBMH = null;
         queryNeffVectorizedMH = null; computePriceMH = null; calculateEpsilonMH = null;
-        checkTransferMH = null; computePidMH = null; resetPidMH = null;
+        checkTransferMH = null; logBlockedTransferMH = null; computePidMH = null; resetPidMH = null;
         calcInflationMH = null; calcStabilityMH = null; calcDecayMH = null;
         computePlayerSellPriceMH = null; computeLogisticDecayMH = null; computeSystemBidMH = null; computePriceFinalMH = null; computeTierPriceMH = null; computePriceBoundedMH = null; computeBatchPricesMH = null;
         injectRemoteTradeMH = null; getDynamicLimitMH = null;
@@ -418,6 +420,13 @@ public class NativeBridge {
         return executeSafely(() -> (int) checkTransferMH.invokeExact(result, ctx, cfg), -1, false);
     }
 
+    /**
+     * 记录一笔实际执行时被拦截的转账。审计检查本身无副作用，预览路径不应调用此方法。
+     */
+    public static void logBlockedTransfer(int warningCode) {
+        executeSafely(() -> (int) logBlockedTransferMH.invokeExact(warningCode), -1, false);
+    }
+
     // ... 其他计算方法 ...
 
     public static double computePrice(double base, double nEff, double amount, double lambda, double epsilon) {
@@ -23,6 +23,12 @@ import java.util.concurrent.TimeUnit;
  */
 public class DatabaseManager {
 
+    /**
+     * 按玩家查询流水的索引。独立于建表语句执行，已存在的旧表在升级后同样会补建。
+     */
+    public static final String SQL_PLAYER_HISTORY_INDEX =
+        "CREATE INDEX IF NOT EXISTS idx_player_history ON ecobridge_sales (player_uuid, timestamp)";
+
     private static HikariDataSource dataSource;
     private static ExecutorService dbExecutor;
 
@@ -171,6 +177,7 @@ public class DatabaseManager {
 
         try (Connection conn = getConnection(); Statement stmt = conn.createStatement()) {
             stmt.execute(sqlSales);
+            stmt.execute(SQL_PLAYER_HISTORY_INDEX);
             stmt.execute(sqlPlayers);
         } catch (SQLException e) {
             LogUtil.error("DDL 初始化失败，请检查数据库权限。", e);
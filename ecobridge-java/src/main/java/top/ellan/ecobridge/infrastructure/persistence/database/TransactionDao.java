@@ -286,6 +286,49 @@ public class TransactionDao {
         return history;
     }
 
+    /**
+     * A row of a player's transaction history.
+     */
+    public record PlayerSale(long timestamp, String productId, double amount) {}
+
+    /**
+     * Get a player's most recent transactions, newest first (for /ecoadmin history).
+     * Returns an empty list for limit <= 0, an unknown player or a disconnected database.
+     */
+    public static List<PlayerSale> getPlayerRecentSales(UUID uuid, int limit) {
+        if (uuid == null || limit <= 0 || !DatabaseManager.isConnected()) return new ArrayList<>();
+
+        try (Connection conn = DatabaseManager.getConnection()) {
+            return queryPlayerRecentSales(conn, uuid, limit);
+        } catch (SQLException e) {
+            LogUtil.error("Player transaction history query failed: " + uuid, e);
+        }
+        return new ArrayList<>();
+    }
+
+    /**
+     * Run the player history query on the given connection (separated for testing).
+     */
+    public static List<PlayerSale> queryPlayerRecentSales(Connection conn, UUID uuid, int limit) throws SQLException {
+        List<PlayerSale> history = new ArrayList<>();
+        if (limit <= 0) return history;
+
+        String sql = "SELECT timestamp, product_id, amount FROM ecobridge_sales WHERE player_uuid = ? " +
+                "ORDER BY timestamp DESC LIMIT ?";
+
+        try (PreparedStatement pstmt = conn.prepareStatement(sql)) {
+            pstmt.setString(1, uuid.toString());
+            pstmt.setInt(2, limit);
+
+            try (ResultSet rs = pstmt.executeQuery()) {
+                while (rs.next()) {
+                    history.add(new PlayerSale(rs.getLong("timestamp"), rs.getString("product_id"), rs.getDouble("amount")));
+                }
+            }
+        }
+        return history;
+    }
+
     private static boolean isFatalError(SQLException e) {
         String state = e.getSQLState();
         if (state == null) return false;
@@ -1,9 +1,17 @@
 package top.ellan.ecobridge.integration.platform.command;
 
+import java.time.Instant;
+import java.time.ZoneId;
+import java.time.format.DateTimeFormatter;
 import java.util.ArrayList;
 import java.util.Collections;
 import java.util.List;
+import java.util.UUID;
+import java.util.concurrent.ExecutorService;
 import java.util.stream.Collectors;
+import net.kyori.adventure.text.minimessage.tag.resolver.Placeholder;
+import org.bukkit.Bukkit;
+import org.bukkit.OfflinePlayer;
 import org.bukkit.command.Command;
 import org.bukkit.command.CommandSender;
 import org.bukkit.command.TabExecutor;
@@ -13,16 +21,23 @@ import org.jetbrains.annotations.Nullable;
 import top.ellan.ecobridge.EcoBridge;
 import top.ellan.ecobridge.application.service.ItemConfigManager;
 import top.ellan.ecobridge.application.service.PricingManager;
+import top.ellan.ecobridge.infrastructure.persistence.database.DatabaseManager;
+import top.ellan.ecobridge.infrastructure.persistence.database.TransactionDao;
+import top.ellan.ecobridge.infrastructure.persistence.database.TransactionDao.PlayerSale;
 import top.ellan.ecobridge.util.UltimateShopImporter;
 
 /**
  * EcoBridge 管理指令 (v1.2.0 - Secured) 职责： 1. 切换影子模式与重载配置。 2. 强制设置商品基准价 (setprice)。 3. 重置商品经济数据
- * (reset)。 4. 从 UltimateShop 导入商品数据 (import)。 * 修复： - [Security] 修复了 Tab 补全的权限泄露问题。 - [Fix]
+ * (reset)。 4. 从 UltimateShop 导入商品数据 (import)。 5. 查询玩家近期流水 (history)。 * 修复： - [Security] 修复了 Tab 补全的权限泄露问题。 - [Fix]
  * 增加了配置读取的空指针防御。
  */
 public class AdminCommand implements TabExecutor {
 
   private static final String PERMISSION = "ecobridge.admin";
+  private static final int DEFAULT_HISTORY_LIMIT = 10;
+  private static final int MAX_HISTORY_LIMIT = 50;
+  private static final DateTimeFormatter HISTORY_TIME_FORMAT =
+      DateTimeFormatter.ofPattern("MM-dd HH:mm").withZone(ZoneId.systemDefault());
 
   @Override
   public boolean onCommand(
@@ -109,6 +124,10 @@ public class AdminCommand implements TabExecutor {
           String importResult = UltimateShopImporter.runImport(defaultLambda);
           sender.sendMessage(EcoBridge.getMiniMessage().deserialize(importResult));
           return true;
+
+        case "history":
+          showPlayerHistory(sender, args);
+          return true;
       }
     }
 
@@ -116,6 +135,69 @@ public class AdminCommand implements TabExecutor {
     return true;
   }
 
+  private void showPlayerHistory(CommandSender sender, String[] args) {
+    if (args.length < 2) {
+      sender.sendMessage(
+          EcoBridge.getMiniMessage().deserialize("<red>用法: /ecoadmin history <玩家> [条数]"));
+      return;
+    }
+    OfflinePlayer target = Bukkit.getOfflinePlayerIfCached(args[1]);
+    if (target == null) {
+      sender.sendMessage(
+          EcoBridge.getMiniMessage()
+              .deserialize("<red>未找到玩家: <name>", Placeholder.unparsed("name", args[1])));
+      return;
+    }
+    int limit = DEFAULT_HISTORY_LIMIT;
+    if (args.length >= 3) {
+      try {
+        limit = Math.clamp(Integer.parseInt(args[2]), 1, MAX_HISTORY_LIMIT);
+      } catch (NumberFormatException e) {
+        sender.sendMessage(EcoBridge.getMiniMessage().deserialize("<red>错误: 条数必须是整数。"));
+        return;
+      }
+    }
+    ExecutorService executor = DatabaseManager.getExecutor();
+    if (executor == null || !DatabaseManager.isConnected()) {
+      sender.sendMessage(EcoBridge.getMiniMessage().deserialize("<red>错误: 数据库未连接。"));
+      return;
+    }
+
+    UUID uuid = target.getUniqueId();
+    String name = args[1];
+    int finalLimit = limit;
+    executor.execute(
+        () -> {
+          List<PlayerSale> sales = TransactionDao.getPlayerRecentSales(uuid, finalLimit);
+          Bukkit.getScheduler()
+              .runTask(EcoBridge.getInstance(), () -> sendPlayerHistory(sender, name, sales));
+        });
+  }
+
+  private void sendPlayerHistory(CommandSender sender, String name, List<PlayerSale> sales) {
+    if (sales.isEmpty()) {
+      sender.sendMessage(
+          EcoBridge.getMiniMessage()
+              .deserialize("<gray><name> 暂无交易记录。", Placeholder.unparsed("name", name)));
+      return;
+    }
+    sender.sendMessage(
+        EcoBridge.getMiniMessage()
+            .deserialize(
+                "<gradient:aqua:blue><name> 的近期交易</gradient>", Placeholder.unparsed("name", name)));
+    for (PlayerSale sale : sales) {
+      String color = sale.amount() >= 0 ? "<aqua>" : "<gold>";
+      String prefix = sale.amount() >= 0 ? "+" : "";
+      sender.sendMessage(
+          EcoBridge.getMiniMessage()
+              .deserialize(
+                  "<gray>[<time>] <white><product> " + color + "<amt>",
+                  Placeholder.unparsed("time", HISTORY_TIME_FORMAT.format(Instant.ofEpochMilli(sale.timestamp()))),
+                  Placeholder.unparsed("product", sale.productId()),
+                  Placeholder.unparsed("amt", prefix + String.format("%.1f", sale.amount()))));
+    }
+  }
+
   private void sendHelp(CommandSender sender) {
     sender.sendMessage(
         EcoBridge.getMiniMessage()
@@ -125,6 +207,7 @@ public class AdminCommand implements TabExecutor {
                     + "<yellow>/ecoadmin setprice <id> <price> <gray>- 强制修正商品基准价\n"
                     + "<yellow>/ecoadmin reset <id> <gray>- 重置商品价格波动数据\n"
                     + "<yellow>/ecoadmin import <gray>- 从 UltimateShop 同步商店物品\n"
+                    + "<yellow>/ecoadmin history <player> [n] <gray>- 查询玩家近期交易流水\n"
                     + "<yellow>/ecoadmin shadow <gray>- 切换影子审计模式\n"
                     + "<yellow>/ecoadmin reload <gray>- 重载配置文件"));
   }
@@ -141,7 +224,7 @@ public class AdminCommand implements TabExecutor {
     }
 
     if (args.length == 1) {
-      return List.of("setprice", "reset", "shadow", "reload", "import").stream()
+      return List.of("setprice", "reset", "shadow", "reload", "import", "history").stream()
           .filter(s -> s.startsWith(args[0].toLowerCase()))
           .collect(Collectors.toList());
     }
@@ -14,6 +14,7 @@ class EconStatusTest {
     assertEquals(EconStatus.NULL_POINTER, EconStatus.from(1));
     assertEquals(EconStatus.INVALID_LENGTH, EconStatus.from(2));
     assertEquals(EconStatus.INVALID_VALUE, EconStatus.from(3));
+    assertEquals(EconStatus.DUPLICATE, EconStatus.from(4));
     assertEquals(EconStatus.NUMERIC_OVERFLOW, EconStatus.from(10));
     assertEquals(EconStatus.INTERNAL_ERROR, EconStatus.from(100));
     assertEquals(EconStatus.PANIC, EconStatus.from(101));
@@ -35,6 +36,15 @@ class EconStatusTest {
     assertFalse(EconStatus.INTERNAL_ERROR.isOk());
   }
 
+  @Test
+  void testDuplicateIsNotAnError() {
+    assertFalse(EconStatus.DUPLICATE.isOk());
+    assertFalse(EconStatus.DUPLICATE.isError());
+    assertFalse(EconStatus.OK.isError());
+    assertTrue(EconStatus.INVALID_VALUE.isError());
+    assertDoesNotThrow(() -> EconStatus.DUPLICATE.check("replayed append"));
+  }
+
   @Test
   void testCheckDoesNotThrowForOk() {
     assertDoesNotThrow(() -> EconStatus.OK.check("test context"));
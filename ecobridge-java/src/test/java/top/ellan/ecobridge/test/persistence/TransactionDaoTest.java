@@ -0,0 +1,92 @@
+package top.ellan.ecobridge.test.persistence;
+
+import static org.junit.jupiter.api.Assertions.*;
+
+import java.sql.Connection;
+import java.sql.DriverManager;
+import java.sql.PreparedStatement;
+import java.sql.SQLException;
+import java.sql.Statement;
+import java.util.List;
+import java.util.UUID;
+import org.junit.jupiter.api.AfterEach;
+import org.junit.jupiter.api.BeforeEach;
+import org.junit.jupiter.api.Test;
+import top.ellan.ecobridge.infrastructure.persistence.database.DatabaseManager;
+import top.ellan.ecobridge.infrastructure.persistence.database.TransactionDao;
+import top.ellan.ecobridge.infrastructure.persistence.database.TransactionDao.PlayerSale;
+
+/** Tests for the per-player sales history query, run against an in-memory H2 database. */
+class TransactionDaoTest {
+
+  private static final UUID ALICE = UUID.fromString("00000000-0000-0000-0000-00000000000a");
+  private static final UUID BOB = UUID.fromString("00000000-0000-0000-0000-00000000000b");
+
+  private Connection conn;
+
+  @BeforeEach
+  void setUp() throws SQLException {
+    conn = DriverManager.getConnection("jdbc:h2:mem:sales;MODE=MySQL;DB_CLOSE_DELAY=-1");
+    try (Statement stmt = conn.createStatement()) {
+      stmt.execute(
+          "CREATE TABLE ecobridge_sales (id BIGINT AUTO_INCREMENT PRIMARY KEY, "
+              + "player_uuid CHAR(36) NOT NULL, product_id VARCHAR(64) NOT NULL, "
+              + "amount DOUBLE NOT NULL, timestamp BIGINT NOT NULL)");
+    }
+    insert(ALICE, "diamond", 3.0, 1_000L);
+    insert(ALICE, "iron", -2.0, 3_000L);
+    insert(BOB, "gold", 5.0, 2_000L);
+    insert(ALICE, "emerald", 1.5, 2_000L);
+  }
+
+  @AfterEach
+  void tearDown() throws SQLException {
+    try (Statement stmt = conn.createStatement()) {
+      stmt.execute("DROP TABLE ecobridge_sales");
+    }
+    conn.close();
+  }
+
+  private void insert(UUID player, String productId, double amount, long ts) throws SQLException {
+    try (PreparedStatement pstmt =
+        conn.prepareStatement(
+            "INSERT INTO ecobridge_sales(player_uuid, product_id, amount, timestamp) VALUES(?,?,?,?)")) {
+      pstmt.setString(1, player.toString());
+      pstmt.setString(2, productId);
+      pstmt.setDouble(3, amount);
+      pstmt.setLong(4, ts);
+      pstmt.executeUpdate();
+    }
+  }
+
+  @Test
+  void testReturnsOnlyThePlayersSalesNewestFirst() throws SQLException {
+    List<PlayerSale> sales = TransactionDao.queryPlayerRecentSales(conn, ALICE, 10);
+    assertEquals(
+        List.of(
+            new PlayerSale(3_000L, "iron", -2.0),
+            new PlayerSale(2_000L, "emerald", 1.5),
+            new PlayerSale(1_000L, "diamond", 3.0)),
+        sales);
+  }
+
+  @Test
+  void testLimitCapsTheResult() throws SQLException {
+    List<PlayerSale> sales = TransactionDao.queryPlayerRecentSales(conn, ALICE, 1);
+    assertEquals(List.of(new PlayerSale(3_000L, "iron", -2.0)), sales);
+  }
+
+  @Test
+  void testUnknownPlayerAndNonPositiveLimitYieldEmpty() throws SQLException {
+    assertTrue(TransactionDao.queryPlayerRecentSales(conn, UUID.randomUUID(), 10).isEmpty());
+    assertTrue(TransactionDao.queryPlayerRecentSales(conn, ALICE, 0).isEmpty());
+  }
+
+  @Test
+  void testPlayerHistoryIndexIsIdempotent() throws SQLException {
+    try (Statement stmt = conn.createStatement()) {
+      stmt.execute(DatabaseManager.SQL_PLAYER_HISTORY_INDEX);
+      assertDoesNotThrow(() -> stmt.execute(DatabaseManager.SQL_PLAYER_HISTORY_INDEX));
+    }
+  }
+}
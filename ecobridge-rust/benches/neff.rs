@@ -0,0 +1,43 @@
+//! N_eff 求和路径基准：AVX2 路径与标量路径在同一份历史上的耗时对比。
+//!
+//! `cargo bench --bench neff` 测量默认构建 (fast-exp 寄存器内 exp)；
+//! `cargo bench --bench neff --no-default-features --features parallel` 测量逐通道标量 exp 的 AVX2 路径。
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ecobridge_rust::economy::summation::{calculate_volume_in_memory, set_parallel_threshold, set_simd_enabled};
+use ecobridge_rust::models::HistoryRecord;
+
+const NOW: i64 = 1_750_000_000_000;
+const TAU_DAYS: f64 = 7.0;
+
+/// 过去 `TAU_DAYS` 天内均匀分布的成交，金额正负交替
+fn history(len: usize) -> Vec<HistoryRecord> {
+    let span = (TAU_DAYS * 86_400_000.0) as i64;
+    (0..len)
+        .map(|i| HistoryRecord {
+            timestamp: NOW - span + span * i as i64 / len as i64,
+            amount_micros: if i % 2 == 0 { 1_500_000 } else { -700_000 },
+        })
+        .collect()
+}
+
+fn bench_neff(c: &mut Criterion) {
+    // 只比较单线程内核，标量路径不交给 rayon
+    set_parallel_threshold(usize::MAX);
+
+    let mut group = c.benchmark_group("neff");
+    for len in [1_000usize, 100_000] {
+        let records = history(len);
+        for (name, simd) in [("simd", true), ("scalar", false)] {
+            set_simd_enabled(simd);
+            group.bench_with_input(BenchmarkId::new(name, len), &records, |b, records| {
+                b.iter(|| calculate_volume_in_memory(black_box(records), NOW, TAU_DAYS))
+            });
+        }
+    }
+    set_simd_enabled(true);
+    group.finish();
+}
+
+criterion_group!(benches, bench_neff);
+criterion_main!(benches);
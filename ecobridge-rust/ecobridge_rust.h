@@ -8,6 +8,77 @@
 #include <stdint.h>
 #include <stdbool.h>
 
+/*
+ 定价 FFI 的 panic 兜底模式：不写入输出 (默认，与旧版一致，仅返回 Panic)
+ */
+#define PRICING_FALLBACK_NONE 0
+
+/*
+ 定价 FFI 的 panic 兜底模式：向输出写入负数哨兵，便于 Java 侧识别并记录
+ */
+#define PRICING_FALLBACK_SENTINEL 1
+
+/*
+ 定价 FFI 的 panic 兜底模式：向输出写入 base
+ */
+#define PRICING_FALLBACK_BASE 2
+
+/*
+ 哨兵模式下写入的价格 (任何合法价格均为正)
+ */
+#define PRICING_PANIC_SENTINEL -1.0
+
+/*
+ 默认微分低通滤波系数 (越小滤波越重)
+ */
+#define DEFAULT_DERIVATIVE_FILTER_ALPHA 0.3
+
+/*
+ 默认周末位掩码：周六 + 周日 (bit0 = 周一 … bit6 = 周日)
+ */
+#define DEFAULT_WEEKEND_MASK 96
+
+/*
+ 周末掩码的有效位 (一周七天)
+ */
+#define WEEKEND_MASK_BITS 127
+
+/*
+ 默认新手优待窗口 (小时)：优待在此期间线性衰减至 0
+ */
+#define DEFAULT_NEWBIE_PROTECTION_HOURS 100.0
+
+/*
+ 默认节庆乘数 (newbie_mask bit1 置位时作用于季节因子)
+ */
+#define DEFAULT_FESTIVAL_MULTIPLIER 1.15
+
+/*
+ 默认环境因子下限
+ */
+#define DEFAULT_EPSILON_MIN 0.1
+
+/*
+ 默认环境因子上限
+ */
+#define DEFAULT_EPSILON_MAX 10.0
+
+/*
+ 默认速率预警比例：傀儡因子超过拦截阈值的 70% 即标记高风险
+ */
+#define DEFAULT_WARNING_VELOCITY_RATIO 0.7
+
+/*
+ 频率惩罚系数默认值 (velocity_penalty_coef 为 0 时生效)
+ */
+#define DEFAULT_VELOCITY_PENALTY_COEF 0.05
+
+#define TREND_FALLING -1
+
+#define TREND_STABLE 0
+
+#define TREND_RISING 1
+
 #define DEFAULT_INTEGRATION_LIMIT 30.0
 
 #define MAX_SAFE_DT 1.0
@@ -24,7 +95,10 @@
 
 #define BACK_CALC_GAIN 0.2
 
-#define DERIVATIVE_FILTER_ALPHA 0.3
+/*
+ 设定值突变时积分项的保留比例 (其余部分立即释放，防止旧积分带来的超调)
+ */
+#define SETPOINT_CHANGE_INTEGRAL_RETAIN 0.2
 
 #define PANIC_THRESHOLD 50.0
 
@@ -32,6 +106,99 @@
 
 #define HEAT_SENSITIVITY 0.5
 
+#define DEFAULT_SCHEDULE_PIVOT 0.05
+
+#define DEFAULT_SCHEDULE_STEEPNESS 20.0
+
+#define PID_PARAM_KP 1
+
+#define PID_PARAM_KI 2
+
+#define PID_PARAM_KD 3
+
+#define PID_PARAM_LAMBDA 4
+
+#define PID_PARAM_INTEGRATION_LIMIT 5
+
+#define PID_PARAM_MAX_DELTA 6
+
+#define PID_PARAM_DEADBAND 7
+
+#define PID_PARAM_DERIVATIVE_ALPHA 8
+
+/*
+ 热度 / 饱和度 EWMA 默认平滑系数
+ */
+#define DEFAULT_HEAT_SMOOTHING_ALPHA 0.2
+
+/*
+ 抖动幅度上限 (±50%)
+ */
+#define MAX_JITTER_PCT 0.5
+
+/*
+ 默认通缩下限
+ */
+#define DEFAULT_INFLATION_MIN -0.15
+
+/*
+ 默认通胀上限
+ */
+#define DEFAULT_INFLATION_MAX 0.45
+
+/*
+ 默认归零阈值 (1.0 标准单位)
+ */
+#define DEFAULT_DECAY_RESET_THRESHOLD 1.0
+
+/*
+ 默认恢复窗口 (15 分钟)
+ */
+#define DEFAULT_RECOVERY_WINDOW_MS 900000.0
+
+/*
+ 默认每日衰减任务频率
+ */
+#define DEFAULT_CYCLES_PER_DAY 48.0
+
+/*
+ 默认缓存有效期 (毫秒)
+ */
+#define DEFAULT_MARKET_STATE_TTL_MS 5000
+
+/*
+ 默认绝对硬底线 (0.01 货币单位)
+ */
+#define DEFAULT_MIN_PRICE 0.01
+
+/*
+ 指数硬限幅的默认对称边界 [-100, 100]
+ */
+#define DEFAULT_EXPONENT_CLAMP 100.0
+
+/*
+ tanh 软限幅的默认尺度 (指数最终被压缩到 ±10 以内)
+ */
+#define DEFAULT_TANH_SCALE 10.0
+
+/*
+ tanh 尺度上限：exp(±300) 仍远离 f64 溢出
+ */
+#define MAX_TANH_SCALE 300.0
+
+/*
+ `steps_to_floor` 的最大模拟步数
+ */
+#define MAX_FLOOR_STEPS 10000
+
+#define DEFAULT_PARALLEL_THRESHOLD 750
+
+#define DECAY_MODEL_EXPONENTIAL 0
+
+#define DECAY_MODEL_LINEAR 1
+
+#define DECAY_MODEL_POWER_LAW 2
+
 #define CODE_NORMAL 0
 
 #define CODE_WARNING_HIGH_RISK 1
@@ -46,6 +213,81 @@
 
 #define CODE_BLOCK_QUANTITY_LIMIT 6
 
+#define CODE_ERROR_NO_GLOBAL_CONFIG -1
+
+#define CODE_ERROR_INVALID_CALL -2
+
+#define CODE_ERROR_NON_FINITE_TAX -3
+
+/*
+ 默认税收封顶比例；配置值不在 (0, 1] 内时回退至此 (兼容零初始化的旧配置)
+ */
+#define DEFAULT_MAX_TAX_RATIO 0.8
+
+/*
+ 每分对应的 Micros 数 (1 分 = 0.01 标准单位)
+ */
+#define MICROS_PER_CENT 10000
+
+/*
+ 建议的高风险分数线：Java 侧据此将 warning_code 提升为 CODE_WARNING_HIGH_RISK
+ */
+#define STRUCTURING_HIGH_RISK_SCORE 0.5
+
+/*
+ Default number of recent dedup keys remembered.
+ */
+#define DEFAULT_DEDUP_CAPACITY 65536
+
+/*
+ 对连续调用的 `market_heat` / `eco_saturation` 做 EWMA 平滑，抑制尖峰带来的 epsilon 抖动
+ */
+typedef struct HeatSmoother HeatSmoother;
+
+/*
+ Forward-only cursor over the global hot history.
+
+ Holds only an absolute position, never a copy of the data, and takes the
+ read lock per step so writers are not blocked between calls. Records pruned
+ from the front while the cursor is open are skipped.
+ */
+typedef struct HistoryCursor HistoryCursor;
+
+/*
+ 单个市场的定价管线状态
+ */
+typedef struct MarketState MarketState;
+
+/*
+ 单条历史交易快照 (16 bytes)
+ */
+typedef struct {
+  long long timestamp;
+  long long amount_micros;
+} HistoryRecord;
+
+/*
+ 单日成交汇总 (32 bytes)，按 UTC 自然日聚合
+ */
+typedef struct {
+  long long day;
+  long long net_micros;
+  long long gross_micros;
+  long long count;
+} DailyStat;
+
+/*
+ 运行健康报告 (40 bytes)：监控周期一次 FFI 调用取回全部计数器
+ */
+typedef struct {
+  unsigned long long total_logs;
+  unsigned long long dropped_logs;
+  unsigned long long panic_count;
+  unsigned long long history_len;
+  int hydration_complete;
+  int _padding;
+} HealthReport;
+
 /*
  交易定价演算上下文 (64 bytes)
  */
@@ -62,7 +304,7 @@ typedef struct {
 } TradeContext;
 
 /*
- 市场动态定价配置 (72 bytes)
+ 市场动态定价配置 (128 bytes)
  */
 typedef struct {
   double base_lambda;
@@ -74,8 +316,38 @@ typedef struct {
   double weekend_weight;
   double newbie_weight;
   double inflation_weight;
+  int weekend_mask;
+  int _padding;
+  double heat_weight;
+  double saturation_weight;
+  double newbie_hours;
+  double festival_multiplier;
+  double epsilon_min;
+  double epsilon_max;
 } MarketConfig;
 
+/*
+ 单笔定价的逐步中间量 (56 bytes)，用于排查异常报价
+ */
+typedef struct {
+  double epsilon;
+  double n_eff;
+  double adj_lambda;
+  double total_n;
+  double raw_exponent;
+  double clamped_exponent;
+  double final_price;
+} PriceTrace;
+
+/*
+ 宏观指标快照 (24 bytes)：一次 FFI 调用同时返回通胀、稳定性与热度衰减
+ */
+typedef struct {
+  double inflation;
+  double stability;
+  double decay_amount;
+} MacroSnapshot;
+
 /*
  交易演算最终结果 (16 bytes)
  */
@@ -104,7 +376,7 @@ typedef struct {
 } TransferContext;
 
 /*
- 审计监管与计税配置 (96 bytes)
+ 审计监管与计税配置 (120 bytes)
  */
 typedef struct {
   double base_tax_rate;
@@ -119,10 +391,34 @@ typedef struct {
   double newbie_hours;
   double veteran_hours;
   double velocity_threshold;
+  double max_tax_ratio;
+  double velocity_penalty_coef;
+  double warning_velocity_ratio;
 } RegulatorConfig;
 
 /*
- 工业级 PID 控制器状态 (72 bytes)
+ 整分定点金额 (8 bytes)：1 = 0.01 标准单位，用于要求精确货币运算的定点计税路径
+ */
+typedef struct {
+  long long cents;
+} PriceI64;
+
+/*
+ 税费构成明细 (48 bytes)：单位为标准货币单位 (非 Micros)，用于向玩家展示税单
+
+ 各分量为累加口径：`base + inflation + velocity + luxury + wealth_gap` 经封顶后即 `final_tax`。
+ */
+typedef struct {
+  double base_tax;
+  double inflation_component;
+  double velocity_penalty;
+  double luxury_tax;
+  double wealth_gap_tax;
+  double final_tax;
+} TaxBreakdown;
+
+/*
+ 工业级 PID 控制器状态 (136 bytes)
  */
 typedef struct {
   double kp;
@@ -135,89 +431,1235 @@ typedef struct {
   double integration_limit;
   int is_saturated;
   int _padding;
+  double prev_target;
+  double setpoint_tolerance;
+  double schedule_pivot;
+  double schedule_steepness;
+  double prev_output;
+  double max_delta_per_step;
+  double deadband;
+  double derivative_alpha;
 } PidState;
 
+/*
+ 玩家间单笔应付款 (24 bytes)，用于批量结算轧差
+ */
+typedef struct {
+  long long from_id;
+  long long to_id;
+  long long amount_micros;
+} PairTransfer;
+
+
+
 int ecobridge_abi_version(void);
 
 const char *ecobridge_version(void);
 
+/*
+ 编译期确定的构建特性，逗号分隔 (如 "x86_64,avx2-dispatch,parallel,fast-exp")，
+ 供插件在加载时打印，便于排查不同构建间的性能差异。返回静态字符串，调用方不得释放。
+ */
+const char *ecobridge_build_features(void);
+
+/*
+ 初始化线程池并安装原生日志器，记录启动时刻供开服软启动使用
+ */
 int ecobridge_init_threading(int num_threads);
 
-int ecobridge_init_db(const char *path_ptr);
+/*
+ # Safety
+ `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_append_trade_to_memory(long long ts, double amount, const char *market_key_ptr);
 
-int ecobridge_shutdown_db(void);
+/*
+ 按 JSON 元数据中的 `category` 字段归档成交 (如 `{"category":"ores"}`)。
+ 元数据无法解析或缺少分类时记入 "uncategorized"，成交量不会丢失；写入成功即返回 `Ok`，
+ 是否回退到 "uncategorized" 通过 `out_fallback` 回报 (1 = 已回退，0 = 使用元数据分类)。
 
-int ecobridge_log_to_duckdb(long long ts,
-                            const char *uuid_ptr,
-                            long long trade_amount_micros,
-                            long long balance_micros,
-                            const char *meta_ptr);
+ # Safety
+ - `meta_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_fallback` 为空 (不关心回退) 或指向可写的 `c_int`
+ */
+int ecobridge_append_trade_with_meta(long long ts,
+                                     double amount,
+                                     const char *meta_ptr,
+                                     int *out_fallback);
 
-int inject_remote_trade(long long amount_micros);
+/*
+ 幂等追加：`dedup_key` 在近期窗口内出现过时返回 `Duplicate` 且不写入
 
-int ecobridge_query_neff_vectorized(long long current_ts, double tau, double *out_result);
+ # Safety
+ `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_append_trade_idempotent(long long ts,
+                                      double amount,
+                                      const char *market_key_ptr,
+                                      unsigned long long dedup_key);
 
-int ecobridge_compute_batch_prices(uint64_t count,
-                                   double neff,
-                                   const TradeContext *ctx_ptr,
-                                   const MarketConfig *cfg_ptr,
-                                   const double *hist_avgs_ptr,
-                                   const double *lambdas_ptr,
-                                   double *results_ptr);
+/*
+ 设置去重窗口容量 (默认 65536)；0 视为非法
+ */
+int ecobridge_set_dedup_capacity(uintptr_t capacity);
 
-int ecobridge_compute_price_final(double base,
-                                  double n_eff,
-                                  double lambda,
-                                  double epsilon,
-                                  double *out_result);
+/*
+ # Safety
+ `records_ptr` 为空或指向至少 `count` 个连续可读的 `HistoryRecord`。
+ */
+int ecobridge_bulk_load_history(const HistoryRecord *records_ptr, uint64_t count);
 
-int ecobridge_compute_tier_price(double base, double qty, int is_sell, double *out_result);
+/*
+ 从种子文件 (每行 `timestamp_ms,amount` 的 CSV) 预热全局热存储，供无历史的新服冷启动。
+ 返回载入条数；路径非法返回 -InvalidValue，文件无法读取返回 -InternalError。
 
-int ecobridge_compute_price_humane(double base,
-                                   double n_eff,
-                                   double trade_amount,
-                                   double lambda,
-                                   double epsilon,
-                                   double *out_result);
+ # Safety
+ `path_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+long long ecobridge_seed_history(const char *path_ptr);
 
-int ecobridge_compute_price_bounded(double base,
-                                    double n_eff,
-                                    double amt,
-                                    double lambda,
-                                    double eps,
-                                    double hist_avg,
-                                    double *out_result);
+/*
+ 运行时重新同步全局热存储：以 Java 侧从 H2 重新查询的记录整体替换内存历史。
+ 返回保留的记录数，参数非法时返回负的 EconStatus。
 
-int ecobridge_calc_inflation(double current_heat, double m1, double *out_result);
+ # Safety
+ `records_ptr` 为空或指向至少 `count` 个连续可读的 `HistoryRecord`。
+ */
+long long ecobridge_rehydrate_history(const HistoryRecord *records_ptr,
+                                      uint64_t count);
 
-int ecobridge_calc_stability(long long last_ts, long long curr_ts, double *out_result);
+/*
+ 后台重建全局热存储，避免启动时阻塞：记录在返回前已复制，调用方可立即释放缓冲区。
+ 已有后台重建在进行或线程无法启动时返回 InternalError (此时应改用同步的 ecobridge_rehydrate_history)。
+ 重建期间 ecobridge_is_hydration_complete 返回 0，Java 侧应暂时走 H2 查询路径。
 
-int ecobridge_calc_decay(double heat, double rate, double *out_result);
+ # Safety
+ `records_ptr` 为空或指向至少 `count` 个连续可读的 `HistoryRecord`。
+ */
+int ecobridge_rehydrate_history_async(const HistoryRecord *records_ptr,
+                                      uint64_t count);
 
-int ecobridge_get_health_stats(uint64_t *out_total, uint64_t *out_dropped);
+/*
+ 后台重建是否已完成 (1 = 完成或未进行，0 = 加载中)
+ */
+int ecobridge_is_hydration_complete(void);
 
-int ecobridge_calculate_epsilon(const TradeContext *ctx_ptr,
-                                const MarketConfig *cfg_ptr,
-                                double *out_result);
+/*
+ 数据保留：清除内存热存储中早于 `cutoff_ts` 的记录，返回全局存储中被移除的条数
+ */
+long long ecobridge_purge_before(long long cutoff_ts);
 
-int ecobridge_compute_transfer_check(TransferResult *out_result,
-                                     const TransferContext *ctx_ptr,
-                                     const RegulatorConfig *cfg_ptr);
+/*
+ 打开全局热存储的游标 (最近 `days` 天)。`days < 0` 时返回 NULL。
+ 游标不持有锁，但必须调用 `ecobridge_history_cursor_close` 释放。
+ */
+HistoryCursor *ecobridge_history_cursor_open(int days);
 
-int ecobridge_get_dynamic_limit(long long play_time_secs,
-                                double base,
-                                double rate,
-                                double max,
-                                double *out_result);
+/*
+ 读取下一条记录：1 = 已写入 `out_record`，0 = 已结束，-1 = 错误
 
-int ecobridge_compute_pid_adjustment(PidState *pid_ptr,
-                                     double target,
-                                     double current,
-                                     double dt,
-                                     double inflation,
-                                     double market_heat,
-                                     double *out_result);
+ # Safety
+ - `cursor` 为空或为 `ecobridge_history_cursor_open` 返回且尚未释放的句柄
+ - `out_record` 为空或指向可写的 `HistoryRecord`
+ */
+int ecobridge_history_cursor_next(HistoryCursor *cursor, HistoryRecord *out_record);
 
-int ecobridge_reset_pid_state(PidState *pid_ptr);
+/*
+ # Safety
+ `cursor` 为空或为 `ecobridge_history_cursor_open` 返回且尚未释放的句柄，调用后不得再使用。
+ */
+void ecobridge_history_cursor_close(HistoryCursor *cursor);
+
+/*
+ 最近 `days` 天的逐日成交汇总 (UTC，由旧到新)。`out_stats` 须可容纳 `capacity` 个元素；
+ 天数超过容量时只写入最近的 `capacity` 天。返回写入条数，参数非法时返回负的 EconStatus。
+
+ # Safety
+ `out_stats` 为空或指向至少 `capacity` 个连续可写的 `DailyStat`。
+ */
+long long ecobridge_query_daily_rollup(int days,
+                                       DailyStat *out_stats,
+                                       uintptr_t capacity);
+
+/*
+ 一次性查询所有市场的 N_eff (按键排序，不含全局聚合键)。
+ 第 i 个市场的键以 UTF-8 字节 (不含 NUL) 依次拼接写入 `out_keys`，长度写入 `out_key_lens[i]`，
+ N_eff 写入 `out_volumes[i]`；条目数或键缓冲区不足时截断到已完整写入的前缀。
+ `out_total` 接收市场总数以便调用方扩容。返回写入条数，参数非法时返回负的 EconStatus。
+
+ # Safety
+ - `out_volumes` 为空或指向至少 `capacity` 个连续可写的 `c_double`
+ - `out_key_lens` 为空或指向至少 `capacity` 个连续可写的 `usize`
+ - `out_keys` 为空或指向至少 `keys_buf_len` 字节的可写缓冲区
+ - `out_total` 为空或指向可写的 `c_ulonglong`
+ */
+long long ecobridge_query_neff_all_markets(long long current_ts,
+                                           double tau,
+                                           double *out_volumes,
+                                           uintptr_t *out_key_lens,
+                                           uintptr_t capacity,
+                                           char *out_keys,
+                                           uintptr_t keys_buf_len,
+                                           unsigned long long *out_total);
+
+/*
+ 最近 `days` 天按本地小时 (应用 `timezone_offset_sec`) 聚合的成交量绝对值，
+ 写入 `out_profile[0..24]` (下标 0 为本地零点)
+
+ # Safety
+ `out_profile` 为空或指向至少 `24` 个连续可写的 `c_double`。
+ */
+int ecobridge_query_hourly_profile(int days, int timezone_offset_sec, double *out_profile);
+
+/*
+ 单次扫描计算多个 tau 下的 N_eff，`out_volumes[i]` 对应 `taus[i]`；
+ `market_key_ptr` 为空时查询全局聚合，非法 tau 对应结果为 0
+
+ # Safety
+ - `taus_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_volumes` 为空或指向至少 `count` 个连续可写的 `c_double`
+ */
+int ecobridge_query_neff_multi_tau(long long current_ts,
+                                   const double *taus_ptr,
+                                   uintptr_t count,
+                                   const char *market_key_ptr,
+                                   double *out_volumes);
+
+/*
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_query_neff_in_memory(long long current_ts,
+                                   double tau,
+                                   const char *market_key_ptr,
+                                   double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_query_neff_global_in_memory(long long current_ts, double tau, double *out_result);
+
+/*
+ # Safety
+ - `out_total` 为空或指向可写的 `u64`
+ - `out_dropped` 为空或指向可写的 `u64`
+ */
+int ecobridge_get_health_stats(uint64_t *out_total, uint64_t *out_dropped);
+
+/*
+ 一次性汇总全部健康计数器 (各独立 getter 仍保留)
+
+ # Safety
+ `out` 为空或指向可写的 `HealthReport`。
+ */
+int ecobridge_get_health_report(HealthReport *out);
+
+/*
+ 设置可接受的市场键最大字节数 (默认 4096)；超长键会被拒绝并计入 dropped
+ */
+int ecobridge_set_max_key_len(uintptr_t max_len);
+
+/*
+ 最近一笔已持久化成交写入热存储时的墙钟时间 (ms)，尚无成交时返回 0；
+ 配合 total_logs 可发现"仍在接收但长时间无新落库"的卡死写入方
+ */
+long long ecobridge_get_last_flush_ts(void);
+
+/*
+ 热存储锁因 panic 中毒后被恢复的次数 (非 0 说明曾有写入方 panic)
+ */
+unsigned long long ecobridge_get_lock_recovered_count(void);
+
+unsigned long long ecobridge_get_panic_count(void);
+
+void ecobridge_reset_panic_count(void);
+
+/*
+ 设置 "系统不稳定" 告警阈值；0 被拒绝
+ */
+int ecobridge_set_panic_threshold(unsigned long long threshold);
+
+/*
+ 注册 panic 回调 (每次拦截到 panic 时调用)；传入 NULL 取消注册
+ */
+void ecobridge_set_panic_callback(void (*callback)(uint64_t count, const char *msg));
+
+/*
+ 注册日志回调 `(级别 1..=5, UTF-8 消息)`，替代默认的 stderr 输出；传入 NULL 恢复 stderr
+ */
+void ecobridge_set_log_callback(void (*callback)(int level, const char *msg));
+
+/*
+ 设置原生日志的最大级别 (0 = 关闭，1 = Error … 5 = Trace，默认 3 = Info)
+ */
+int ecobridge_set_log_level(int level);
+
+/*
+ 设置定价 FFI 的 panic 兜底模式 (0 = 不写入输出，默认；1 = 哨兵 -1.0；2 = base)
+ */
+int ecobridge_set_pricing_fallback_mode(int mode);
+
+int ecobridge_set_future_tolerance_ms(long long tolerance);
+
+/*
+ 设置 N_eff 读穿缓存 TTL (ms，默认 0 即关闭)；负值视为非法。
+ 缓存不随成交追加失效，开启后同一 TTL 内的连续卖出会读到陈旧的 N_eff。
+ */
+int ecobridge_set_neff_cache_ttl_ms(long long ttl_ms);
+
+/*
+ 设置标量求和启用 rayon 并行的最小记录数 (默认 750)；0 视为非法
+ */
+int ecobridge_set_parallel_threshold(uintptr_t threshold);
+
+/*
+ 校验模式：非 0 时 N_eff 改走逐条标量实现 (用于与 SIMD 路径对账)，0 恢复默认
+ */
+int ecobridge_set_neff_reference_mode(int enabled);
+
+/*
+ 诊断开关：0 时 N_eff 累计强制走标量路径 (排查 SIMD 偏差)，非 0 恢复默认的 AVX2 路径
+ */
+int ecobridge_set_simd_enabled(int enabled);
+
+/*
+ tau → 半衰期 (天)，供配置界面显示 "交易量每 N 天减半"
+
+ # Safety
+ `out_days` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_tau_to_halflife_days(double tau, double *out_days);
+
+/*
+ 半衰期 (天) → tau
+
+ # Safety
+ `out_tau` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_halflife_to_tau(double halflife_days, double *out_tau);
+
+int inject_remote_trade(long long amount_micros);
+
+/*
+ # Safety
+ `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int inject_remote_trade_for_key(const char *market_key_ptr, long long amount_micros);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_longlong`。
+ */
+int ecobridge_money_to_micros(double value, long long *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_micros_to_money(long long value_micros, double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_volatility_from_stability(double stability, double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_velocity_decay(double velocity,
+                                     long long delta_ms,
+                                     double half_life_ms,
+                                     double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_fallback_tax(double amount, double *out_result);
+
+/*
+ # Safety
+ - `out_tax` 为空或指向可写的 `c_double`
+ - `out_net` 为空或指向可写的 `c_double`
+ */
+int ecobridge_compute_settlement(double amount,
+                                 double suggested_tax,
+                                 int bypass_tax,
+                                 double *out_tax,
+                                 double *out_net);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_query_neff_vectorized(long long current_ts, double tau, double *out_result);
+
+/*
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_query_neff_for_key(long long current_ts,
+                                 double tau,
+                                 const char *market_key_ptr,
+                                 double *out_result);
+
+/*
+ 按方向拆分的 N_eff (买入量、卖出量，均为非负)；`market_key_ptr` 为空时查询全局聚合。
+ 不消费远程流量累加器。
+
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_buy` 为空或指向可写的 `c_double`
+ - `out_sell` 为空或指向可写的 `c_double`
+ */
+int ecobridge_query_neff_split(long long current_ts,
+                               double tau,
+                               const char *market_key_ptr,
+                               double *out_buy,
+                               double *out_sell);
+
+/*
+ 按指定衰减模型查询 N_eff：`model` 取 0 = 指数 (默认)、1 = 线性、2 = 幂律 (使用 `exponent`)；
+ `market_key_ptr` 为空时查询全局聚合。不经过 N_eff 缓存，也不消费远程流量累加器。
+
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_query_neff_model(long long current_ts,
+                               double tau,
+                               int model,
+                               double exponent,
+                               const char *market_key_ptr,
+                               double *out_result);
+
+/*
+ # Safety
+ - `ctx_ptr` 为空或指向至少 `count` 个连续可读的 `TradeContext`
+ - `cfg_ptr` 为空或指向至少 `count` 个连续可读的 `MarketConfig`
+ - `hist_avgs_ptr` 为空或指向至少 `count` 个连续可读的 `f64`
+ - `lambdas_ptr` 为空或指向至少 `count` 个连续可读的 `f64`
+ - `results_ptr` 为空或指向至少 `count` 个连续可写的 `f64`
+ */
+int ecobridge_compute_batch_prices(uint64_t count,
+                                   double neff,
+                                   const TradeContext *ctx_ptr,
+                                   const MarketConfig *cfg_ptr,
+                                   const double *hist_avgs_ptr,
+                                   const double *lambdas_ptr,
+                                   double *results_ptr);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_price_final(double base,
+                                  double n_eff,
+                                  double lambda,
+                                  double epsilon,
+                                  double *out_result);
+
+/*
+ 砸盘深度：每步卖出 `step_amount` 件，需多少步成交价触及有界定价底价；
+ 已在底价返回 0，无法在步数上限内触底返回 -1 (结果写入 `out_steps`)
+
+ # Safety
+ `out_steps` 为空或指向可写的 `c_longlong`。
+ */
+int ecobridge_steps_to_floor(double base,
+                             double n_eff,
+                             double lambda,
+                             double epsilon,
+                             double hist_avg,
+                             double step_amount,
+                             long long *out_steps);
+
+/*
+ 做市双边报价：以参考价为中间价，买入价/卖出价各偏离 `spread_pct / 2`，均不低于硬底线；
+ `spread_pct` 须位于 [0, 1)
+
+ # Safety
+ - `out_buy` 为空或指向可写的 `c_double`
+ - `out_sell` 为空或指向可写的 `c_double`
+ */
+int ecobridge_compute_spread(double base,
+                             double n_eff,
+                             double lambda,
+                             double epsilon,
+                             double spread_pct,
+                             double *out_buy,
+                             double *out_sell);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_tier_price(double base, double qty, int is_sell, double *out_result);
+
+/*
+ 任意档数的阶梯定价：`breakpoints` 为 `breakpoint_count` 个严格递增的累计件数，
+ `rates` 须恰有 `breakpoint_count + 1` 项 (否则返回 `InvalidLength`)；其余校验失败返回 `InvalidValue`。
+
+ # Safety
+ - `breakpoints_ptr` 为空或指向至少 `breakpoint_count` 个连续可读的 `c_double`
+ - `rates_ptr` 为空或指向至少 `rate_count` 个连续可读的 `c_double`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_compute_tier_price_table(double base,
+                                       double qty,
+                                       int is_sell,
+                                       const double *breakpoints_ptr,
+                                       uintptr_t breakpoint_count,
+                                       const double *rates_ptr,
+                                       uintptr_t rate_count,
+                                       double *out_result);
+
+/*
+ 配置大宗买入的阶梯溢价 (第二档 501-2000 件、第三档 2000 件以上)；默认 1.0 即不溢价。
+ 要求 1.0 <= tier2 <= tier3。
+ */
+int ecobridge_set_buy_tier_multipliers(double tier2,
+                                       double tier3);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_price_humane(double base,
+                                   double n_eff,
+                                   double trade_amount,
+                                   double lambda,
+                                   double epsilon,
+                                   double *out_result);
+
+/*
+ 可调限幅的行为定价核心：`exponent_clamp` 为指数硬限幅的对称边界，
+ `tanh_scale` 为软限幅尺度 (默认 100 / 10，尺度上限 300)
+
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_price_core_ex(double base,
+                                    double n_eff,
+                                    double trade_amount,
+                                    double lambda,
+                                    double epsilon,
+                                    double exponent_clamp,
+                                    double tanh_scale,
+                                    double *out_result);
+
+/*
+ 设置全局绝对硬底线 (默认 0.01)；`min` 必须为正的有限值
+ */
+int ecobridge_set_min_price(double min);
+
+/*
+ 预测连续交易的价格路径，`out_prices` 须至少容纳 `count` 个元素
+
+ # Safety
+ - `steps_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ - `out_prices` 为空或指向至少 `count` 个连续可写的 `c_double`
+ */
+int ecobridge_predict_price_path(double base,
+                                 double n_eff,
+                                 double lambda,
+                                 double epsilon,
+                                 const double *steps_ptr,
+                                 uintptr_t count,
+                                 double *out_prices);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_price_bounded(double base,
+                                    double n_eff,
+                                    double amt,
+                                    double lambda,
+                                    double eps,
+                                    double hist_avg,
+                                    double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_price_banded(double base,
+                                   double n_eff,
+                                   double amt,
+                                   double lambda,
+                                   double eps,
+                                   double hist_avg,
+                                   double floor_frac,
+                                   double ceil_mult,
+                                   double *out_result);
+
+/*
+ 大额抛售冷却：对 `category` 记录本次大额卖出，冷却期内的连续抛售逐次追加折扣
+
+ # Safety
+ - `category_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_apply_dump_cooldown(double price,
+                                  const char *category_ptr,
+                                  long long ts,
+                                  long long cooldown_ms,
+                                  double *out_result);
+
+/*
+ 诊断：按默认限幅走一遍行为定价核心并写出每一步中间量；`out.final_price` 与
+ ecobridge_compute_price_humane 的结果一致
+
+ # Safety
+ `out` 为空或指向可写的 `PriceTrace`。
+ */
+int ecobridge_trace_price(double base,
+                          double n_eff,
+                          double trade_amount,
+                          double lambda,
+                          double epsilon,
+                          PriceTrace *out);
+
+/*
+ 开服软启动：以 ecobridge_init_threading 记录的启动时刻为起点，`ramp_ms` 内将动态价格
+ 向 `base` 线性混合 (启动时完全为基准价，满 `ramp_ms` 后完全为动态价)。未初始化时原样返回。
+
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_apply_soft_start(double price,
+                               double base,
+                               long long current_ts,
+                               long long ramp_ms,
+                               double *out_result);
+
+/*
+ 线性定价模式：clamp(base × (1 − λ × (n_eff + amt)), floor, ceil)；
+ 要求 0 <= floor <= ceil，floor 实际不低于全局硬底线
+
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_price_linear(double base,
+                                   double n_eff,
+                                   double amt,
+                                   double lambda,
+                                   double floor,
+                                   double ceil,
+                                   double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_player_sell_price(double base,
+                                        double epsilon,
+                                        double lambda,
+                                        double delta,
+                                        double tau,
+                                        double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_logistic_decay(double sold_count,
+                                     double days_ago,
+                                     double delta,
+                                     double tau,
+                                     double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_compute_system_bid(double base, double hist_avg, double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_calc_inflation(double current_heat, double m1, double *out_result);
+
+/*
+ 可配置钳位区间的通胀率；区间须满足 `min_rate < max_rate`，否则返回 InvalidValue
+
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_calc_inflation_ex(double current_heat,
+                                double m1,
+                                double min_rate,
+                                double max_rate,
+                                double *out_result);
+
+/*
+ 推荐回收量：使通胀率回落到 `target_inflation` 需移除的流通热度 (>= 0)
+
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_recommended_sink(double current_heat,
+                               double m1,
+                               double target_inflation,
+                               double *out_result);
+
+/*
+ # Safety
+ `out_result` 须指向可写的 `c_double`。
+ */
+int ecobridge_calc_stability(long long last_ts, long long curr_ts, double *out_result);
+
+/*
+ 可配置恢复窗口的稳定性因子；非正或非有限的窗口回退为默认 900000ms
+
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_calc_stability_ex(long long last_ts,
+                                long long curr_ts,
+                                double recovery_window_ms,
+                                double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_calc_decay(double heat, double rate, double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_calc_decay_ex(double heat,
+                            double rate,
+                            double cycles_per_day,
+                            double reset_threshold,
+                            double *out_result);
+
+MacroSnapshot ecobridge_compute_macro_snapshot(double current_heat,
+                                               double m1,
+                                               long long last_volatile_ts,
+                                               long long current_ts,
+                                               double recovery_window_ms,
+                                               double decay_rate,
+                                               double cycles_per_day);
+
+/*
+ 泰尔指数 (财富集中度)；`balances_ptr` 为标准化余额数组 (已由 Micros 缩放)
+
+ # Safety
+ - `balances_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_calculate_theil(const double *balances_ptr, uintptr_t count, double *out_result);
+
+/*
+ # Safety
+ - `ctx_ptr` 为空或指向有效的 `TradeContext`
+ - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_calculate_epsilon(const TradeContext *ctx_ptr,
+                                const MarketConfig *cfg_ptr,
+                                double *out_result);
+
+/*
+ 调试：输出 epsilon 计算中使用的本地星期 (0=周一)、周末与节庆状态；`cfg_ptr` 为空时按默认周末判定
+
+ # Safety
+ - `ctx_ptr` 为空或指向有效的 `TradeContext`
+ - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+ - `out_day_of_week` 为空或指向可写的 `c_int`
+ - `out_is_weekend` 为空或指向可写的 `c_int`
+ - `out_festival` 为空或指向可写的 `c_int`
+ */
+int ecobridge_debug_environment(const TradeContext *ctx_ptr,
+                                const MarketConfig *cfg_ptr,
+                                int *out_day_of_week,
+                                int *out_is_weekend,
+                                int *out_festival);
+
+/*
+ # Safety
+ - `ctx_ptr` 为空或指向有效的 `TradeContext`
+ - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_calculate_epsilon_jittered(const TradeContext *ctx_ptr,
+                                         const MarketConfig *cfg_ptr,
+                                         unsigned long long seed,
+                                         double jitter_pct,
+                                         double *out_result);
+
+/*
+ 转账审计 (纯计算，无副作用)：预览与重复检查不会写入拦截审计轨迹，
+ 实际执行时被拒绝的转账由调用方通过 ecobridge_log_blocked_transfer 记录。
+
+ # Safety
+ - `out_result` 为空或指向可写的 `TransferResult`
+ - `ctx_ptr` 为空或指向有效的 `TransferContext`
+ - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+ */
+int ecobridge_compute_transfer_check(TransferResult *out_result,
+                                     const TransferContext *ctx_ptr,
+                                     const RegulatorConfig *cfg_ptr);
+
+/*
+ 定点 (整分) 审计：拦截判定与 ecobridge_compute_transfer_check 相同，税额以整分写入 `out_tax`，
+ `out_result.final_tax_micros` 恰为其 10_000 倍。精度保证见 security::regulator 定点计税一节。
+
+ # Safety
+ - `out_result` 为空或指向可写的 `TransferResult`
+ - `out_tax` 为空或指向可写的 `PriceI64`
+ - `ctx_ptr` 为空或指向有效的 `TransferContext`
+ - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+ */
+int ecobridge_compute_transfer_check_fixed(TransferResult *out_result,
+                                           PriceI64 *out_tax,
+                                           const TransferContext *ctx_ptr,
+                                           const RegulatorConfig *cfg_ptr);
+
+/*
+ 定点 (整分) 计税，不做拦截判定；参数非有限时返回 `InvalidValue`
+
+ # Safety
+ - `ctx_ptr` 为空或指向有效的 `TransferContext`
+ - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+ - `out_tax` 为空或指向可写的 `PriceI64`
+ */
+int ecobridge_compute_tax_fixed(const TransferContext *ctx_ptr,
+                                const RegulatorConfig *cfg_ptr,
+                                PriceI64 *out_tax);
+
+/*
+ 设置全局审计配置 (复制保存)，供 ecobridge_compute_transfer_check_global 使用；
+ 传入 NULL 清除。多策略调用方继续使用显式传参的 ecobridge_compute_transfer_check。
+
+ # Safety
+ `cfg_ptr` 须指向有效的 `RegulatorConfig`。
+ */
+int ecobridge_set_global_regulator_config(const RegulatorConfig *cfg_ptr);
+
+/*
+ 使用全局配置审计。未设置全局配置时返回 warning_code = -1 的拒绝结果，
+ 空指针或 panic 时返回 warning_code = -2 的拒绝结果。
+
+ # Safety
+ `ctx_ptr` 须指向有效的 `TransferContext`。
+ */
+TransferResult ecobridge_compute_transfer_check_global(const TransferContext *ctx_ptr);
+
+/*
+ 税费构成明细 (用于玩家税单展示)；空指针或 panic 时返回全零
+
+ # Safety
+ - `ctx_ptr` 为空或指向有效的 `TransferContext`
+ - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+ */
+TaxBreakdown ecobridge_compute_tax_breakdown(const TransferContext *ctx_ptr,
+                                             const RegulatorConfig *cfg_ptr);
+
+/*
+ 批量审计：`ctx_array_ptr` 与 `out_results_ptr` 均须包含 `count` 个元素。
+ 返回处理的笔数，参数非法时返回负的 EconStatus。
+
+ # Safety
+ - `ctx_array_ptr` 为空或指向至少 `count` 个连续可读的 `TransferContext`
+ - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+ - `out_results_ptr` 为空或指向至少 `count` 个连续可写的 `TransferResult`
+ */
+int ecobridge_compute_transfer_check_batch(const TransferContext *ctx_array_ptr,
+                                           const RegulatorConfig *cfg_ptr,
+                                           uintptr_t count,
+                                           TransferResult *out_results_ptr);
+
+/*
+ 记录一笔实际执行时被拦截的转账 (以当前时间计)，供 ecobridge_query_blocked_count 统计。
+ 仅接受正的拦截原因码；调用错误码 (<= 0) 返回 InvalidValue 且不记录。
+ */
+int ecobridge_log_blocked_transfer(int warning_code);
+
+/*
+ 统计最近 `days` 天内因 `code` 被拦截的转账次数 (code < 0 表示全部原因)
+
+ # Safety
+ `out_count` 为空或指向可写的 `u64`。
+ */
+int ecobridge_query_blocked_count(double days, int code, uint64_t *out_count);
+
+/*
+ 拆分规避评分 [0, 1]：`amounts_ptr` 为发送方近期转账金额 (标准单位)
+
+ # Safety
+ - `amounts_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_structuring_score(const double *amounts_ptr,
+                                uintptr_t count,
+                                double threshold,
+                                double *out_result);
+
+/*
+ # Safety
+ `out_result` 为空或指向可写的 `c_double`。
+ */
+int ecobridge_get_dynamic_limit(long long play_time_secs,
+                                double base,
+                                double rate,
+                                double max,
+                                double *out_result);
+
+/*
+ # Safety
+ - `pid_ptr` 为空或指向可读写的 `PidState`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_compute_pid_adjustment(PidState *pid_ptr,
+                                     double target,
+                                     double current,
+                                     double dt,
+                                     double inflation,
+                                     double market_heat,
+                                     double *out_result);
+
+/*
+ 校验并写入 PID 参数 (替代直接改写结构体字段)。
+ 成功返回 0；参数非法时返回首个非法字段编号的负值
+ (-1 = kp, -2 = ki, -3 = kd, -4 = lambda, -5 = integration_limit)，此时结构体不被修改；
+ 空指针与 panic 仍以正的 EconStatus 返回。
+
+ # Safety
+ `pid_ptr` 须指向可读写的 `PidState`。
+ */
+int ecobridge_configure_pid(PidState *pid_ptr,
+                            double kp,
+                            double ki,
+                            double kd,
+                            double lambda,
+                            double integration_limit);
+
+/*
+ 配置 PID 输出平滑：单步最大变化量与误差死区 (0 表示关闭)。
+ 返回约定同 ecobridge_configure_pid (-6 = max_delta_per_step, -7 = deadband)。
+
+ # Safety
+ `pid_ptr` 须指向可读写的 `PidState`。
+ */
+int ecobridge_configure_pid_output(PidState *pid_ptr, double max_delta_per_step, double deadband);
+
+/*
+ 配置微分低通滤波系数 (0, 1]，默认 0.3；越小滤波越重。非法时返回 -8。
+
+ # Safety
+ `pid_ptr` 须指向可读写的 `PidState`。
+ */
+int ecobridge_configure_pid_derivative_filter(PidState *pid_ptr, double alpha);
+
+/*
+ 手动调价结束、重新启用 PID 前调用：反算积分项使首次自动输出接近 `current_output`
+
+ # Safety
+ `pid_ptr` 须指向可读写的 `PidState`。
+ */
+int ecobridge_pid_bumpless_transfer(PidState *pid_ptr,
+                                    double current_output,
+                                    double current_vel);
+
+/*
+ # Safety
+ `pid_ptr` 须指向可读写的 `PidState`。
+ */
+int ecobridge_reset_pid_state(PidState *pid_ptr);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_garch_init(const char *key_ptr, double alpha, double beta, double omega);
+
+/*
+ # Safety
+ - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_vol` 为空或指向可写的 `c_double`
+ */
+int ecobridge_garch_update(const char *key_ptr, double return_val, double *out_vol);
+
+/*
+ # Safety
+ - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_vol` 为空或指向可写的 `c_double`
+ */
+int ecobridge_garch_forecast(const char *key_ptr, int steps, double *out_vol);
+
+/*
+ # Safety
+ - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_mult` 为空或指向可写的 `c_double`
+ */
+int ecobridge_garch_multiplier(const char *key_ptr, double *out_mult);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_garch_free(const char *key_ptr);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_kalman_init(const char *key_ptr);
+
+/*
+ # Safety
+ - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_filtered` 为空或指向可写的 `c_double`
+ */
+int ecobridge_kalman_filter(const char *key_ptr,
+                            double measurement,
+                            double dt,
+                            double *out_filtered);
+
+/*
+ # Safety
+ - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_vel` 为空或指向可写的 `c_double`
+ */
+int ecobridge_kalman_velocity(const char *key_ptr, double *out_vel);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_kalman_free(const char *key_ptr);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_arima_init(const char *key_ptr, int p, int d);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_arima_add_obs(const char *key_ptr, double value);
+
+/*
+ # Safety
+ - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_pred` 为空或指向可写的 `c_double`
+ */
+int ecobridge_arima_predict(const char *key_ptr, int horizon, double *out_pred);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_arima_free(const char *key_ptr);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_mpc_init(const char *key_ptr, int horizon);
+
+/*
+ # Safety
+ - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_lambda` 为空或指向可写的 `c_double`
+ - `out_sink` 为空或指向可写的 `c_double`
+ - `out_faucet` 为空或指向可写的 `c_double`
+ - `out_pred_m1` 为空或指向可写的 `c_double`
+ */
+int ecobridge_mpc_optimize(const char *key_ptr,
+                           double m1_ratio,
+                           double price_index,
+                           double inflation_rate,
+                           double market_heat,
+                           double net_flow_rate,
+                           double target_m1,
+                           double dt_seconds,
+                           double *out_lambda,
+                           double *out_sink,
+                           double *out_faucet,
+                           double *out_pred_m1);
+
+/*
+ # Safety
+ `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+ */
+int ecobridge_mpc_free(const char *key_ptr);
+
+/*
+ 对一批玩家间应付款做轧差，将最小化后的结算转账写入 `out_ptr`，所需笔数写入 `out_count`。
+ 若所需笔数超过 `max_out`，仅写入前 `max_out` 笔 (调用方可据 `out_count` 扩容重试)。
+ 空输入返回 Ok 且笔数为 0；指针为空返回 NullPointer，`count` 超过 1_000_000 返回 InvalidLength。
+
+ # Safety
+ - `transfers_ptr` 为空或指向至少 `count` 个连续可读的 `PairTransfer`
+ - `out_ptr` 为空或指向至少 `max_out` 个连续可写的 `PairTransfer`
+ - `out_count` 为空或指向可写的 `usize`
+ */
+int ecobridge_net_settle(const PairTransfer *transfers_ptr,
+                         uintptr_t count,
+                         PairTransfer *out_ptr,
+                         uintptr_t max_out,
+                         uintptr_t *out_count);
+
+/*
+ # Safety
+ - `quantities_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ - `prices_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ */
+double ecobridge_portfolio_value(const double *quantities_ptr,
+                                 const double *prices_ptr,
+                                 uintptr_t count);
+
+/*
+ # Safety
+ - `quantities_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ - `prices_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ */
+double ecobridge_portfolio_value_with_liquidation(const double *quantities_ptr,
+                                                  const double *prices_ptr,
+                                                  uintptr_t count);
+
+/*
+ 基于内存热历史估计价格弹性；`market_key_ptr` 为空时查询全局聚合
+
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_estimate_elasticity(const char *market_key_ptr, double tau, double *out_result);
+
+/*
+ 时间加权均价：`timestamps` 与 `prices` 为等长、按时间升序的采样，最后一个价格持续到 `end_ts`
+
+ # Safety
+ - `timestamps_ptr` 为空或指向至少 `count` 个连续可读的 `c_longlong`
+ - `prices_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_compute_twap(const long long *timestamps_ptr,
+                           const double *prices_ptr,
+                           uintptr_t count,
+                           long long end_ts,
+                           double *out_result);
+
+/*
+ 基于内存热历史的价格趋势 (-1 下跌 / 0 平稳 / 1 上涨)；`market_key_ptr` 为空时查询全局聚合
+
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_trend` 为空或指向可写的 `c_int`
+ */
+int ecobridge_price_trend(const char *market_key_ptr,
+                          double short_tau,
+                          double long_tau,
+                          int *out_trend);
+
+/*
+ 基于内存热历史的 MACD 指标 (MACD 线、信号线、柱状图)；`market_key_ptr` 为空时查询全局聚合
+
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `out_macd` 为空或指向可写的 `c_double`
+ - `out_signal` 为空或指向可写的 `c_double`
+ - `out_histogram` 为空或指向可写的 `c_double`
+ */
+int ecobridge_compute_macd(const char *market_key_ptr,
+                           double fast_tau,
+                           double slow_tau,
+                           double signal_tau,
+                           double *out_macd,
+                           double *out_signal,
+                           double *out_histogram);
+
+/*
+ 一次性校验 MarketConfig / RegulatorConfig / PidState 的全部字段。
+ 所有违规项以换行分隔写入 `out_buf` (可为空以仅获取数量)，返回违规项数量；
+ 指针非法时返回负的 EconStatus。
+
+ # Safety
+ - `market_ptr` 为空或指向有效的 `MarketConfig`
+ - `regulator_ptr` 为空或指向有效的 `RegulatorConfig`
+ - `pid_ptr` 为空或指向有效的 `PidState`
+ - `out_buf` 须指向至少 `buf_len` 字节的可写缓冲区
+ */
+int ecobridge_validate_configs(const MarketConfig *market_ptr,
+                               const RegulatorConfig *regulator_ptr,
+                               const PidState *pid_ptr,
+                               char *out_buf,
+                               uintptr_t buf_len);
+
+/*
+ 创建市场状态句柄：`ctx_ptr` 为环境因子的上下文模板 (时间戳在每次定价时替换)，
+ `ttl_ms <= 0` 使用默认 5 秒。参数非法时返回 NULL；须以 `ecobridge_market_state_free` 释放。
+
+ # Safety
+ - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+ - `ctx_ptr` 为空或指向有效的 `TradeContext`
+ - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+ */
+MarketState *ecobridge_market_state_new(const char *market_key_ptr,
+                                        const TradeContext *ctx_ptr,
+                                        const MarketConfig *cfg_ptr,
+                                        double tau,
+                                        long long ttl_ms);
+
+/*
+ 使用句柄缓存的 epsilon 计算价格 (TTL 内复用，过期自动重算)；N_eff 每次重新查询
+
+ # Safety
+ - `state` 须为 `ecobridge_market_state_new` 返回且尚未释放的句柄
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_market_state_price(MarketState *state,
+                                 long long current_ts,
+                                 double base,
+                                 double trade_amount,
+                                 double lambda,
+                                 double *out_result);
+
+/*
+ # Safety
+ `state` 为空或为 `ecobridge_market_state_new` 返回且尚未释放的句柄，调用后不得再使用。
+ */
+void ecobridge_market_state_free(MarketState *state);
+
+/*
+ 创建热度平滑句柄 (`alpha` 非法时使用默认 0.2)；须以 `ecobridge_heat_smoother_free` 释放
+ */
+HeatSmoother *ecobridge_heat_smoother_new(double alpha);
+
+/*
+ 以平滑后的 market_heat / eco_saturation 计算 epsilon，并更新句柄状态
+
+ # Safety
+ - `smoother` 须为 `ecobridge_heat_smoother_new` 返回且尚未释放的句柄
+ - `ctx_ptr` 为空或指向有效的 `TradeContext`
+ - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+ - `out_result` 为空或指向可写的 `c_double`
+ */
+int ecobridge_calculate_epsilon_smoothed(HeatSmoother *smoother,
+                                         const TradeContext *ctx_ptr,
+                                         const MarketConfig *cfg_ptr,
+                                         double *out_result);
+
+/*
+ # Safety
+ `smoother` 为空或为 `ecobridge_heat_smoother_new` 返回且尚未释放的句柄，调用后不得再使用。
+ */
+void ecobridge_heat_smoother_free(HeatSmoother *smoother);
 
 #endif  /* ECOBRIDGE_RUST_H */
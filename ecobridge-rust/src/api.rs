@@ -0,0 +1,115 @@
+// ==================================================
+// FILE: ecobridge-rust/src/api.rs
+// ==================================================
+
+//! Safe Rust API
+//!
+//! 供其他 Rust 服务 (如独立的市场模拟器) 直接依赖本 crate，无需经过 C 字符串与裸指针。
+//! 所有函数按值接收结构体，参数或配置非法时返回 `Err` 而不是静默回退。
+//!
+//! # 与 FFI 的差异
+//! 1. 金额参数为 i64 Micros，与内部定价核心一致。
+//! 2. `transfer_check` 为纯函数，不写入拦截审计日志。
+
+use std::fmt;
+
+use crate::economy::{environment, pricing};
+use crate::security::regulator;
+
+pub use crate::models::{MarketConfig, RegulatorConfig, TradeContext, TransferContext, TransferResult};
+
+/// 安全 API 的错误类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// 单个标量参数非法 (参数名, 取值)
+    InvalidArgument(&'static str, f64),
+    /// 配置结构体校验失败，包含全部越界字段描述
+    InvalidConfig(Vec<String>),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::InvalidArgument(name, value) => write!(f, "invalid argument {} = {}", name, value),
+            ApiError::InvalidConfig(violations) => write!(f, "invalid config: {}", violations.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+pub type Result<T> = std::result::Result<T, ApiError>;
+
+fn require_finite(name: &'static str, value: f64) -> Result<f64> {
+    if value.is_finite() { Ok(value) } else { Err(ApiError::InvalidArgument(name, value)) }
+}
+
+fn check_pricing_args(n_eff: f64, lambda: f64, epsilon: f64) -> Result<()> {
+    require_finite("n_eff", n_eff)?;
+    if require_finite("lambda", lambda)? < 0.0 {
+        return Err(ApiError::InvalidArgument("lambda", lambda));
+    }
+    if require_finite("epsilon", epsilon)? <= 0.0 {
+        return Err(ApiError::InvalidArgument("epsilon", epsilon));
+    }
+    Ok(())
+}
+
+fn into_result(violations: Vec<String>) -> Result<()> {
+    if violations.is_empty() { Ok(()) } else { Err(ApiError::InvalidConfig(violations)) }
+}
+
+/// 参考价格 (不含本次交易量冲击)
+pub fn price_final(base_micros: i64, n_eff: f64, lambda: f64, epsilon: f64) -> Result<f64> {
+    check_pricing_args(n_eff, lambda, epsilon)?;
+    Ok(pricing::compute_price_final_internal(base_micros, n_eff, lambda, epsilon))
+}
+
+/// 单体成交价格 (包含本次交易量冲击；`trade_amount_micros` 正数为卖出，负数为买入)
+pub fn price_humane(base_micros: i64, n_eff: f64, trade_amount_micros: i64, lambda: f64, epsilon: f64) -> Result<f64> {
+    check_pricing_args(n_eff, lambda, epsilon)?;
+    Ok(pricing::compute_price_humane_internal(base_micros, n_eff, trade_amount_micros, lambda, epsilon))
+}
+
+/// 环境因子 epsilon；上下文中的非有限浮点视为 0，配置须通过校验
+pub fn epsilon(ctx: TradeContext, cfg: MarketConfig) -> Result<f64> {
+    let mut violations = Vec::new();
+    cfg.collect_violations(&mut violations);
+    into_result(violations)?;
+    Ok(environment::calculate_epsilon_internal(&ctx.sanitized(), &cfg))
+}
+
+/// 转账审计与计税
+pub fn transfer_check(ctx: TransferContext, cfg: RegulatorConfig) -> Result<TransferResult> {
+    let mut violations = Vec::new();
+    cfg.collect_violations(&mut violations);
+    into_result(violations)?;
+    Ok(regulator::compute_transfer_check_internal(&ctx, &cfg))
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_matches_internal_and_rejects_bad_input() {
+        let price = price_final(1_000_000, 50.0, 0.01, 1.0).unwrap();
+        assert_eq!(price, pricing::compute_price_final_internal(1_000_000, 50.0, 0.01, 1.0));
+        assert_ne!(price_humane(1_000_000, 50.0, 10_000_000, 0.01, 1.0).unwrap(), price);
+
+        assert!(matches!(price_final(1_000_000, f64::NAN, 0.01, 1.0), Err(ApiError::InvalidArgument("n_eff", _))));
+        assert!(matches!(price_final(1_000_000, 0.0, 0.01, 0.0), Err(ApiError::InvalidArgument("epsilon", _))));
+        assert!(matches!(price_humane(1_000_000, 0.0, 0, -1.0, 1.0), Err(ApiError::InvalidArgument("lambda", _))));
+
+        let eps = epsilon(TradeContext::default(), MarketConfig::default()).unwrap();
+        assert!((0.1..=10.0).contains(&eps));
+        let bad = MarketConfig { seasonal_weight: -1.0, ..MarketConfig::default() };
+        assert!(matches!(epsilon(TradeContext::default(), bad), Err(ApiError::InvalidConfig(v)) if v.len() == 1));
+
+        let ctx = TransferContext { amount_micros: 1_000_000, sender_balance: 10_000_000, ..Default::default() };
+        let res = transfer_check(ctx, RegulatorConfig::default()).unwrap();
+        assert_eq!(res.final_tax_micros, regulator::compute_transfer_check_internal(&ctx, &RegulatorConfig::default()).final_tax_micros);
+    }
+}
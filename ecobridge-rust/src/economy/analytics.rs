@@ -0,0 +1,319 @@
+// ==================================================
+// FILE: ecobridge-rust/src/economy/analytics.rs
+// ==================================================
+
+//! Market Analytics Module
+//!
+//! 基于内存热历史的统计诊断指标，供管理员校准 lambda 等参数。
+//!
+//! # 价格代理说明
+//! 热历史只记录 (时间戳, 数量 Micros)，不含成交价。数量符号编码方向：
+//! 正数为玩家卖出 (供给)，负数为玩家买入 (需求)。因此本模块使用
+//! **订单失衡** (净买入占比 ∈ [-1, 1]) 作为对数价格变化的代理：
+//! 买压推高价格，卖压压低价格。
+
+use crate::economy::summation::calculate_volume_in_memory;
+use crate::models::HistoryRecord;
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+const MICROS_SCALE: f64 = 1_000_000.0;
+/// 弹性估计的最小窗口宽度 (1 分钟)：更窄的窗口没有统计意义，且会让分桶代价失控
+const MIN_ELASTICITY_WINDOW_MS: i64 = 60_000;
+/// 回归所需的最少非空窗口数
+const MIN_ELASTICITY_WINDOWS: usize = 3;
+/// 趋势判定死区：短/长窗口流量相对差异低于此值视为平稳
+const TREND_DEAD_BAND: f64 = 0.1;
+
+pub const TREND_FALLING: i32 = -1;
+pub const TREND_STABLE: i32 = 0;
+pub const TREND_RISING: i32 = 1;
+
+/// 按 `tau` 天宽度切分窗口，返回每个非空窗口的 (窗口结束时间, 净买入占比)
+fn imbalance_windows(history: &[HistoryRecord], window_ms: i64) -> Vec<(i64, f64)> {
+    let mut windows: Vec<(i64, f64)> = Vec::new();
+    let Some(first) = history.first() else { return windows; };
+
+    let mut bucket_end = first.timestamp.saturating_add(window_ms);
+    let (mut net_buy, mut gross) = (0.0_f64, 0.0_f64);
+
+    for rec in history {
+        if rec.timestamp >= bucket_end {
+            if gross > 0.0 {
+                windows.push((bucket_end, net_buy / gross));
+            }
+            net_buy = 0.0;
+            gross = 0.0;
+            // 直接跳过中间的空窗口
+            let steps = (rec.timestamp - bucket_end) / window_ms + 1;
+            bucket_end = bucket_end.saturating_add(steps.saturating_mul(window_ms));
+        }
+        let amount = rec.amount_micros as f64;
+        net_buy -= amount;
+        gross += amount.abs();
+    }
+    if gross > 0.0 {
+        windows.push((bucket_end, net_buy / gross));
+    }
+    windows
+}
+
+/// 经验价格弹性估计
+///
+/// 以 `tau` 天为窗口，回归每个窗口的订单失衡 (对数价格代理) 对窗口结束时衰减有效供应量
+/// (N_eff) 的 OLS 斜率。供应累积伴随卖压时斜率为负，其绝对值越大说明价格对供应越敏感。
+/// 数据不足 (少于 3 个非空窗口)、窗口窄于 1 分钟或结果非有限时返回 0.0。
+pub fn estimate_elasticity(history: &[HistoryRecord], tau: f64) -> f64 {
+    if history.len() < MIN_ELASTICITY_WINDOWS || !tau.is_finite() || tau <= 0.0 {
+        return 0.0;
+    }
+    let window_ms = (tau * MS_PER_DAY) as i64;
+    if window_ms < MIN_ELASTICITY_WINDOW_MS {
+        return 0.0;
+    }
+
+    let windows = imbalance_windows(history, window_ms);
+    if windows.len() < MIN_ELASTICITY_WINDOWS {
+        return 0.0;
+    }
+
+    let ends: Vec<i64> = windows.iter().map(|w| w.0).collect();
+    let points: Vec<(f64, f64)> = supply_at(history, &ends, tau)
+        .into_iter()
+        .zip(windows.iter().map(|w| w.1))
+        .collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for &(x, y) in &points {
+        cov += (x - mean_x) * (y - mean_y);
+        var += (x - mean_x) * (x - mean_x);
+    }
+
+    if var <= f64::EPSILON {
+        return 0.0;
+    }
+    let slope = cov / var;
+    if slope.is_finite() { slope } else { 0.0 }
+}
+
+/// 各窗口结束时刻的衰减有效供应量 (N_eff)，`ends` 须升序
+///
+/// 与对每个时刻调用 `calculate_volume_in_memory` 的结果一致：仅计入早于该时刻的记录
+/// (窗口边界后的交易不混入)，且只保留 10·tau 天内的记录。为避免 O(窗口数 × 记录数)，
+/// 上一时刻的和按时间差整体衰减，再加入新进入的记录、扣除滑出视界的记录。
+fn supply_at(history: &[HistoryRecord], ends: &[i64], tau: f64) -> Vec<f64> {
+    let lambda = 1.0 / (tau * MS_PER_DAY);
+    let horizon_ms = (tau * MS_PER_DAY * 10.0) as i64;
+    let contribution = |r: &HistoryRecord, at: i64| (r.amount_micros as f64) * (-(at.saturating_sub(r.timestamp) as f64) * lambda).exp();
+
+    let (mut sum, mut sum_ts) = (0.0_f64, ends.first().copied().unwrap_or(0));
+    let (mut entered, mut expired) = (0usize, 0usize);
+    ends.iter()
+        .map(|&end_ts| {
+            sum *= (-(end_ts.saturating_sub(sum_ts) as f64) * lambda).exp();
+            sum_ts = end_ts;
+            while entered < history.len() && history[entered].timestamp < end_ts {
+                sum += contribution(&history[entered], end_ts);
+                entered += 1;
+            }
+            let valid_past = end_ts.saturating_sub(horizon_ms);
+            while expired < entered && history[expired].timestamp < valid_past {
+                sum -= contribution(&history[expired], end_ts);
+                expired += 1;
+            }
+            let volume = sum / MICROS_SCALE;
+            if volume.is_finite() { volume } else { 0.0 }
+        })
+        .collect()
+}
+
+/// 价格趋势信号 (GUI 箭头用)：-1 下跌、0 平稳、1 上涨
+///
+/// 以最后一条记录的时间为当前时刻，分别计算短/长窗口的衰减有效供应量，
+/// 并除以各自的 tau 换算为“日均净供应流量”。短期流量明显高于长期均值
+/// (供给加速) 说明价格走弱；明显低于 (买压加速) 说明价格走强。
+/// 要求 `0 < short_tau < long_tau`，否则或数据为空时返回平稳。
+pub fn price_trend(history: &[HistoryRecord], short_tau: f64, long_tau: f64) -> i32 {
+    let valid_tau = |t: f64| t.is_finite() && t > 0.0;
+    if !valid_tau(short_tau) || !valid_tau(long_tau) || short_tau >= long_tau {
+        return TREND_STABLE;
+    }
+    let Some(last) = history.last() else { return TREND_STABLE; };
+    let now = last.timestamp;
+
+    let short_rate = calculate_volume_in_memory(history, now, short_tau) / short_tau;
+    let long_rate = calculate_volume_in_memory(history, now, long_tau) / long_tau;
+
+    let scale = short_rate.abs().max(long_rate.abs());
+    if !scale.is_finite() || scale <= f64::EPSILON {
+        return TREND_STABLE;
+    }
+
+    let relative = (short_rate - long_rate) / scale;
+    if relative > TREND_DEAD_BAND {
+        TREND_FALLING
+    } else if relative < -TREND_DEAD_BAND {
+        TREND_RISING
+    } else {
+        TREND_STABLE
+    }
+}
+
+/// 时间加权均价 (TWAP)
+///
+/// 热历史不含成交价，因此由调用方提供按时间升序的 `(时间戳, 价格)` 采样
+/// (例如 Java 侧从 H2 读取的商店报价快照)。每个价格持续到下一次采样，
+/// 最后一个价格持续到 `end_ts`。非有限价格的采样被跳过；总时长为 0 时
+/// 退化为算术平均，无有效采样时返回 0.0。
+pub fn time_weighted_average(samples: &[(i64, f64)], end_ts: i64) -> f64 {
+    let valid: Vec<(i64, f64)> = samples.iter().copied().filter(|(_, p)| p.is_finite()).collect();
+    if valid.is_empty() {
+        return 0.0;
+    }
+
+    let (mut weighted, mut total_ms) = (0.0_f64, 0.0_f64);
+    for (i, &(ts, price)) in valid.iter().enumerate() {
+        let next_ts = valid.get(i + 1).map_or(end_ts, |&(t, _)| t);
+        let span = next_ts.saturating_sub(ts).max(0) as f64;
+        weighted += price * span;
+        total_ms += span;
+    }
+
+    if total_ms > 0.0 {
+        weighted / total_ms
+    } else {
+        valid.iter().map(|(_, p)| p).sum::<f64>() / valid.len() as f64
+    }
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400_000;
+
+    fn make_record(ts: i64, amount_micros: i64) -> HistoryRecord {
+        HistoryRecord { timestamp: ts, amount_micros }
+    }
+
+    #[test]
+    fn test_insufficient_data_returns_zero() {
+        assert_eq!(estimate_elasticity(&[], 7.0), 0.0);
+        let history = vec![make_record(DAY, 1_000_000), make_record(2 * DAY, 1_000_000)];
+        assert_eq!(estimate_elasticity(&history, 7.0), 0.0);
+    }
+
+    #[test]
+    fn test_twap_weights_by_duration() {
+        // 等间距：TWAP 与算术平均一致
+        let even = [(0, 10.0), (DAY, 20.0), (2 * DAY, 30.0)];
+        assert!((time_weighted_average(&even, 3 * DAY) - 20.0).abs() < 1e-12);
+
+        // 非等间距：低价维持了大部分时间，TWAP 低于算术平均
+        let uneven = [(0, 10.0), (5 * DAY, 20.0), (5 * DAY + DAY / 2, 30.0)];
+        let twap = time_weighted_average(&uneven, 6 * DAY);
+        assert!((twap - (10.0 * 5.0 + 20.0 * 0.5 + 30.0 * 0.5) / 6.0).abs() < 1e-12);
+        assert!(twap < 20.0);
+
+        assert_eq!(time_weighted_average(&[], DAY), 0.0);
+        assert_eq!(time_weighted_average(&[(DAY, 7.0), (DAY, f64::NAN)], DAY), 7.0);
+    }
+
+    #[test]
+    fn test_invalid_tau_returns_zero() {
+        let history: Vec<_> = (0..10).map(|i| make_record(i * DAY, 1_000_000)).collect();
+        assert_eq!(estimate_elasticity(&history, 0.0), 0.0);
+        assert_eq!(estimate_elasticity(&history, f64::NAN), 0.0);
+    }
+
+    #[test]
+    fn test_sell_pressure_with_rising_supply_gives_negative_slope() {
+        // 交替出现卖压窗口 (供给上升) 与买压窗口 (供给回落)
+        let mut history = Vec::new();
+        for w in 0..8i64 {
+            let ts = 1_000_000_000_000 + w * DAY + DAY / 2;
+            let amount = if w % 2 == 0 { 50_000_000 } else { -30_000_000 };
+            history.push(make_record(ts, amount));
+        }
+        let elasticity = estimate_elasticity(&history, 1.0);
+        assert!(elasticity < 0.0 && elasticity.is_finite(),
+            "supply build-up under sell pressure should yield a negative slope, got {}", elasticity);
+    }
+
+    #[test]
+    fn test_incremental_supply_matches_direct_query() {
+        let history: Vec<_> = (0..500i64)
+            .map(|i| make_record(1_000_000_000_000 + i * i * 60_000, if i % 3 == 0 { -2_000_000 } else { 3_000_000 }))
+            .collect();
+        let tau = 0.5;
+        let ends: Vec<i64> = (1..40).map(|k| 1_000_000_000_000 + k * DAY / 2).collect();
+
+        for (end_ts, incremental) in ends.iter().zip(supply_at(&history, &ends, tau)) {
+            let visible = history.partition_point(|r| r.timestamp < *end_ts);
+            let direct = calculate_volume_in_memory(&history[..visible], *end_ts, tau);
+            assert!((incremental - direct).abs() <= 1e-9 * direct.abs().max(1.0),
+                "at {}: incremental {} vs direct {}", end_ts, incremental, direct);
+        }
+    }
+
+    #[test]
+    fn test_tiny_window_is_rejected_and_gaps_are_skipped() {
+        // 一个月跨度、只有数笔成交：1 毫秒窗口直接拒绝，不逐窗口扫描
+        let history: Vec<_> = (0..6).map(|i| make_record(1_000_000_000_000 + i * 6 * DAY, 1_000_000)).collect();
+        assert_eq!(estimate_elasticity(&history, 1.0 / MS_PER_DAY), 0.0);
+
+        // 最小合法窗口下，空窗口被整体跳过，每笔成交各占一个窗口
+        let windows = imbalance_windows(&history, MIN_ELASTICITY_WINDOW_MS);
+        assert_eq!(windows.len(), history.len());
+        for (w, r) in windows.iter().zip(&history) {
+            assert!(w.0 > r.timestamp && w.0 - r.timestamp <= MIN_ELASTICITY_WINDOW_MS);
+        }
+    }
+
+    #[test]
+    fn test_constant_imbalance_returns_zero() {
+        let history: Vec<_> = (0..10)
+            .map(|i| make_record(1_000_000_000_000 + i * DAY, 1_000_000))
+            .collect();
+        assert!(estimate_elasticity(&history, 1.0).abs() < 1e-12);
+    }
+
+    /// 60 天每小时一笔卖出 1 件，最后 `tail_hours` 小时改为 `tail_amount`
+    fn hourly_series(tail_hours: i64, tail_amount: i64) -> Vec<HistoryRecord> {
+        let hour = DAY / 24;
+        let total = 60 * 24;
+        (0..total)
+            .map(|h| {
+                let amount = if h >= total - tail_hours { tail_amount } else { 1_000_000 };
+                make_record(1_000_000_000_000 + h * hour, amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trend_stable_for_steady_flow() {
+        assert_eq!(price_trend(&hourly_series(0, 1_000_000), 1.0, 14.0), TREND_STABLE);
+    }
+
+    #[test]
+    fn test_trend_rising_on_recent_buying() {
+        assert_eq!(price_trend(&hourly_series(48, -5_000_000), 1.0, 14.0), TREND_RISING);
+    }
+
+    #[test]
+    fn test_trend_falling_on_recent_dumping() {
+        assert_eq!(price_trend(&hourly_series(48, 5_000_000), 1.0, 14.0), TREND_FALLING);
+    }
+
+    #[test]
+    fn test_trend_invalid_windows_are_stable() {
+        let history = hourly_series(48, 5_000_000);
+        assert_eq!(price_trend(&history, 14.0, 1.0), TREND_STABLE);
+        assert_eq!(price_trend(&history, 0.0, 14.0), TREND_STABLE);
+        assert_eq!(price_trend(&[], 1.0, 14.0), TREND_STABLE);
+    }
+}
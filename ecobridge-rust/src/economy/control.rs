@@ -15,6 +15,7 @@
 //! 1. **Gain Scheduling**: 增益随市场热度（Heat）动态缩放。
 //! 2. **Anti-Windup**: 采用 Back-calculation 算法防止积分饱和。
 //! 3. **Panic Damping**: 监测二阶导数（加速度），在市场恐慌时强制阻尼。
+//! 4. **Setpoint Reset**: 设定值发生显著变化时衰减积分项，避免管理员调整目标后的超调。
 
 use crate::models::PidState;
 
@@ -31,7 +32,10 @@ pub const OUTPUT_BASELINE: f64 = 1.0;
 
 pub const INTEGRAL_DECAY: f64 = 0.99999;
 pub const BACK_CALC_GAIN: f64 = 0.2;
-pub const DERIVATIVE_FILTER_ALPHA: f64 = 0.3;
+/// 微分低通滤波系数默认值；可由 `PidState::derivative_alpha` 覆盖
+pub const DERIVATIVE_FILTER_ALPHA: f64 = crate::models::DEFAULT_DERIVATIVE_FILTER_ALPHA;
+/// 设定值突变时积分项的保留比例 (其余部分立即释放，防止旧积分带来的超调)
+pub const SETPOINT_CHANGE_INTEGRAL_RETAIN: f64 = 0.2;
 
 // ==================== 行为经济学：宏观调控常量 ====================
 
@@ -39,6 +43,8 @@ pub const PANIC_THRESHOLD: f64 = 50.0;     // 触发恐慌抑制的加速度阈
 pub const PANIC_DAMPING: f64 = 1.8;       // 恐慌状态下的微分项放大倍数
 // [v2.0] These are tuned defaults — overridable via economy.macro.* in config.yml
 pub const HEAT_SENSITIVITY: f64 = 0.5;    // 财富流速感应灵敏度 (config: economy.macro.heat-sensitivity)
+pub const DEFAULT_SCHEDULE_PIVOT: f64 = 0.05;      // 增益调度通胀拐点
+pub const DEFAULT_SCHEDULE_STEEPNESS: f64 = 20.0;  // 增益调度陡峭度
 
 #[inline]
 fn sigmoid(x: f64) -> f64 {
@@ -64,6 +70,19 @@ pub fn compute_adaptive_gain(cfg: &PidState, heat: f64) -> (f64, f64) {
     (adaptive_kp, adaptive_ki)
 }
 
+/// 通胀增益调度系数 γ ∈ [1, 2]：通胀越过拐点后增益逐步翻倍
+///
+/// `schedule_steepness <= 0` (如 Java 侧零初始化的旧状态) 时拐点与陡峭度均取默认值。
+#[inline]
+pub fn compute_schedule_gamma(cfg: &PidState, inflation: f64) -> f64 {
+    let (pivot, steepness) = if cfg.schedule_steepness > 0.0 && cfg.schedule_pivot.is_finite() {
+        (cfg.schedule_pivot, cfg.schedule_steepness)
+    } else {
+        (DEFAULT_SCHEDULE_PIVOT, DEFAULT_SCHEDULE_STEEPNESS)
+    };
+    1.0 + sigmoid((inflation - pivot) * steepness)
+}
+
 // ==================== 2. 全自适应 PID 调节核心 ====================
 
 /// 演进后的宏观调控步进计算
@@ -86,12 +105,21 @@ pub fn compute_pid_adjustment_internal(
     let error = target_vel - current_vel;
     let dt_safe = dt.clamp(0.0, MAX_SAFE_DT);
 
+    // 设定值突变检测：相对变化超过 setpoint_tolerance 时释放大部分历史积分
+    if pid.setpoint_tolerance > 0.0 {
+        let reference = pid.prev_target.abs().max(MIN_TIME_STEP);
+        if (target_vel - pid.prev_target).abs() / reference > pid.setpoint_tolerance {
+            pid.integral *= SETPOINT_CHANGE_INTEGRAL_RETAIN;
+        }
+    }
+    pid.prev_target = target_vel;
+
     // 2. 计算基于流速的自适应基础增益
     let (base_kp, base_ki) = compute_adaptive_gain(pid, market_heat);
 
     // 3. 叠加宏观周期调度 (Gain Scheduling)
     // 通胀率越高，强制系统进入收缩模式（增强价格向上弹性的阻力）
-    let schedule_gamma = 1.0 + sigmoid((inflation - 0.05) * 20.0);
+    let schedule_gamma = compute_schedule_gamma(pid, inflation);
     let active_kp = base_kp * schedule_gamma;
     let active_ki = base_ki * schedule_gamma;
     
@@ -114,9 +142,10 @@ pub fn compute_pid_adjustment_internal(
     let raw_derivative = if dt_safe > MIN_TIME_STEP { delta_pv / dt_safe } else { 0.0 };
     
     // 低通滤波滤除瞬时噪声
-    pid.filtered_d = DERIVATIVE_FILTER_ALPHA.mul_add(
+    let alpha = pid.effective_derivative_alpha();
+    pid.filtered_d = alpha.mul_add(
         raw_derivative,
-        (1.0 - DERIVATIVE_FILTER_ALPHA) * pid.filtered_d
+        (1.0 - alpha) * pid.filtered_d
     );
     pid.prev_pv = current_vel;
 
@@ -138,20 +167,138 @@ pub fn compute_pid_adjustment_internal(
     // 更新饱和状态标志 (对齐 models.rs 中的 c_int 类型)
     pid.is_saturated = if (raw_output - final_output).abs() > 1e-6 { 1 } else { 0 };
     
-    if final_output.is_finite() { final_output } else { OUTPUT_BASELINE }
+    let final_output = if final_output.is_finite() { final_output } else { OUTPUT_BASELINE };
+
+    // 8. 输出平滑：死区内保持上一次输出，并限制单步变化幅度，避免价格闪烁
+    let smoothed = smooth_output(pid, error, final_output);
+    pid.prev_output = smoothed;
+    smoothed
+}
+
+/// 对控制器输出施加死区与限速 (首次调用时 prev_output <= 0，不做平滑)
+#[inline]
+fn smooth_output(pid: &PidState, error: f64, output: f64) -> f64 {
+    let prev = pid.prev_output;
+    if !(prev.is_finite() && prev > 0.0) {
+        return output;
+    }
+    if pid.deadband > 0.0 && error.abs() <= pid.deadband {
+        return prev;
+    }
+    if pid.max_delta_per_step > 0.0 {
+        return output.clamp(prev - pid.max_delta_per_step, prev + pid.max_delta_per_step);
+    }
+    output
 }
 
 /// 验证 PID 配置参数的合法性
 pub fn validate_pid_params(pid: &PidState) -> bool {
-    pid.kp.is_finite() && pid.kp >= 0.0
-        && pid.ki.is_finite() && pid.ki >= 0.0
-        && pid.kd.is_finite() && pid.kd >= 0.0
-        && pid.lambda.is_finite() && (0.0..=1.0).contains(&pid.lambda)
+    invalid_pid_param(pid).is_none()
+}
+
+// PID 参数编号 (ecobridge_configure_pid 以其负值报告首个非法字段)
+pub const PID_PARAM_KP: i32 = 1;
+pub const PID_PARAM_KI: i32 = 2;
+pub const PID_PARAM_KD: i32 = 3;
+pub const PID_PARAM_LAMBDA: i32 = 4;
+pub const PID_PARAM_INTEGRATION_LIMIT: i32 = 5;
+pub const PID_PARAM_MAX_DELTA: i32 = 6;
+pub const PID_PARAM_DEADBAND: i32 = 7;
+pub const PID_PARAM_DERIVATIVE_ALPHA: i32 = 8;
+
+/// 返回首个非法增益参数的编号 (不检查 integration_limit)
+pub fn invalid_pid_param(pid: &PidState) -> Option<i32> {
+    let non_negative = |v: f64| v.is_finite() && v >= 0.0;
+    if !non_negative(pid.kp) {
+        Some(PID_PARAM_KP)
+    } else if !non_negative(pid.ki) {
+        Some(PID_PARAM_KI)
+    } else if !non_negative(pid.kd) {
+        Some(PID_PARAM_KD)
+    } else if !(pid.lambda.is_finite() && (0.0..=1.0).contains(&pid.lambda)) {
+        Some(PID_PARAM_LAMBDA)
+    } else {
+        None
+    }
+}
+
+/// 校验后整体写入控制器参数；任一参数非法时 `pid` 保持不变并返回其编号
+///
+/// 积分项会被钳位到新的限幅内，其余运行状态保留。
+pub fn configure_pid(
+    pid: &mut PidState,
+    kp: f64,
+    ki: f64,
+    kd: f64,
+    lambda: f64,
+    integration_limit: f64,
+) -> Result<(), i32> {
+    let candidate = PidState { kp, ki, kd, lambda, integration_limit, ..*pid };
+    if let Some(field) = invalid_pid_param(&candidate) {
+        return Err(field);
+    }
+    if !(integration_limit.is_finite() && integration_limit > 0.0) {
+        return Err(PID_PARAM_INTEGRATION_LIMIT);
+    }
+
+    *pid = candidate;
+    pid.integral = pid.integral.clamp(-integration_limit, integration_limit);
+    Ok(())
+}
+
+/// 校验后写入输出平滑参数 (0 表示关闭对应功能)；非法时 `pid` 保持不变并返回字段编号
+pub fn configure_pid_output(pid: &mut PidState, max_delta_per_step: f64, deadband: f64) -> Result<(), i32> {
+    let non_negative = |v: f64| v.is_finite() && v >= 0.0;
+    if !non_negative(max_delta_per_step) {
+        return Err(PID_PARAM_MAX_DELTA);
+    }
+    if !non_negative(deadband) {
+        return Err(PID_PARAM_DEADBAND);
+    }
+    pid.max_delta_per_step = max_delta_per_step;
+    pid.deadband = deadband;
+    Ok(())
+}
+
+/// 校验后写入微分低通滤波系数，须在 (0, 1] 内；非法时 `pid` 保持不变并返回字段编号
+pub fn configure_pid_derivative_filter(pid: &mut PidState, alpha: f64) -> Result<(), i32> {
+    if !(alpha.is_finite() && alpha > 0.0 && alpha <= 1.0) {
+        return Err(PID_PARAM_DERIVATIVE_ALPHA);
+    }
+    pid.derivative_alpha = alpha;
+    Ok(())
+}
+
+/// 手动 → 自动无扰切换：反算积分项，使切回自动后的首次输出接近 `current_output`
+///
+/// 假定切换时刻误差为零，增益按零热度、零通胀评估；同时以当前流速重置微分状态，
+/// 并把 `current_output` 作为限速基准。输入非有限时 `pid` 保持不变并返回 false。
+pub fn prime_for_bumpless(pid: &mut PidState, current_output: f64, current_vel: f64) -> bool {
+    if !current_output.is_finite() || !current_vel.is_finite() {
+        return false;
+    }
+    let output = current_output.clamp(OUTPUT_MIN_CLAMP, OUTPUT_MAX_CLAMP);
+
+    let (_, base_ki) = compute_adaptive_gain(pid, 0.0);
+    let active_ki = base_ki * compute_schedule_gamma(pid, 0.0);
+    let limit = if pid.integration_limit > 0.0 { pid.integration_limit } else { DEFAULT_INTEGRATION_LIMIT };
+    pid.integral = if active_ki > MIN_TIME_STEP {
+        ((output - OUTPUT_BASELINE) / active_ki).clamp(-limit, limit)
+    } else {
+        0.0
+    };
+
+    pid.prev_pv = current_vel;
+    pid.filtered_d = 0.0;
+    pid.is_saturated = 0;
+    pid.prev_output = output;
+    true
 }
 
 // ==================== 自动化回归测试 ====================
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default, clippy::manual_range_contains)]
 mod tests {
     use super::*;
     
@@ -209,6 +356,22 @@ mod tests {
         assert!(ki3 < ki2 && ki2 < ki1, "Ki should decrease monotonically with heat (anti-windup)");
     }
 
+    #[test]
+    fn test_schedule_pivot_shifts_gain_curve() {
+        let default_pid = PidState::default();
+        let late_pid = PidState { schedule_pivot: 0.10, ..PidState::default() };
+
+        // 默认拐点处 γ = 1.5
+        assert!((compute_schedule_gamma(&default_pid, 0.05) - 1.5).abs() < 1e-12);
+        // 拐点右移后，同一通胀水平下增益更低，新拐点处回到 1.5
+        assert!(compute_schedule_gamma(&late_pid, 0.07) < compute_schedule_gamma(&default_pid, 0.07));
+        assert!((compute_schedule_gamma(&late_pid, 0.10) - 1.5).abs() < 1e-12);
+
+        // 零初始化的旧状态沿用默认曲线
+        let zeroed = PidState { schedule_pivot: 0.0, schedule_steepness: 0.0, ..PidState::default() };
+        assert_eq!(compute_schedule_gamma(&zeroed, 0.07), compute_schedule_gamma(&default_pid, 0.07));
+    }
+
     #[test]
     fn test_pid_non_finite_input_returns_baseline() {
         let mut pid = PidState::default();
@@ -219,6 +382,43 @@ mod tests {
         assert!((out2 - OUTPUT_BASELINE).abs() < 1e-6, "negative dt should return baseline");
     }
 
+    /// 简单积分型被控对象：先追踪远端目标积累积分，再将目标下调到当前值附近，
+    /// 返回切换后流速越过新目标的最大超调量
+    fn run_setpoint_change(setpoint_tolerance: f64) -> f64 {
+        let mut pid = PidState { ki: 0.5, setpoint_tolerance, ..PidState::default() };
+        let mut current = 0.0;
+        for _ in 0..10 {
+            let out = compute_pid_adjustment_internal(&mut pid, 10.0, current, 0.5, 0.0, 0.0);
+            current += (out - OUTPUT_BASELINE) * 0.5;
+        }
+        let new_target = current + 0.5;
+        let mut max_overshoot: f64 = 0.0;
+        for _ in 0..40 {
+            let out = compute_pid_adjustment_internal(&mut pid, new_target, current, 0.5, 0.0, 0.0);
+            current += (out - OUTPUT_BASELINE) * 0.5;
+            max_overshoot = max_overshoot.max(current - new_target);
+        }
+        max_overshoot
+    }
+
+    #[test]
+    fn test_setpoint_change_reduces_overshoot() {
+        let with_reset = run_setpoint_change(0.1);
+        let without_reset = run_setpoint_change(0.0);
+        assert!(with_reset < without_reset,
+            "integral reset should reduce overshoot ({} vs {})", with_reset, without_reset);
+    }
+
+    #[test]
+    fn test_small_setpoint_drift_keeps_integral() {
+        let mut pid = PidState { setpoint_tolerance: 0.1, ..PidState::default() };
+        compute_pid_adjustment_internal(&mut pid, 10.0, 0.0, 0.5, 0.0, 0.0);
+        compute_pid_adjustment_internal(&mut pid, 10.0, 0.0, 0.5, 0.0, 0.0);
+        let before = pid.integral;
+        compute_pid_adjustment_internal(&mut pid, 10.5, 0.0, 0.5, 0.0, 0.0);
+        assert!(pid.integral > before, "a 5% drift within tolerance must not reset the integral");
+    }
+
     #[test]
     fn test_validate_pid_params_accepts_default() {
         let pid = PidState::default();
@@ -231,4 +431,111 @@ mod tests {
         pid.lambda = 1.5;
         assert!(!validate_pid_params(&pid));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_configure_pid_validates_and_reports_field() {
+        let mut pid = PidState { integral: 25.0, ..PidState::default() };
+        assert_eq!(configure_pid(&mut pid, 1.0, 0.2, 0.1, 0.05, 10.0), Ok(()));
+        assert_eq!((pid.kp, pid.ki, pid.kd, pid.lambda), (1.0, 0.2, 0.1, 0.05));
+        assert_eq!(pid.integration_limit, 10.0);
+        assert_eq!(pid.integral, 10.0, "integral must be clamped to the new limit");
+
+        let before = pid;
+        assert_eq!(configure_pid(&mut pid, f64::NAN, 0.2, 0.1, 0.05, 10.0), Err(PID_PARAM_KP));
+        assert_eq!(configure_pid(&mut pid, 1.0, 0.2, -0.1, 0.05, 10.0), Err(PID_PARAM_KD));
+        assert_eq!(configure_pid(&mut pid, 1.0, 0.2, 0.1, 1.5, 10.0), Err(PID_PARAM_LAMBDA));
+        assert_eq!(configure_pid(&mut pid, 1.0, 0.2, 0.1, 0.05, 0.0), Err(PID_PARAM_INTEGRATION_LIMIT));
+        assert_eq!(pid.kp, before.kp);
+        assert_eq!(pid.integration_limit, before.integration_limit, "rejected config must not be written");
+    }
+
+    #[test]
+    fn test_output_slew_limit_ramps_step_change() {
+        let mut pid = PidState::default();
+        configure_pid_output(&mut pid, 0.1, 0.0).unwrap();
+
+        // 首次调用建立基线
+        let first = compute_pid_adjustment_internal(&mut pid, 10.0, 10.0, 0.1, 0.0, 0.0);
+
+        // 设定值阶跃：无限速时输出会直接跳到上限附近，此处每步最多变化 0.1
+        let mut prev = first;
+        for _ in 0..5 {
+            let out = compute_pid_adjustment_internal(&mut pid, 100.0, 10.0, 0.1, 0.0, 0.0);
+            assert!((out - prev).abs() <= 0.1 + 1e-12, "output jumped from {} to {}", prev, out);
+            assert!(out > prev, "output should ramp towards the new target");
+            prev = out;
+        }
+
+        let mut unlimited = PidState::default();
+        let base = compute_pid_adjustment_internal(&mut unlimited, 10.0, 10.0, 0.1, 0.0, 0.0);
+        let jump = compute_pid_adjustment_internal(&mut unlimited, 100.0, 10.0, 0.1, 0.0, 0.0);
+        assert!(jump - base > 0.1, "without a slew limit the step passes through");
+    }
+
+    #[test]
+    fn test_output_deadband_holds_previous_output() {
+        let mut pid = PidState::default();
+        configure_pid_output(&mut pid, 0.0, 0.5).unwrap();
+
+        let held = compute_pid_adjustment_internal(&mut pid, 10.0, 12.0, 0.1, 0.0, 0.0);
+        let tiny = compute_pid_adjustment_internal(&mut pid, 10.0, 10.2, 0.1, 0.0, 0.0);
+        assert_eq!(tiny, held, "errors inside the deadband must not move the output");
+
+        assert_eq!(configure_pid_output(&mut pid, -1.0, 0.0), Err(PID_PARAM_MAX_DELTA));
+        assert_eq!(configure_pid_output(&mut pid, 0.0, f64::NAN), Err(PID_PARAM_DEADBAND));
+        assert_eq!(pid.deadband, 0.5);
+    }
+
+    /// 对带交替噪声的流速序列运行控制器，返回 filtered_d 相邻步变化量之和
+    fn filtered_d_roughness(alpha: f64) -> f64 {
+        let mut pid = PidState::default();
+        configure_pid_derivative_filter(&mut pid, alpha).unwrap();
+        let mut prev_d = 0.0;
+        let mut roughness = 0.0;
+        for i in 0..40 {
+            let noise = if i % 2 == 0 { 0.5 } else { -0.5 };
+            compute_pid_adjustment_internal(&mut pid, 10.0, 10.0 + noise, 0.1, 0.0, 0.0);
+            roughness += (pid.filtered_d - prev_d).abs();
+            prev_d = pid.filtered_d;
+        }
+        roughness
+    }
+
+    #[test]
+    fn test_derivative_filter_alpha_controls_smoothing() {
+        let heavy = filtered_d_roughness(0.1);
+        let light = filtered_d_roughness(0.9);
+        assert!(heavy < light, "alpha 0.1 should smooth noise better ({} vs {})", heavy, light);
+
+        let mut pid = PidState::default();
+        assert_eq!(configure_pid_derivative_filter(&mut pid, 0.0), Err(PID_PARAM_DERIVATIVE_ALPHA));
+        assert_eq!(configure_pid_derivative_filter(&mut pid, 1.5), Err(PID_PARAM_DERIVATIVE_ALPHA));
+        assert_eq!(pid.derivative_alpha, DERIVATIVE_FILTER_ALPHA);
+
+        // 零初始化的旧状态回退为默认系数
+        assert_eq!(PidState { derivative_alpha: 0.0, ..PidState::default() }.effective_derivative_alpha(), 0.3);
+    }
+
+    #[test]
+    fn test_bumpless_transfer_keeps_first_auto_step_near_manual_output() {
+        let mut pid = PidState::default();
+        for _ in 0..20 {
+            compute_pid_adjustment_internal(&mut pid, 10.0, 8.0, 0.5, 0.0, 0.0);
+        }
+        let stale = pid;
+
+        // 管理员手动将输出压到 0.8，流速在此期间回到目标附近
+        let manual_output = 0.8;
+        let vel = 9.8;
+        assert!(prime_for_bumpless(&mut pid, manual_output, vel));
+        let primed = compute_pid_adjustment_internal(&mut pid, 9.8, vel, 0.5, 0.0, 0.0);
+        assert!((primed - manual_output).abs() < 0.05,
+            "first auto step should stay near the manual output, got {}", primed);
+
+        let mut unprimed = stale;
+        let jump = compute_pid_adjustment_internal(&mut unprimed, 9.8, vel, 0.5, 0.0, 0.0);
+        assert!((jump - manual_output).abs() > (primed - manual_output).abs());
+
+        assert!(!prime_for_bumpless(&mut pid, f64::NAN, vel));
+    }
+}
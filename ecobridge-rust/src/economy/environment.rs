@@ -5,7 +5,8 @@
 //! Environmental Factor Engine (v1.6.0 - Precision Aligned)
 //! 
 //! 本模块计算定价公式中的 Epsilon (ε) 因子。
-//! Epsilon 是一个综合乘数，包含了季节性波动、周末效应、新手保护和通胀反馈。
+//! Epsilon 是一个综合乘数，包含了季节性波动、周末效应、新手保护和通胀反馈，
+//! 以及可选的市场热度 / 饱和度项 (权重默认为 0)。
 //! 
 //! # 变更记录
 //! - [v1.6.0] 语义化对齐：适配 i64 定点数协议上下文，确保与 models.rs 兼容。
@@ -18,6 +19,14 @@ const SECONDS_PER_DAY: f64 = 86400.0;
 const SECONDS_PER_WEEK: f64 = 604800.0;
 const SECONDS_PER_MONTH: f64 = 2592000.0;
 
+/// 热度 / 饱和度 EWMA 默认平滑系数
+pub const DEFAULT_HEAT_SMOOTHING_ALPHA: f64 = 0.2;
+
+/// 抖动分桶宽度：同一小时内的交易共享同一扰动
+const JITTER_BUCKET_MS: i64 = 3_600_000;
+/// 抖动幅度上限 (±50%)
+pub const MAX_JITTER_PCT: f64 = 0.5;
+
 // ==================== 辅助数学函数 ====================
 
 /// Sigmoid 函数：用于在特定阈值附近平滑触发反馈逻辑
@@ -26,6 +35,46 @@ fn sigmoid(x: f64) -> f64 {
     1.0 / (1.0 + (-x * 10.0).exp())
 }
 
+/// SplitMix64 混合函数：无状态、可复现的 64 位伪随机数
+#[inline]
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// ==================== 本地日历状态 ====================
+
+/// 环境因子计算过程中的日历中间量 (调试用)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalCalendar {
+    /// 0 = 周一, 6 = 周日 (已应用时区偏移)
+    pub day_of_week: i32,
+    pub is_weekend: bool,
+    /// 节庆模式 (newbie_mask bit1)
+    pub festival: bool,
+}
+
+/// 本地时间戳 (秒，已应用时区偏移)
+#[inline]
+fn local_seconds(ctx: &TradeContext) -> f64 {
+    (ctx.current_timestamp as f64) / 1000.0 + ctx.timezone_offset as f64
+}
+
+/// 计算上下文对应的本地星期与节庆状态
+///
+/// `weekend_mask` 的 bit i 表示星期 i (0 = 周一) 计为周末，通常取 `MarketConfig::effective_weekend_mask()`。
+pub fn local_calendar(ctx: &TradeContext, weekend_mask: i32) -> LocalCalendar {
+    let day_index = (local_seconds(ctx) / SECONDS_PER_DAY).floor() as i64;
+    let day_of_week = (day_index + 4).rem_euclid(7) as i32; // 0=周一, 6=周日
+    LocalCalendar {
+        day_of_week,
+        is_weekend: (weekend_mask >> day_of_week) & 1 == 1,
+        festival: (ctx.newbie_mask >> 1) & 1 == 1,
+    }
+}
+
 // ==================== 核心逻辑实现 ====================
 
 /// 纯 Rust 实现的环境因子计算 (v1.6.0)
@@ -36,9 +85,8 @@ pub fn calculate_epsilon_internal(
     cfg: &MarketConfig,
 ) -> f64 {
     // 1. 时间轴对齐 (UTC -> Local)
-    let ts_sec_utc = (ctx.current_timestamp as f64) / 1000.0;
-    let offset_sec = ctx.timezone_offset as f64;
-    let ts_sec_local = ts_sec_utc + offset_sec;
+    let ts_sec_local = local_seconds(ctx);
+    let calendar = local_calendar(ctx, cfg.effective_weekend_mask());
     
     let safe_ln = |factor: f64| factor.max(0.01).ln();
 
@@ -51,20 +99,18 @@ pub fn calculate_epsilon_internal(
     let seasonal_factor = 0.6 * day_wave + 0.3 * week_wave + 0.1 * month_wave;
     let mut f_sea = 1.0 + cfg.seasonal_amplitude * seasonal_factor;
     
-    // 节庆模式 (Festival Mode)：检查位掩码 bit1
-    if (ctx.newbie_mask >> 1) & 1 == 1 {
-        f_sea *= 1.15; 
+    // 节庆模式 (Festival Mode)：检查位掩码 bit1，乘数可按活动配置 (默认 1.15)
+    if calendar.festival {
+        f_sea *= cfg.effective_festival_multiplier();
     }
 
     // 3. 周末因子 (Weekend Factor)
-    let day_index = (ts_sec_local / SECONDS_PER_DAY).floor() as i64;
-    let day_of_week = (day_index + 4).rem_euclid(7); // 0=周一, 6=周日
-    let f_wk = if day_of_week >= 5 { cfg.weekend_multiplier } else { 1.0 };
+    let f_wk = if calendar.is_weekend { cfg.weekend_multiplier } else { 1.0 };
 
     // 4. 渐进式优待因子 (Dynamic Protection Factor)
-    // 逻辑：优待随 play_time_seconds 增加而线性衰减，100小时后完全消失
+    // 逻辑：优待随 play_time_seconds 增加而线性衰减，超过 newbie_hours (默认 100 小时) 后完全消失
     let play_hours = (ctx.play_time_seconds as f64) / 3600.0;
-    let protection_decay = (1.0 - (play_hours / 100.0)).clamp(0.0, 1.0); 
+    let protection_decay = (1.0 - (play_hours / cfg.effective_newbie_hours())).clamp(0.0, 1.0);
     
     // 优待表现为价格折扣 (f_nb < 1.0)
     let f_nb = 1.0 - (cfg.newbie_protection_rate * protection_decay);
@@ -74,29 +120,99 @@ pub fn calculate_epsilon_internal(
     let sigmoid_trigger = sigmoid(ctx.inflation_rate - 0.05);
     let f_inf = 1.0 + (ctx.inflation_rate * 0.2 * sigmoid_trigger);
 
-    // 6. 对数加权合成最终 Epsilon (Geometric Mean Approximation)
+    // 6. 市场热度与饱和度 (对数压缩，负值视为 0)
+    let f_heat = 1.0 + ctx.market_heat.max(0.0);
+    let f_sat = 1.0 + ctx.eco_saturation.max(0.0);
+
+    // 7. 对数加权合成最终 Epsilon (Geometric Mean Approximation)
     let log_eps = 
           cfg.seasonal_weight   * safe_ln(f_sea)
         + cfg.weekend_weight    * safe_ln(f_wk)
         + cfg.newbie_weight     * safe_ln(f_nb)
-        + cfg.inflation_weight  * safe_ln(f_inf);
+        + cfg.inflation_weight  * safe_ln(f_inf)
+        + cfg.heat_weight       * safe_ln(f_heat)
+        + cfg.saturation_weight * safe_ln(f_sat);
 
     let mut epsilon = log_eps.exp();
 
-    // 7. 市场波动率非线性增强
+    // 8. 市场波动率非线性增强
     if cfg.volatility_factor > 1.001 {
         epsilon = 1.0 + (epsilon - 1.0) * cfg.volatility_factor;
     }
 
     // 安全阀：严禁环境因子导致价格归零或爆炸
-    epsilon.clamp(0.1, 10.0)
+    let (eps_min, eps_max) = cfg.effective_epsilon_bounds();
+    epsilon.clamp(eps_min, eps_max)
+}
+
+/// 带确定性抖动的环境因子 (A/B 实验用)
+///
+/// 扰动由 (seed, 小时桶) 经 SplitMix64 推导，相同输入必得相同结果。
+/// `jitter_pct` 会被限制在 [0, 0.5]，非有限值视为 0（不抖动）。
+pub fn calculate_epsilon_jittered(
+    ctx: &TradeContext,
+    cfg: &MarketConfig,
+    seed: u64,
+    jitter_pct: f64,
+) -> f64 {
+    let epsilon = calculate_epsilon_internal(ctx, cfg);
+    let pct = if jitter_pct.is_finite() { jitter_pct.clamp(0.0, MAX_JITTER_PCT) } else { 0.0 };
+    if pct == 0.0 {
+        return epsilon;
+    }
+
+    let bucket = ctx.current_timestamp.div_euclid(JITTER_BUCKET_MS) as u64;
+    let bits = splitmix64(seed ^ splitmix64(bucket));
+    // 取高 53 位映射到 [0, 1)，再线性映射到 [-1, 1)
+    let unit = (bits >> 11) as f64 / (1u64 << 53) as f64;
+    let noise = unit * 2.0 - 1.0;
+
+    let (eps_min, eps_max) = cfg.effective_epsilon_bounds();
+    (epsilon * (1.0 + pct * noise)).clamp(eps_min, eps_max)
+}
+
+// ==================== 热度平滑 ====================
+
+/// 对连续调用的 `market_heat` / `eco_saturation` 做 EWMA 平滑，抑制尖峰带来的 epsilon 抖动
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeatSmoother {
+    alpha: f64,
+    heat: Option<f64>,
+    saturation: Option<f64>,
+}
+
+impl HeatSmoother {
+    /// `alpha` 为新样本权重，限制在 (0, 1]；非法值回退为默认
+    pub fn new(alpha: f64) -> Self {
+        let alpha = if alpha.is_finite() && alpha > 0.0 { alpha.min(1.0) } else { DEFAULT_HEAT_SMOOTHING_ALPHA };
+        Self { alpha, heat: None, saturation: None }
+    }
+
+    /// 吸收本次上下文中的原始读数，返回替换为平滑值的上下文 (首个样本原样通过)
+    pub fn smooth(&mut self, ctx: &TradeContext) -> TradeContext {
+        let alpha = self.alpha;
+        let mix = |prev: Option<f64>, raw: f64| prev.map_or(raw, |p| p + alpha * (raw - p));
+        let heat = mix(self.heat, ctx.market_heat);
+        let saturation = mix(self.saturation, ctx.eco_saturation);
+        self.heat = Some(heat);
+        self.saturation = Some(saturation);
+        TradeContext { market_heat: heat, eco_saturation: saturation, ..*ctx }
+    }
+
+    /// 平滑后计算 epsilon
+    pub fn epsilon(&mut self, ctx: &TradeContext, cfg: &MarketConfig) -> f64 {
+        let smoothed = self.smooth(ctx);
+        calculate_epsilon_internal(&smoothed, cfg)
+    }
 }
 
 // ==================== 单元测试 ====================
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default, clippy::manual_range_contains)]
 mod tests {
     use super::*;
+    use crate::models::DEFAULT_WEEKEND_MASK;
 
     #[test]
     fn test_v1_6_progressive_protection() {
@@ -123,6 +239,84 @@ mod tests {
         assert!((eps_pro - 1.0).abs() < 1e-4);
     }
 
+    #[test]
+    fn test_heat_and_saturation_weights() {
+        let cfg = MarketConfig {
+            seasonal_weight: 0.0, weekend_weight: 0.0, newbie_weight: 0.0, inflation_weight: 0.0,
+            ..MarketConfig::default()
+        };
+        let calm = TradeContext { play_time_seconds: 200 * 3600, ..Default::default() };
+        let hot = TradeContext { market_heat: 3.0, eco_saturation: 1.0, ..calm };
+
+        // 默认权重为 0：行为不变
+        assert_eq!(calculate_epsilon_internal(&hot, &cfg), calculate_epsilon_internal(&calm, &cfg));
+
+        // 加权后热度推高 epsilon：exp(0.5 * ln 4) = 2
+        let heat_cfg = MarketConfig { heat_weight: 0.5, ..cfg };
+        assert!((calculate_epsilon_internal(&hot, &heat_cfg) - 2.0).abs() < 1e-9);
+
+        // 负权重的饱和度压低 epsilon：exp(-1 * ln 2) = 0.5
+        let sat_cfg = MarketConfig { saturation_weight: -1.0, ..cfg };
+        assert!((calculate_epsilon_internal(&hot, &sat_cfg) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_heat_smoother_damps_spikes() {
+        let cfg = MarketConfig {
+            seasonal_weight: 0.0, weekend_weight: 0.0, newbie_weight: 0.0, inflation_weight: 0.0,
+            heat_weight: 1.0,
+            ..MarketConfig::default()
+        };
+        let base = TradeContext { play_time_seconds: 200 * 3600, market_heat: 1.0, ..Default::default() };
+        let spike = TradeContext { market_heat: 9.0, ..base };
+
+        let mut smoother = HeatSmoother::new(0.25);
+        let steady = smoother.epsilon(&base, &cfg);
+        assert!((smoother.smooth(&spike).market_heat - 3.0).abs() < 1e-12);
+
+        let mut fresh = HeatSmoother::new(0.25);
+        fresh.epsilon(&base, &cfg);
+        let smoothed = fresh.epsilon(&spike, &cfg);
+        assert!(smoothed > steady && smoothed < calculate_epsilon_internal(&spike, &cfg));
+    }
+
+    #[test]
+    fn test_configurable_newbie_window() {
+        let cfg = MarketConfig {
+            newbie_protection_rate: 0.2,
+            newbie_weight: 1.0,
+            seasonal_weight: 0.0, weekend_weight: 0.0, inflation_weight: 0.0,
+            newbie_hours: 20.0,
+            ..MarketConfig::default()
+        };
+        let at_hours = |h: i64| TradeContext { play_time_seconds: h * 3600, ..Default::default() };
+
+        // 窗口过半：保留一半优待
+        assert!((calculate_epsilon_internal(&at_hours(10), &cfg) - 0.9).abs() < 1e-4);
+        assert!((calculate_epsilon_internal(&at_hours(20), &cfg) - 1.0).abs() < 1e-4);
+
+        // 零初始化回退为默认 100 小时
+        let zeroed = MarketConfig { newbie_hours: 0.0, ..cfg };
+        assert!((calculate_epsilon_internal(&at_hours(50), &zeroed) - 0.9).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_festival_multiplier_is_configurable() {
+        let cfg = MarketConfig {
+            seasonal_weight: 1.0, weekend_weight: 0.0, newbie_weight: 0.0, inflation_weight: 0.0,
+            ..MarketConfig::default()
+        };
+        let festival = TradeContext { newbie_mask: 0b10, current_timestamp: 1_700_000_000_000, ..Default::default() };
+
+        let default_eps = calculate_epsilon_internal(&festival, &cfg);
+        let major = calculate_epsilon_internal(&festival, &MarketConfig { festival_multiplier: 1.5, ..cfg });
+        assert!(major > default_eps, "1.5x festival should beat the default: {} vs {}", major, default_eps);
+
+        // 零初始化回退为默认 1.15
+        let zeroed = calculate_epsilon_internal(&festival, &MarketConfig { festival_multiplier: 0.0, ..cfg });
+        assert_eq!(zeroed, default_eps);
+    }
+
     #[test]
     fn test_seasonal_cycle_produces_valid_range() {
         let cfg = MarketConfig {
@@ -182,6 +376,30 @@ mod tests {
         assert!(eps >= 0.1 && eps <= 10.0, "epsilon must be clamped to [0.1, 10.0]");
     }
 
+    #[test]
+    fn test_epsilon_bounds_are_configurable() {
+        let extreme = MarketConfig {
+            volatility_factor: 1000.0,
+            seasonal_amplitude: 100.0,
+            ..MarketConfig::default()
+        };
+        let narrow = MarketConfig { epsilon_min: 0.5, epsilon_max: 2.0, ..extreme };
+        for ts in (0..24).map(|h| 1_000_000_000_000 + h * 3_600_000 * 37) {
+            let ctx = TradeContext { current_timestamp: ts, inflation_rate: 0.5, ..Default::default() };
+            let eps = calculate_epsilon_internal(&ctx, &narrow);
+            assert!((0.5..=2.0).contains(&eps), "epsilon {} escaped [0.5, 2.0]", eps);
+        }
+
+        let mut violations = Vec::new();
+        MarketConfig { epsilon_min: 2.0, epsilon_max: 2.0, ..MarketConfig::default() }.collect_violations(&mut violations);
+        MarketConfig { epsilon_min: -1.0, epsilon_max: 2.0, ..MarketConfig::default() }.collect_violations(&mut violations);
+        assert_eq!(violations.len(), 2, "{:?}", violations);
+
+        // 零初始化回退为默认区间
+        let zeroed = MarketConfig { epsilon_min: 0.0, epsilon_max: 0.0, ..MarketConfig::default() };
+        assert_eq!(zeroed.effective_epsilon_bounds(), (0.1, 10.0));
+    }
+
     #[test]
     fn test_inflation_feedback_triggers_above_5_percent() {
         let mut cfg = MarketConfig {
@@ -208,4 +426,101 @@ mod tests {
         let eps_high = calculate_epsilon_internal(&ctx_high, &cfg);
         assert!(eps_high > eps_low, "high inflation should produce larger epsilon");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_jittered_epsilon_is_reproducible() {
+        let cfg = MarketConfig::default();
+        let ctx = TradeContext { current_timestamp: 1_700_000_000_000, ..Default::default() };
+
+        let a = calculate_epsilon_jittered(&ctx, &cfg, 42, 0.1);
+        let b = calculate_epsilon_jittered(&ctx, &cfg, 42, 0.1);
+        assert_eq!(a.to_bits(), b.to_bits(), "same seed and inputs must match exactly");
+
+        // 同一小时桶内结果不变
+        let ctx_same_bucket = TradeContext { current_timestamp: ctx.current_timestamp + 1_000, ..ctx };
+        let base = calculate_epsilon_internal(&ctx, &cfg);
+        let base_same = calculate_epsilon_internal(&ctx_same_bucket, &cfg);
+        let c = calculate_epsilon_jittered(&ctx_same_bucket, &cfg, 42, 0.1);
+        assert!((c / base_same - a / base).abs() < 1e-12);
+
+        // 不同种子产生不同扰动
+        let d = calculate_epsilon_jittered(&ctx, &cfg, 43, 0.1);
+        assert_ne!(a.to_bits(), d.to_bits());
+    }
+
+    #[test]
+    fn test_jitter_stays_within_band() {
+        let cfg = MarketConfig::default();
+        for i in 0..200 {
+            let ctx = TradeContext {
+                current_timestamp: 1_700_000_000_000 + i * JITTER_BUCKET_MS,
+                ..Default::default()
+            };
+            let base = calculate_epsilon_internal(&ctx, &cfg);
+            let eps = calculate_epsilon_jittered(&ctx, &cfg, 7, 0.05);
+            let ratio = eps / base;
+            assert!((0.95..=1.05).contains(&ratio), "ratio {} out of ±5% band", ratio);
+        }
+
+        // 0 或非法幅度不产生抖动
+        let ctx = TradeContext { current_timestamp: 1_700_000_000_000, ..Default::default() };
+        let base = calculate_epsilon_internal(&ctx, &cfg);
+        assert_eq!(calculate_epsilon_jittered(&ctx, &cfg, 7, 0.0), base);
+        assert_eq!(calculate_epsilon_jittered(&ctx, &cfg, 7, f64::NAN), base);
+    }
+
+    #[test]
+    fn test_local_calendar_applies_timezone_and_festival() {
+        // 该时间戳在 UTC 下距跨日仅 30 分钟；UTC+8 时已进入下一日
+        let ctx = TradeContext { current_timestamp: 1_704_583_800_000, ..Default::default() };
+        let cal = local_calendar(&ctx, DEFAULT_WEEKEND_MASK);
+        assert_eq!(cal.day_of_week, 6);
+        assert!(cal.is_weekend);
+        assert!(!cal.festival);
+
+        let ctx_cn = TradeContext { timezone_offset: 8 * 3600, newbie_mask: 0b10, ..ctx };
+        let cal_cn = local_calendar(&ctx_cn, DEFAULT_WEEKEND_MASK);
+        assert_eq!(cal_cn.day_of_week, 0);
+        assert!(!cal_cn.is_weekend);
+        assert!(cal_cn.festival);
+
+        // UTC-12 时仍为同一日
+        let ctx_west = TradeContext { timezone_offset: -12 * 3600, ..ctx };
+        assert_eq!(local_calendar(&ctx_west, DEFAULT_WEEKEND_MASK).day_of_week, 6);
+    }
+
+    #[test]
+    fn test_weekend_mask_selects_weekend_days() {
+        // 连续三日，day_of_week 依次为 4 (周五)、5 (周六)、6 (周日)
+        let fri = TradeContext { current_timestamp: 1_745_481_600_000, ..Default::default() };
+        let sat = TradeContext { current_timestamp: 1_745_568_000_000, ..Default::default() };
+        let sun = TradeContext { current_timestamp: 1_745_654_400_000, ..Default::default() };
+        assert_eq!(local_calendar(&fri, 0).day_of_week, 4);
+        assert_eq!(local_calendar(&sun, 0).day_of_week, 6);
+
+        // 默认 (周六、周日)；零掩码回退为默认
+        let sat_sun = MarketConfig { weekend_mask: 0, ..MarketConfig::default() };
+        let mask = sat_sun.effective_weekend_mask();
+        assert_eq!(mask, DEFAULT_WEEKEND_MASK);
+        assert!(!local_calendar(&fri, mask).is_weekend);
+        assert!(local_calendar(&sat, mask).is_weekend);
+        assert!(local_calendar(&sun, mask).is_weekend);
+
+        // 周五、周六为周末的地区
+        let fri_sat = MarketConfig { weekend_mask: 0b011_0000, ..MarketConfig::default() };
+        let mask = fri_sat.effective_weekend_mask();
+        assert!(local_calendar(&fri, mask).is_weekend);
+        assert!(local_calendar(&sat, mask).is_weekend);
+        assert!(!local_calendar(&sun, mask).is_weekend);
+
+        // 周末乘数随掩码生效
+        let cfg = MarketConfig {
+            weekend_weight: 1.0, seasonal_weight: 0.0, newbie_weight: 0.0, inflation_weight: 0.0,
+            ..fri_sat
+        };
+        let sun_cfg = MarketConfig { weekend_mask: DEFAULT_WEEKEND_MASK, ..cfg };
+        let weekend = calculate_epsilon_internal(&sun, &sun_cfg);
+        let weekday = calculate_epsilon_internal(&sun, &cfg);
+        assert!(weekend > weekday, "Sunday is only a weekend under the Sat/Sun mask");
+    }
+}
@@ -18,6 +18,7 @@
 use std::sync::Mutex;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use crate::storage;
 
 /// ARIMA model state for a single forecast series.
 #[derive(Debug, Clone)]
@@ -40,7 +41,7 @@ pub struct ArimaState {
 
 impl ArimaState {
     pub fn new(p: usize, d: usize) -> Self {
-        assert!(p >= 1 && p <= 10, "AR order must be 1-10");
+        assert!((1..=10).contains(&p), "AR order must be 1-10");
         assert!(d <= 2, "differencing order must be 0-2");
         Self {
             p,
@@ -59,7 +60,7 @@ static ARIMA_STATES: LazyLock<Mutex<HashMap<String, ArimaState>>> =
 
 /// Initialize ARIMA predictor for a given key.
 pub fn arima_init(key: &str, p: usize, d: usize) {
-    let mut states = ARIMA_STATES.lock().unwrap();
+    let mut states = storage::lock(&ARIMA_STATES);
     let p_clamped = p.clamp(1, 10);
     let d_clamped = d.min(2);
     states.insert(key.to_string(), ArimaState::new(p_clamped, d_clamped));
@@ -67,7 +68,7 @@ pub fn arima_init(key: &str, p: usize, d: usize) {
 
 /// Add an observation and optionally re-estimate coefficients.
 pub fn arima_add_observation(key: &str, value: f64) {
-    let mut states = ARIMA_STATES.lock().unwrap();
+    let mut states = storage::lock(&ARIMA_STATES);
     let state = match states.get_mut(key) {
         Some(s) => s,
         None => return,
@@ -145,10 +146,7 @@ fn estimate_ar_coefficients(state: &mut ArimaState) {
     // r[i] = gamma(i+1)
     // Solve R * phi = r using Levinson-Durbin
 
-    let mut r = vec![0.0; p];
-    for i in 0..p {
-        r[i] = gamma(i + 1);
-    }
+    let r: Vec<f64> = (0..p).map(|i| gamma(i + 1)).collect();
 
     let g0 = gamma(0);
     if g0 <= 0.0 || !g0.is_finite() {
@@ -163,10 +161,9 @@ fn estimate_ar_coefficients(state: &mut ArimaState) {
     v *= 1.0 - phi[0] * phi[0];
 
     for k in 1..p {
-        let mut sum = 0.0;
-        for j in 0..k {
-            sum += phi[j] * gamma(k - j);
-        }
+        let sum: f64 = phi.iter().take(k).enumerate()
+            .map(|(j, &phi_j)| phi_j * gamma(k - j))
+            .sum();
         let reflection = (r[k] - sum) / v;
 
         // Update phi[0..k]
@@ -188,7 +185,7 @@ fn estimate_ar_coefficients(state: &mut ArimaState) {
 
 /// Predict H steps ahead. Returns vector of predictions.
 pub fn arima_predict(key: &str, horizon: usize) -> Vec<f64> {
-    let states = ARIMA_STATES.lock().unwrap();
+    let states = storage::lock(&ARIMA_STATES);
     let state = match states.get(key) {
         Some(s) => s,
         None => return vec![],
@@ -224,9 +221,9 @@ pub fn arima_predict(key: &str, horizon: usize) -> Vec<f64> {
         let last_raw = state.raw_history.last().copied().unwrap_or(0.0);
         for i in 0..max_horizon {
             if i == 0 {
-                forecasts[i] = last_raw + forecasts[i];
+                forecasts[i] += last_raw;
             } else {
-                forecasts[i] = forecasts[i - 1] + forecasts[i];
+                forecasts[i] += forecasts[i - 1];
             }
         }
     }
@@ -258,13 +255,13 @@ pub fn arima_predict_single(key: &str, horizon: usize) -> f64 {
 
 /// Free ARIMA state for a key.
 pub fn arima_free(key: &str) {
-    let mut states = ARIMA_STATES.lock().unwrap();
+    let mut states = storage::lock(&ARIMA_STATES);
     states.remove(key);
 }
 
 /// Get current AR coefficients (for diagnostics).
 pub fn arima_coefficients(key: &str) -> Vec<f64> {
-    let states = ARIMA_STATES.lock().unwrap();
+    let states = storage::lock(&ARIMA_STATES);
     if let Some(state) = states.get(key) {
         state.phi.clone()
     } else {
@@ -18,6 +18,7 @@
 use std::sync::Mutex;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use crate::storage;
 
 /// 3-state Kalman filter (position, velocity, acceleration).
 /// Uses constant-acceleration kinematics model.
@@ -56,20 +57,20 @@ static KALMAN_STATES: LazyLock<Mutex<HashMap<String, KalmanState>>> =
 
 /// Initialize or reset a Kalman filter state for a given key.
 pub fn kalman_init(key: &str) {
-    let mut states = KALMAN_STATES.lock().unwrap();
+    let mut states = storage::lock(&KALMAN_STATES);
     states.insert(key.to_string(), KalmanState::new(0.001, 0.01, 0.005, 0.05));
 }
 
 /// Initialize with custom noise parameters.
 pub fn kalman_init_tuned(key: &str, q_pos: f64, q_vel: f64, q_acc: f64, r: f64) {
-    let mut states = KALMAN_STATES.lock().unwrap();
+    let mut states = storage::lock(&KALMAN_STATES);
     states.insert(key.to_string(), KalmanState::new(q_pos, q_vel, q_acc, r));
 }
 
 /// Predict step: propagate state forward by dt seconds.
 /// Returns the predicted position.
 pub fn kalman_predict(key: &str, dt: f64) -> f64 {
-    let mut states = KALMAN_STATES.lock().unwrap();
+    let mut states = storage::lock(&KALMAN_STATES);
     let state = states.entry(key.to_string()).or_insert_with(|| {
         KalmanState::new(0.001, 0.01, 0.005, 0.05)
     });
@@ -116,7 +117,7 @@ pub fn kalman_predict(key: &str, dt: f64) -> f64 {
 /// Update step: incorporate a new measurement of the position.
 /// Returns the filtered (posterior) position estimate.
 pub fn kalman_update(key: &str, measurement: f64) -> f64 {
-    let mut states = KALMAN_STATES.lock().unwrap();
+    let mut states = storage::lock(&KALMAN_STATES);
     let state = states.entry(key.to_string()).or_insert_with(|| {
         KalmanState::new(0.001, 0.01, 0.005, 0.05)
     });
@@ -174,7 +175,7 @@ pub fn kalman_filter(key: &str, measurement: f64, dt: f64) -> f64 {
 
 /// Get the current filtered velocity estimate.
 pub fn kalman_velocity(key: &str) -> f64 {
-    let states = KALMAN_STATES.lock().unwrap();
+    let states = storage::lock(&KALMAN_STATES);
     if let Some(state) = states.get(key) {
         if state.initialized { state.x[1] } else { 0.0 }
     } else {
@@ -184,7 +185,7 @@ pub fn kalman_velocity(key: &str) -> f64 {
 
 /// Get the full state: [position, velocity, acceleration].
 pub fn kalman_state(key: &str) -> [f64; 3] {
-    let states = KALMAN_STATES.lock().unwrap();
+    let states = storage::lock(&KALMAN_STATES);
     if let Some(state) = states.get(key) {
         state.x
     } else {
@@ -194,7 +195,7 @@ pub fn kalman_state(key: &str) -> [f64; 3] {
 
 /// Free state for a given key.
 pub fn kalman_free(key: &str) {
-    let mut states = KALMAN_STATES.lock().unwrap();
+    let mut states = storage::lock(&KALMAN_STATES);
     states.remove(key);
 }
 
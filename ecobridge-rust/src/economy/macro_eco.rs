@@ -11,6 +11,8 @@
 //! 必须是经过标准化处理的（即：原始金额 / 1,000,000.0）。
 //! 这种“中间高精度浮点，两端定点整数”的架构确保了宏观趋势计算的平滑性。
 
+use crate::models::{HistoryRecord, MacroSnapshot};
+
 /// 计算通货膨胀率 (Inflation Rate)
 /// 
 /// 公式: ε = (当前流通热度 / M1 货币总量)
@@ -21,15 +23,55 @@
 /// * `m1_supply` - 标准化 M1 供应量 (已由 Micros 缩放)
 #[inline(always)]
 pub fn calculate_inflation_rate(current_heat: f64, m1_supply: f64) -> f64 {
+    // 硬约束: 通胀率上限 45% (高税收/高价格), 通缩率下限 -15% (补贴/底价保护)
+    calculate_inflation_rate_ex(current_heat, m1_supply, DEFAULT_INFLATION_MIN, DEFAULT_INFLATION_MAX)
+}
+
+/// 默认通缩下限
+pub const DEFAULT_INFLATION_MIN: f64 = -0.15;
+/// 默认通胀上限
+pub const DEFAULT_INFLATION_MAX: f64 = 0.45;
+
+/// 通胀钳位区间是否合法：两端有限且 `min_rate < max_rate`
+pub fn validate_inflation_bounds(min_rate: f64, max_rate: f64) -> bool {
+    min_rate.is_finite() && max_rate.is_finite() && min_rate < max_rate
+}
+
+/// 计算通货膨胀率，钳位区间可配置
+///
+/// 恶性通胀活动或紧缩型经济可放宽/收紧默认的 [-0.15, 0.45]。
+/// 区间非法 (见 `validate_inflation_bounds`) 时回退为默认区间。
+#[inline(always)]
+pub fn calculate_inflation_rate_ex(current_heat: f64, m1_supply: f64, min_rate: f64, max_rate: f64) -> f64 {
+    let (min_rate, max_rate) = if validate_inflation_bounds(min_rate, max_rate) {
+        (min_rate, max_rate)
+    } else {
+        (DEFAULT_INFLATION_MIN, DEFAULT_INFLATION_MAX)
+    };
+
     // 防御性编程: 防止由于极小货币总量导致的数值爆炸
     if m1_supply <= 1.0 { 
         return 0.0; 
     }
     
     let raw_rate = current_heat / m1_supply;
-    
-    // 硬约束: 通胀率上限 45% (高税收/高价格), 通缩率下限 -15% (补贴/底价保护)
-    raw_rate.clamp(-0.15, 0.45)
+    raw_rate.clamp(min_rate, max_rate)
+}
+
+/// 推荐回收量 (Sink)：为使通胀率回落到 `target_inflation` 需从流通热度中移除的金额
+///
+/// 由 `(current_heat - sink) / m1 == target` 反解得到 `sink = current_heat - target · m1`，
+/// 结果钳位在 [0, current_heat]。已处于目标或以下、M1 过小 (<= 1.0) 或输入非有限时返回 0.0。
+pub fn recommended_sink(current_heat: f64, m1_supply: f64, target_inflation: f64) -> f64 {
+    if !current_heat.is_finite() || !m1_supply.is_finite() || !target_inflation.is_finite() {
+        return 0.0;
+    }
+    if m1_supply <= 1.0 || current_heat <= 0.0 {
+        return 0.0;
+    }
+
+    let sink = current_heat - target_inflation * m1_supply;
+    sink.clamp(0.0, current_heat)
 }
 
 /// 计算市场稳定性因子 (Stability Factor)
@@ -75,8 +117,32 @@ pub fn calculate_stability(
 /// * `cycles_per_day` - 每日任务频率
 #[inline(always)]
 pub fn calculate_decay(current_heat: f64, daily_decay_rate: f64, cycles_per_day: f64) -> f64 {
-    // 归零逻辑：如果热度绝对值小于 1.0 标准单位，则直接返回当前值进行全量扣除
-    if current_heat.abs() < 1.0 { 
+    calculate_decay_ex(current_heat, daily_decay_rate, cycles_per_day, DEFAULT_DECAY_RESET_THRESHOLD)
+}
+
+/// 默认归零阈值 (1.0 标准单位)
+pub const DEFAULT_DECAY_RESET_THRESHOLD: f64 = 1.0;
+
+/// 计算热度自然衰减量，归零阈值可配置
+///
+/// 以大面额计价的经济体（热度以百万计）应按量级放大 `reset_threshold`，
+/// 否则残余热度永远无法触发归零而缓慢漂移。
+/// 阈值为 0 时关闭归零逻辑；负数或非有限值回退为默认阈值。
+#[inline(always)]
+pub fn calculate_decay_ex(
+    current_heat: f64,
+    daily_decay_rate: f64,
+    cycles_per_day: f64,
+    reset_threshold: f64,
+) -> f64 {
+    let threshold = if reset_threshold.is_finite() && reset_threshold >= 0.0 {
+        reset_threshold
+    } else {
+        DEFAULT_DECAY_RESET_THRESHOLD
+    };
+
+    // 归零逻辑：如果热度绝对值小于阈值，则直接返回当前值进行全量扣除
+    if current_heat.abs() < threshold { 
         return current_heat; 
     }
     
@@ -86,7 +152,121 @@ pub fn calculate_decay(current_heat: f64, daily_decay_rate: f64, cycles_per_day:
     current_heat * per_cycle_rate
 }
 
+/// 默认恢复窗口 (15 分钟)
+pub const DEFAULT_RECOVERY_WINDOW_MS: f64 = 900_000.0;
+/// 默认每日衰减任务频率
+pub const DEFAULT_CYCLES_PER_DAY: f64 = 48.0;
+
+/// 在同一时刻计算通胀率、稳定性与热度衰减，保证三者时间上一致
+///
+/// 非正的 `recovery_window_ms` / `cycles_per_day` 回退为默认值。
+pub fn compute_macro_snapshot(
+    current_heat: f64,
+    m1_supply: f64,
+    last_volatile_ts: i64,
+    current_ts: i64,
+    recovery_window_ms: f64,
+    daily_decay_rate: f64,
+    cycles_per_day: f64,
+) -> MacroSnapshot {
+    let window = if recovery_window_ms.is_finite() && recovery_window_ms > 0.0 {
+        recovery_window_ms
+    } else {
+        DEFAULT_RECOVERY_WINDOW_MS
+    };
+    let cycles = if cycles_per_day.is_finite() && cycles_per_day > 0.0 {
+        cycles_per_day
+    } else {
+        DEFAULT_CYCLES_PER_DAY
+    };
+
+    MacroSnapshot {
+        inflation: calculate_inflation_rate(current_heat, m1_supply),
+        stability: calculate_stability(last_volatile_ts, current_ts, window),
+        decay_amount: calculate_decay(current_heat, daily_decay_rate, cycles),
+    }
+}
+
+/// 计算泰尔指数 T (Theil T Index)，衡量财富集中度
+///
+/// 公式: T = (1/N) · Σ (x_i/μ) · ln(x_i/μ)
+/// 取值范围 [0, ln N]：0 表示完全平均，ln N 表示一人占有全部财富。
+/// 零余额按极限 x·ln x → 0 计入人数；负数与非有限值视为无效数据跳过。
+/// 空输入或总余额为零时返回 0.0。
+pub fn calculate_theil(balances: &[f64]) -> f64 {
+    let valid = || balances.iter().copied().filter(|b| b.is_finite() && *b >= 0.0);
+
+    let (count, total) = valid().fold((0usize, 0.0_f64), |(n, sum), b| (n + 1, sum + b));
+    if count == 0 || total <= 0.0 {
+        return 0.0;
+    }
+    let mean = total / count as f64;
+
+    let sum: f64 = valid()
+        .filter(|b| *b > 0.0)
+        .map(|b| {
+            let ratio = b / mean;
+            ratio * ratio.ln()
+        })
+        .sum();
+
+    // 浮点误差可能产生极小的负值
+    (sum / count as f64).max(0.0)
+}
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+const MICROS_SCALE: f64 = 1_000_000.0;
+
+/// 以指数加权成交量为序列的 MACD 指标
+///
+/// 逐条记录递推两条衰减累积量 S ← S·e^(-Δt/τ) + amount，并除以 τ 得到日均流量；
+/// MACD 线 = 快线流量 − 慢线流量，信号线为 MACD 线以 `signal_tau` 为时间常数的指数平滑，
+/// 柱状图 = MACD − 信号线。所有 tau 单位为天，结果在最后一条记录的时刻给出。
+///
+/// 返回 `(macd, signal, histogram)`；记录少于 2 条或参数非法 (要求 0 < fast < slow、signal > 0) 时返回全零。
+pub fn compute_macd(
+    history: &[HistoryRecord],
+    fast_tau: f64,
+    slow_tau: f64,
+    signal_tau: f64,
+) -> (f64, f64, f64) {
+    let valid = |t: f64| t.is_finite() && t > 0.0;
+    if history.len() < 2 || !valid(fast_tau) || !valid(slow_tau) || !valid(signal_tau) || fast_tau >= slow_tau {
+        return (0.0, 0.0, 0.0);
+    }
+
+    let (mut fast_sum, mut slow_sum) = (0.0_f64, 0.0_f64);
+    let mut signal = 0.0_f64;
+    let mut macd = 0.0_f64;
+    let mut prev_ts: Option<i64> = None;
+
+    for rec in history {
+        let amount = rec.amount_micros as f64 / MICROS_SCALE;
+        let dt_days = prev_ts
+            .map(|p| (rec.timestamp.saturating_sub(p).max(0)) as f64 / MS_PER_DAY)
+            .unwrap_or(0.0);
+
+        fast_sum = fast_sum * (-dt_days / fast_tau).exp() + amount;
+        slow_sum = slow_sum * (-dt_days / slow_tau).exp() + amount;
+        macd = fast_sum / fast_tau - slow_sum / slow_tau;
+
+        signal = match prev_ts {
+            None => macd,
+            Some(_) => signal + (1.0 - (-dt_days / signal_tau).exp()) * (macd - signal),
+        };
+        prev_ts = Some(rec.timestamp);
+    }
+
+    let histogram = macd - signal;
+    if macd.is_finite() && signal.is_finite() {
+        (macd, signal, histogram)
+    } else {
+        (0.0, 0.0, 0.0)
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::float_equality_without_abs)]
 mod tests {
     use super::*;
 
@@ -97,6 +277,18 @@ mod tests {
         assert_eq!(calculate_inflation_rate(5000.0, 1000.0), 0.45);
     }
 
+    #[test]
+    fn test_inflation_custom_bounds() {
+        // 原始通胀率 0.8：默认上限截断为 0.45，放宽后原样通过
+        assert_eq!(calculate_inflation_rate(800.0, 1000.0), 0.45);
+        assert!((calculate_inflation_rate_ex(800.0, 1000.0, -0.5, 1.0) - 0.8).abs() < 1e-12);
+        assert_eq!(calculate_inflation_rate_ex(-900.0, 1000.0, -0.5, 1.0), -0.5);
+
+        // 非法区间回退为默认值
+        assert!(!validate_inflation_bounds(1.0, -0.5));
+        assert_eq!(calculate_inflation_rate_ex(800.0, 1000.0, 1.0, 1.0), 0.45);
+    }
+
     #[test]
     fn test_stability_monotonicity() {
         let window = 1000.0;
@@ -114,4 +306,88 @@ mod tests {
         let large_heat = 1000.0;
         assert!(calculate_decay(large_heat, 0.48, 48.0) - 10.0 < f64::EPSILON);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_decay_custom_reset_threshold() {
+        // 低于阈值：全量归零
+        assert_eq!(calculate_decay_ex(500.0, 0.48, 48.0, 1000.0), 500.0);
+        // 高于阈值：正常按比例衰减
+        assert!((calculate_decay_ex(5000.0, 0.48, 48.0, 1000.0) - 50.0).abs() < 1e-9);
+        // 非法阈值回退为默认值
+        assert_eq!(calculate_decay_ex(0.5, 0.48, 48.0, f64::NAN), 0.5);
+    }
+
+    #[test]
+    fn test_macro_snapshot_matches_individual_calls() {
+        let snap = compute_macro_snapshot(100.0, 1000.0, 1000, 1500, 1000.0, 0.48, 48.0);
+        assert_eq!(snap.inflation, calculate_inflation_rate(100.0, 1000.0));
+        assert_eq!(snap.stability, calculate_stability(1000, 1500, 1000.0));
+        assert_eq!(snap.decay_amount, calculate_decay(100.0, 0.48, 48.0));
+    }
+
+    #[test]
+    fn test_macro_snapshot_invalid_window_falls_back() {
+        let snap = compute_macro_snapshot(100.0, 1000.0, 1000, 451_000, 0.0, 0.48, -1.0);
+        assert_eq!(snap.stability, calculate_stability(1000, 451_000, DEFAULT_RECOVERY_WINDOW_MS));
+        assert_eq!(snap.decay_amount, calculate_decay(100.0, 0.48, DEFAULT_CYCLES_PER_DAY));
+    }
+
+    #[test]
+    fn test_macd_hand_computed_series() {
+        const DAY: i64 = 86_400_000;
+        let history = [
+            HistoryRecord { timestamp: 0, amount_micros: 1_000_000 },
+            HistoryRecord { timestamp: DAY, amount_micros: 1_000_000 },
+        ];
+        let (macd, signal, hist) = compute_macd(&history, 1.0, 2.0, 1.0);
+
+        // t0: S_f = S_s = 1 → macd0 = 1/1 − 1/2 = 0.5，信号线初始化为 0.5
+        // t1: S_f = e^-1 + 1 = 1.367879，S_s = e^-0.5 + 1 = 1.606531
+        //     macd1 = 1.367879 − 0.803265 = 0.564614
+        //     signal = 0.5 + (1 − e^-1) × 0.064614 = 0.540844
+        assert!((macd - 0.564_614).abs() < 1e-6, "macd = {}", macd);
+        assert!((signal - 0.540_844).abs() < 1e-6, "signal = {}", signal);
+        assert!((hist - 0.023_770).abs() < 1e-6, "histogram = {}", hist);
+    }
+
+    #[test]
+    fn test_macd_insufficient_data_returns_zeros() {
+        let one = [HistoryRecord { timestamp: 0, amount_micros: 1_000_000 }];
+        assert_eq!(compute_macd(&one, 1.0, 2.0, 1.0), (0.0, 0.0, 0.0));
+        assert_eq!(compute_macd(&[], 1.0, 2.0, 1.0), (0.0, 0.0, 0.0));
+        let two = [one[0], HistoryRecord { timestamp: 1, amount_micros: 1 }];
+        assert_eq!(compute_macd(&two, 2.0, 1.0, 1.0), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_theil_known_vector_and_equality() {
+        // μ = 2: (0.5·ln0.5 + 1.5·ln1.5) / 2
+        let expected = (0.5 * 0.5_f64.ln() + 1.5 * 1.5_f64.ln()) / 2.0;
+        assert!((calculate_theil(&[1.0, 3.0]) - expected).abs() < 1e-12);
+
+        // 完全平均 → 0；一人独占 → ln N
+        assert!(calculate_theil(&[5.0; 8]).abs() < 1e-12);
+        assert!((calculate_theil(&[0.0, 0.0, 0.0, 10.0]) - 4.0_f64.ln()).abs() < 1e-12);
+
+        // 空输入、全零与无效数据
+        assert_eq!(calculate_theil(&[]), 0.0);
+        assert_eq!(calculate_theil(&[0.0, 0.0]), 0.0);
+        assert!((calculate_theil(&[1.0, -4.0, f64::NAN, 3.0]) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_recommended_sink() {
+        // 热度 300 / M1 1000 = 30% 通胀，目标 10% → 需回收 200
+        let sink = recommended_sink(300.0, 1000.0, 0.10);
+        assert!((sink - 200.0).abs() < 1e-9);
+        assert!((calculate_inflation_rate(300.0 - sink, 1000.0) - 0.10).abs() < 1e-12);
+
+        // 已处于目标以下 (或通缩) 时无需回收
+        assert_eq!(recommended_sink(50.0, 1000.0, 0.10), 0.0);
+        assert_eq!(recommended_sink(-100.0, 1000.0, 0.10), 0.0);
+
+        // 目标为负时回收量不超过全部热度
+        assert_eq!(recommended_sink(300.0, 1000.0, -0.5), 300.0);
+        assert_eq!(recommended_sink(300.0, 0.5, 0.10), 0.0);
+    }
+}
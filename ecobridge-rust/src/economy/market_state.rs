@@ -0,0 +1,134 @@
+// ==================================================
+// FILE: ecobridge-rust/src/economy/market_state.rs
+// ==================================================
+
+//! Market State Handle
+//!
+//! 商店界面每次打开都会为同一物品重新计算 epsilon 与 N_eff。
+//! `MarketState` 以不透明句柄的形式绑定一个市场的定价管线，
+//! 在 TTL 内复用上一次的 epsilon，N_eff 与本次交易量每次重新计算。
+//!
+//! # 实现说明
+//! 1. epsilon 为市场级缓存：基于创建时传入的上下文模板，仅替换时间戳。
+//! 2. TTL 按调用方传入的逻辑时间计算；时间回拨视为缓存失效。
+//! 3. N_eff 不进入句柄缓存：每笔成交都会立即影响下一次报价，连续抛售无法沿用首笔之前的供给量。
+
+use crate::economy::{environment, pricing, summation};
+use crate::models::{MarketConfig, TradeContext};
+
+/// 默认缓存有效期 (毫秒)
+pub const DEFAULT_MARKET_STATE_TTL_MS: i64 = 5_000;
+
+/// 上一次计算的环境因子
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedEpsilon {
+    pub computed_at: i64,
+    pub epsilon: f64,
+}
+
+/// 单次定价使用的环境因子与有效交易量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Factors {
+    pub epsilon: f64,
+    pub n_eff: f64,
+}
+
+/// 单个市场的定价管线状态
+#[derive(Debug, Clone)]
+pub struct MarketState {
+    market_key: String,
+    ctx_template: TradeContext,
+    cfg: MarketConfig,
+    tau: f64,
+    ttl_ms: i64,
+    cached: Option<CachedEpsilon>,
+}
+
+impl MarketState {
+    /// `ttl_ms <= 0` 时使用默认有效期
+    pub fn new(market_key: &str, ctx_template: TradeContext, cfg: MarketConfig, tau: f64, ttl_ms: i64) -> Self {
+        Self {
+            market_key: market_key.to_string(),
+            ctx_template,
+            cfg: cfg.sanitized(),
+            tau,
+            ttl_ms: if ttl_ms > 0 { ttl_ms } else { DEFAULT_MARKET_STATE_TTL_MS },
+            cached: None,
+        }
+    }
+
+    /// 返回 `current_ts` 时刻的 epsilon，过期时重新计算
+    pub fn epsilon(&mut self, current_ts: i64) -> CachedEpsilon {
+        if let Some(c) = self.cached {
+            let age = current_ts.saturating_sub(c.computed_at);
+            if (0..=self.ttl_ms).contains(&age) {
+                return c;
+            }
+        }
+
+        let ctx = TradeContext { current_timestamp: current_ts, ..self.ctx_template };
+        let fresh = CachedEpsilon {
+            computed_at: current_ts,
+            epsilon: environment::calculate_epsilon_internal(&ctx, &self.cfg),
+        };
+        self.cached = Some(fresh);
+        fresh
+    }
+
+    /// 缓存的 epsilon 与当前 N_eff
+    pub fn factors(&mut self, current_ts: i64) -> Factors {
+        Factors {
+            epsilon: self.epsilon(current_ts).epsilon,
+            n_eff: summation::query_neff_internal(current_ts, self.tau, &self.market_key),
+        }
+    }
+
+    /// 使用缓存的 epsilon 与当前 N_eff 计算本次交易价格
+    pub fn price(&mut self, current_ts: i64, base_micros: i64, trade_amount_micros: i64, lambda: f64) -> f64 {
+        let f = self.factors(current_ts);
+        pricing::compute_price_humane_internal(base_micros, f.n_eff, trade_amount_micros, lambda, f.epsilon)
+    }
+
+    /// 丢弃缓存，下次调用强制重新计算
+    pub fn invalidate(&mut self) {
+        self.cached = None;
+    }
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_state_reuses_epsilon_until_ttl() {
+        let key = "test_market_state_item";
+        let t0 = 1_700_000_000_000i64;
+        let mut state = MarketState::new(key, TradeContext::default(), MarketConfig::default(), 7.0, 5_000);
+        let cached = state.epsilon(t0);
+
+        assert_eq!(state.epsilon(t0 + 2_000), cached);
+        assert_eq!(state.epsilon(t0 + 20_000).computed_at, t0 + 20_000);
+
+        // 时间回拨视为失效
+        assert_eq!(state.epsilon(t0).computed_at, t0);
+    }
+
+    #[test]
+    fn test_market_state_sees_appends_within_ttl() {
+        let _state = crate::test_support::shared();
+        let key = "test_market_state_dump";
+        let t0 = crate::test_support::isolated_ts();
+        summation::append_trade_to_memory(t0 - 1_000, 100.0, key);
+
+        let mut state = MarketState::new(key, TradeContext::default(), MarketConfig::default(), 7.0, 5_000);
+        let first = state.price(t0, 1_000_000, 0, 0.01);
+
+        // TTL 内的抛售立即压低下一次报价
+        summation::append_trade_to_memory(t0 + 500, 5_000.0, key);
+        let second = state.price(t0 + 1_000, 1_000_000, 0, 0.01);
+        assert!(second < first, "append inside the TTL must move the price: {} vs {}", second, first);
+        assert_eq!(state.epsilon(t0 + 1_000).computed_at, t0);
+    }
+}
@@ -2,6 +2,7 @@
 // FILE: ecobridge-rust/src/economy/mod.rs (v1.7.0)
 // ==================================================
 
+pub mod analytics;
 pub mod control;
 pub mod environment;
 
@@ -16,8 +17,12 @@ pub mod macro_eco;
 #[path = "mpc.rs"]
 pub mod mpc;
 
+pub mod portfolio;
+
 pub mod pricing;
 
+pub mod settlement;
+
 #[path = "summation.rs"]
 pub mod summation;
 
@@ -51,12 +56,14 @@ pub use summation::{
     query_neff_internal
 };
 
+pub use analytics::estimate_elasticity;
+
 // -----------------------------------------------------------------------------
 // 默认参数定义
 // -----------------------------------------------------------------------------
 pub const DEFAULT_LAMBDA: f64 = 0.01;
 pub const DEFAULT_TAU: f64 = 7.0;
-pub const MIN_PHYSICAL_PRICE: f64 = 0.01;
+pub const MIN_PHYSICAL_PRICE: f64 = pricing::DEFAULT_MIN_PRICE;
 
 #[inline]
 pub fn get_default_params() -> (f64, f64) {
@@ -29,6 +29,7 @@
 use std::sync::Mutex;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use crate::storage;
 
 // ==================== MPC State ====================
 
@@ -74,17 +75,18 @@ const _DEFAULT_KEY: &str = "__global__";
 // ==================== Public API ====================
 
 pub fn mpc_init(key: &str, horizon: usize) {
-    let mut states = MPC_STATES.lock().unwrap();
-    let mut state = MpcState::default();
-    state.horizon = horizon.clamp(4, 48);
-    states.insert(key.to_string(), state);
+    let mut states = storage::lock(&MPC_STATES);
+    states.insert(key.to_string(), MpcState {
+        horizon: horizon.clamp(4, 48),
+        ..MpcState::default()
+    });
 }
 
 pub fn mpc_init_tuned(
     key: &str, horizon: usize,
     w_target: f64, w_price: f64, w_inflation: f64, w_effort: f64,
 ) {
-    let mut states = MPC_STATES.lock().unwrap();
+    let mut states = storage::lock(&MPC_STATES);
     states.insert(key.to_string(), MpcState {
         horizon: horizon.clamp(4, 48),
         w_target, w_price, w_inflation, w_effort,
@@ -94,6 +96,7 @@ pub fn mpc_init_tuned(
 
 /// Compute optimal controls given current economic state.
 /// Returns the recommended lambda/sink/faucet adjustments for THIS step.
+#[allow(clippy::too_many_arguments)]
 pub fn mpc_optimize(
     key: &str,
     m1_ratio: f64,          // current M1 / targetM1
@@ -104,7 +107,7 @@ pub fn mpc_optimize(
     target_m1: f64,         // target M1 supply
     dt_seconds: f64,        // time step
 ) -> MpcResult {
-    let states = MPC_STATES.lock().unwrap();
+    let states = storage::lock(&MPC_STATES);
     let cfg = match states.get(key) {
         Some(s) => s.clone(),
         None => MpcState::default(),
@@ -193,6 +196,7 @@ pub fn mpc_optimize(
 /// Forward simulation of the economy under a control sequence.
 /// Returns ([trajectory_states], total_cost).
 /// Each state is [m1_ratio, price_index, inflation].
+#[allow(clippy::too_many_arguments)]
 fn simulate_trajectory(
     m1_ratio: f64,
     price_index: f64,
@@ -261,7 +265,7 @@ fn simulate_trajectory(
 }
 
 pub fn mpc_free(key: &str) {
-    let mut states = MPC_STATES.lock().unwrap();
+    let mut states = storage::lock(&MPC_STATES);
     states.remove(key);
 }
 
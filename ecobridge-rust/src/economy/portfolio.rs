@@ -0,0 +1,117 @@
+// ==================================================
+// FILE: ecobridge-rust/src/economy/portfolio.rs
+// ==================================================
+
+//! Portfolio Valuation Module
+//!
+//! 计算玩家持仓 (物品数量向量) 在当前价格下的总价值，用于包含物品资产的财富排行。
+//!
+//! # 实现说明
+//! 1. 点积在 x86_64 + AVX2 下向量化执行，其余平台走标量路径。
+//! 2. 任一侧为 NaN/Inf 的条目被视为 0，避免单个脏数据污染整体估值。
+//! 3. 清算估值版本对大额持仓套用阶梯定价，模拟"一次性全部卖出"带来的价格冲击。
+
+use crate::economy::pricing::compute_tier_price_internal;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// 持仓按当前价格的简单估值 (数量 · 价格 点积)
+pub fn portfolio_value(quantities: &[f64], prices: &[f64]) -> f64 {
+    let len = quantities.len().min(prices.len());
+    let (quantities, prices) = (&quantities[..len], &prices[..len]);
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        return unsafe { dot_product_avx2(quantities, prices) };
+    }
+
+    dot_product_scalar(quantities, prices)
+}
+
+/// 清算估值：每个条目按阶梯定价的平均成交价计价，大额持仓因此低于简单估值
+pub fn portfolio_value_with_liquidation(quantities: &[f64], prices: &[f64]) -> f64 {
+    quantities.iter()
+        .zip(prices)
+        .filter(|(q, p)| q.is_finite() && p.is_finite())
+        .map(|(&q, &p)| q * compute_tier_price_internal(p, q, true))
+        .sum()
+}
+
+#[inline]
+fn dot_product_scalar(quantities: &[f64], prices: &[f64]) -> f64 {
+    quantities.iter()
+        .zip(prices)
+        .filter(|(q, p)| q.is_finite() && p.is_finite())
+        .map(|(q, p)| q * p)
+        .sum()
+}
+
+/// AVX2 点积：通过 `x - x == 0` 在寄存器内屏蔽非有限值 (NaN/Inf 相减得 NaN)
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn dot_product_avx2(quantities: &[f64], prices: &[f64]) -> f64 {
+    let mut sum_vec = _mm256_setzero_pd();
+    let zero = _mm256_setzero_pd();
+
+    let q_chunks = quantities.chunks_exact(4);
+    let p_chunks = prices.chunks_exact(4);
+    let q_rem = q_chunks.remainder();
+    let p_rem = p_chunks.remainder();
+
+    for (q, p) in q_chunks.zip(p_chunks) {
+        let v_q = _mm256_loadu_pd(q.as_ptr());
+        let v_p = _mm256_loadu_pd(p.as_ptr());
+
+        let q_finite = _mm256_cmp_pd::<_CMP_EQ_OQ>(_mm256_sub_pd(v_q, v_q), zero);
+        let p_finite = _mm256_cmp_pd::<_CMP_EQ_OQ>(_mm256_sub_pd(v_p, v_p), zero);
+        let mask = _mm256_and_pd(q_finite, p_finite);
+
+        let product = _mm256_and_pd(_mm256_mul_pd(v_q, v_p), mask);
+        sum_vec = _mm256_add_pd(sum_vec, product);
+    }
+
+    let mut temp = [0.0f64; 4];
+    _mm256_storeu_pd(temp.as_mut_ptr(), sum_vec);
+    let total = temp[0] + temp[1] + temp[2] + temp[3];
+
+    total + dot_product_scalar(q_rem, p_rem)
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portfolio_value_matches_hand_calculation() {
+        // 7 个条目：覆盖 4 路向量块与标量余数
+        let quantities = [10.0, 2.0, 0.0, 5.0, 1.0, 3.0, 4.0];
+        let prices = [1.5, 20.0, 99.0, 0.2, 7.0, 2.0, 0.25];
+        let expected = 10.0 * 1.5 + 2.0 * 20.0 + 5.0 * 0.2 + 7.0 + 3.0 * 2.0 + 4.0 * 0.25;
+        assert!((portfolio_value(&quantities, &prices) - expected).abs() < 1e-9);
+        assert!((dot_product_scalar(&quantities, &prices) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_non_finite_entries_ignored() {
+        let quantities = [1.0, f64::NAN, 2.0, 3.0, f64::INFINITY];
+        let prices = [10.0, 10.0, f64::NEG_INFINITY, 1.0, 1.0];
+        let value = portfolio_value(&quantities, &prices);
+        assert!((value - 13.0).abs() < 1e-9, "non-finite pairs must contribute zero, got {}", value);
+    }
+
+    #[test]
+    fn test_liquidation_value_lower_for_large_holdings() {
+        let prices = [10.0, 4.0];
+
+        let small = [100.0, 50.0];
+        assert!((portfolio_value_with_liquidation(&small, &prices) - portfolio_value(&small, &prices)).abs() < 1e-9,
+            "holdings below the first tier should not be discounted");
+
+        let large = [3000.0, 5000.0];
+        assert!(portfolio_value_with_liquidation(&large, &prices) < portfolio_value(&large, &prices),
+            "dumping a large holding should be valued below the simple dot product");
+    }
+}
@@ -4,17 +4,40 @@
 // [v1.7.0] Recovery & Adaptive Tau: added mean-reversion with integral memory
 // for sustained price stability under chronic oversupply (shop收购 > 玩家购买).
 
-use crate::models::{TradeContext, MarketConfig};
+use crate::models::{MarketConfig, PriceTrace, TradeContext};
 use rayon::prelude::*;
 use crate::economy::environment;
 use crate::economy::volatility;
-use std::sync::Mutex;
+use crate::storage;
+use std::sync::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 
 /// 精度缩放常量 (1.0 = 1,000,000 Micros)
 const MICROS_SCALE: f64 = 1_000_000.0;
 
+/// 默认绝对硬底线 (0.01 货币单位)
+pub const DEFAULT_MIN_PRICE: f64 = 0.01;
+
+/// 可配置的绝对硬底线 (以 f64 位模式存储)
+static MIN_PRICE_BITS: AtomicU64 = AtomicU64::new(DEFAULT_MIN_PRICE.to_bits());
+
+/// 设置绝对硬底线；非有限或非正值被拒绝并返回 false
+pub fn set_min_price(min: f64) -> bool {
+    if !min.is_finite() || min <= 0.0 {
+        return false;
+    }
+    MIN_PRICE_BITS.store(min.to_bits(), Ordering::Relaxed);
+    true
+}
+
+/// 当前绝对硬底线
+#[inline]
+pub fn min_price() -> f64 {
+    f64::from_bits(MIN_PRICE_BITS.load(Ordering::Relaxed))
+}
+
 // ==================== Recovery State (v1.7.0) ====================
 
 /// Per-item recovery integral state — tracks how long price has been suppressed.
@@ -56,76 +79,292 @@ fn compute_price_behavioral_core(
     lambda: f64,
     epsilon: f64,
 ) -> f64 {
+    compute_price_core_ex(
+        base_price_micros, n_eff, trade_amount_micros, lambda, epsilon,
+        DEFAULT_EXPONENT_CLAMP, DEFAULT_TANH_SCALE,
+    )
+}
+
+/// 指数硬限幅的默认对称边界 [-100, 100]
+pub const DEFAULT_EXPONENT_CLAMP: f64 = 100.0;
+/// tanh 软限幅的默认尺度 (指数最终被压缩到 ±10 以内)
+pub const DEFAULT_TANH_SCALE: f64 = 10.0;
+/// tanh 尺度上限：exp(±300) 仍远离 f64 溢出
+pub const MAX_TANH_SCALE: f64 = 300.0;
+
+/// 校验限幅参数：硬限幅为正 (对称区间 [-clamp, clamp])，软限幅尺度在 (0, 300]
+pub fn validate_exponent_clamp(exponent_clamp: f64, tanh_scale: f64) -> bool {
+    exponent_clamp.is_finite() && exponent_clamp > 0.0
+        && tanh_scale.is_finite() && tanh_scale > 0.0 && tanh_scale <= MAX_TANH_SCALE
+}
+
+/// 可调限幅的行为定价核心
+///
+/// 货币量级较大的经济体可放宽 `tanh_scale`，使大额成交的价格响应更陡峭；
+/// 参数非法时回退为默认值 (100 / 10)。
+pub fn compute_price_core_ex(
+    base_price_micros: i64,
+    n_eff: f64,
+    trade_amount_micros: i64,
+    lambda: f64,
+    epsilon: f64,
+    exponent_clamp: f64,
+    tanh_scale: f64,
+) -> f64 {
+    trace_price_core_ex(
+        base_price_micros, n_eff, trade_amount_micros, lambda, epsilon, exponent_clamp, tanh_scale,
+    ).final_price
+}
+
+/// 默认限幅下行为定价核心的逐步中间量 (诊断用，`final_price` 与 `compute_price_humane_internal` 一致)
+pub fn trace_price(
+    base_price_micros: i64,
+    n_eff: f64,
+    trade_amount_micros: i64,
+    lambda: f64,
+    epsilon: f64,
+) -> PriceTrace {
+    trace_price_core_ex(
+        base_price_micros, n_eff, trade_amount_micros, lambda, epsilon,
+        DEFAULT_EXPONENT_CLAMP, DEFAULT_TANH_SCALE,
+    )
+}
+
+/// 行为定价核心的实现，记录每一步中间量；输入非有限时中间量为 0，价格为硬底线
+fn trace_price_core_ex(
+    base_price_micros: i64,
+    n_eff: f64,
+    trade_amount_micros: i64,
+    lambda: f64,
+    epsilon: f64,
+    exponent_clamp: f64,
+    tanh_scale: f64,
+) -> PriceTrace {
+    let (exponent_clamp, tanh_scale) = if validate_exponent_clamp(exponent_clamp, tanh_scale) {
+        (exponent_clamp, tanh_scale)
+    } else {
+        (DEFAULT_EXPONENT_CLAMP, DEFAULT_TANH_SCALE)
+    };
+    let mut trace = PriceTrace { epsilon, n_eff, ..PriceTrace::default() };
+
     // 1. 数据转换与安全性检查
     let base_price_f64 = (base_price_micros as f64) / MICROS_SCALE;
     let trade_amount_f64 = (trade_amount_micros as f64) / MICROS_SCALE;
 
     if !base_price_f64.is_finite() || !n_eff.is_finite() ||
        !lambda.is_finite() || !epsilon.is_finite() {
-        return 0.01;
+        trace.final_price = min_price();
+        return trace;
     }
 
     // 2. 非对称灵敏度 (Asymmetric Sensitivity)
     // 逻辑：卖出物品时灵敏度降低(0.6x)，模拟”价格下行粘性”
-    let adj_lambda = if trade_amount_micros > 0 {
+    trace.adj_lambda = if trade_amount_micros > 0 {
         lambda * 0.6
     } else {
         lambda
     };
 
     // 3. 计算总有效供应量冲击
-    let total_n = n_eff + trade_amount_f64;
+    trace.total_n = n_eff + trade_amount_f64;
 
     // 4. 指数演算与平滑限幅 (Soft Clamping)
-    let raw_exponent = (-adj_lambda * total_n).clamp(-100.0, 100.0);
+    trace.raw_exponent = (-trace.adj_lambda * trace.total_n).clamp(-exponent_clamp, exponent_clamp);
 
     // 使用 tanh 确保价格曲线在极端工业产出下平滑逼近底价，不会突变为 0
-    let clamped_exponent = 10.0 * (raw_exponent / 10.0).tanh();
+    trace.clamped_exponent = tanh_scale * (trace.raw_exponent / tanh_scale).tanh();
 
-    let final_price = base_price_f64 * epsilon * clamped_exponent.exp();
+    let final_price = base_price_f64 * epsilon * trace.clamped_exponent.exp();
 
-    // 5. 绝对硬底线 (0.01 货币单位)
-    final_price.max(0.01)
+    // 5. 绝对硬底线 (默认 0.01 货币单位，可配置)
+    trace.final_price = final_price.max(min_price());
+    trace
 }
 
 // -----------------------------------------------------------------------------
 // 2. 阶梯定价与底价保护 (Defense Layers)
 // -----------------------------------------------------------------------------
 
+// 阶梯分档：0-500 件为第一档，501-2000 件为第二档，其余为第三档
+const TIER_1_SIZE: f64 = 500.0;
+const TIER_2_SIZE: f64 = 1500.0;
+const SELL_TIER_MULTIPLIERS: (f64, f64) = (0.85, 0.60);
+
+/// 大宗买入第二、三档的默认溢价乘数 (1.0 = 不溢价，保持旧行为)
+pub const DEFAULT_BUY_TIER_MULTIPLIERS: (f64, f64) = (1.0, 1.0);
+
+static BUY_TIER_MULTIPLIERS: RwLock<(f64, f64)> = RwLock::new(DEFAULT_BUY_TIER_MULTIPLIERS);
+
+/// 配置大宗买入的阶梯溢价 (例如 1.15 / 1.4)；要求 1.0 <= tier2 <= tier3，否则返回 false
+pub fn set_buy_tier_multipliers(tier2: f64, tier3: f64) -> bool {
+    if !tier2.is_finite() || !tier3.is_finite() || tier2 < 1.0 || tier3 < tier2 {
+        return false;
+    }
+    if let Ok(mut m) = BUY_TIER_MULTIPLIERS.write() {
+        *m = (tier2, tier3);
+    }
+    true
+}
+
+/// 当前大宗买入阶梯乘数 (第二档, 第三档)
+pub fn buy_tier_multipliers() -> (f64, f64) {
+    BUY_TIER_MULTIPLIERS.read().map(|m| *m).unwrap_or(DEFAULT_BUY_TIER_MULTIPLIERS)
+}
+
 /// 计算阶梯定价 (Tier Pricing)
-/// 针对单笔超大量物品售出的防御性降价逻辑
+/// 卖出：超大量售出的防御性降价；买入：按配置的溢价模拟大宗采购的稀缺压力
 #[inline]
 pub fn compute_tier_price_internal(
     base_price: f64, 
     quantity_f64: f64, 
     is_sell: bool
 ) -> f64 {
-    // 只有卖出且物品数量超过 500 件时触发阶梯折扣
-    if !is_sell || quantity_f64 <= 500.0 || quantity_f64 <= 0.0 {
+    let multipliers = if is_sell { SELL_TIER_MULTIPLIERS } else { buy_tier_multipliers() };
+    compute_tier_price_with(base_price, quantity_f64, multipliers)
+}
+
+/// 以给定的第二、三档乘数计算阶梯均价 (默认三档表的便捷封装)
+pub fn compute_tier_price_with(base_price: f64, quantity_f64: f64, multipliers: (f64, f64)) -> f64 {
+    let (tier2, tier3) = multipliers;
+    tier_average_price(
+        base_price,
+        quantity_f64,
+        &[TIER_1_SIZE, TIER_1_SIZE + TIER_2_SIZE],
+        &[1.0, tier2, tier3],
+    )
+}
+
+/// 校验阶梯表：`breakpoints` 为严格递增的正累计件数，`rates` 比 `breakpoints` 多一项
+/// (最后一档无上限)。卖出各档乘数须在 (0, 1] 且逐档不升；买入须 >= 1 且逐档不降。
+pub fn validate_tier_table(breakpoints: &[f64], rates: &[f64], is_sell: bool) -> bool {
+    if rates.len() != breakpoints.len() + 1 {
+        return false;
+    }
+    let ascending = breakpoints.iter().all(|b| b.is_finite() && *b > 0.0)
+        && breakpoints.windows(2).all(|w| w[0] < w[1]);
+    let rates_ok = if is_sell {
+        rates.iter().all(|r| r.is_finite() && *r > 0.0 && *r <= 1.0) && rates.windows(2).all(|w| w[1] <= w[0])
+    } else {
+        rates.iter().all(|r| r.is_finite() && *r >= 1.0) && rates.windows(2).all(|w| w[1] >= w[0])
+    };
+    ascending && rates_ok
+}
+
+/// 任意档数的阶梯均价：第 i 档覆盖 (breakpoints[i-1], breakpoints[i]] 件，按 `base × rates[i]` 计价。
+/// 阶梯表非法时不打折，直接返回 `base_price`。
+pub fn compute_tier_price_table(
+    base_price: f64,
+    quantity_f64: f64,
+    is_sell: bool,
+    breakpoints: &[f64],
+    rates: &[f64],
+) -> f64 {
+    if !validate_tier_table(breakpoints, rates, is_sell) {
+        return base_price;
+    }
+    tier_average_price(base_price, quantity_f64, breakpoints, rates)
+}
+
+/// 阶梯均价核心 (调用方保证 `rates.len() == breakpoints.len() + 1`)
+fn tier_average_price(base_price: f64, quantity_f64: f64, breakpoints: &[f64], rates: &[f64]) -> f64 {
+    if quantity_f64.is_nan() || quantity_f64 <= 0.0 {
         return base_price;
     }
 
     let mut total_value = 0.0;
-    let mut remaining = quantity_f64;
+    let mut lower = 0.0;
+    for (i, rate) in rates.iter().enumerate() {
+        let upper = breakpoints.get(i).copied().unwrap_or(f64::INFINITY);
+        let portion = quantity_f64.min(upper) - lower;
+        if portion <= 0.0 {
+            break;
+        }
+        total_value += portion * (base_price * rate);
+        lower = upper;
+    }
+
+    total_value / quantity_f64
+}
 
-    // Tier 1: 0 - 500 件 (100% 原始演算价)
-    let t1 = remaining.min(500.0);
-    total_value += t1 * base_price;
-    remaining -= t1;
+// ==================== 抛售冷却 (Dump Cooldown) ====================
 
-    // Tier 2: 501 - 2000 件 (85% 折扣价)
-    if remaining > 0.0 {
-        let t2 = remaining.min(1500.0);
-        total_value += t2 * (base_price * 0.85);
-        remaining -= t2;
+/// 冷却期内每次连续大额抛售追加的折扣乘数
+const DUMP_DISCOUNT_STEP: f64 = 0.9;
+/// 连续抛售折扣的下限 (最多压到原价的 50%)
+const MIN_DUMP_MULTIPLIER: f64 = 0.5;
+/// 类别表达到该规模时，在下一次写入前清除已过冷却期的条目
+const DUMP_COOLDOWN_PRUNE_LEN: usize = 1024;
+
+/// 每个物品类别最近一次大额抛售的时间与冷却期内的连续次数
+#[derive(Debug, Clone, Copy)]
+struct DumpCooldown {
+    last_ts: i64,
+    streak: i32,
+}
+
+static DUMP_COOLDOWNS: LazyLock<RwLock<HashMap<String, DumpCooldown>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 大额抛售冷却：调用方仅在判定为大额卖出时调用，本次抛售会被记录。
+///
+/// 若同类别上一次大额抛售距今不超过 `cooldown_ms`，连续次数加一，价格乘以
+/// 0.9^次数 (不低于 0.5 倍与全局硬底线)；否则重新计数、价格不变。
+/// 时间回拨视为仍在冷却期内。类别表超过 `DUMP_COOLDOWN_PRUNE_LEN` 时顺带清除已过期的类别
+/// (过期条目与不存在等价，清除不影响结果)。
+pub fn apply_dump_cooldown(price: f64, category: &str, ts: i64, cooldown_ms: i64) -> f64 {
+    if !price.is_finite() {
+        return min_price();
     }
+    let streak = {
+        let mut states = DUMP_COOLDOWNS.write().unwrap_or_else(|e| e.into_inner());
+        if states.len() >= DUMP_COOLDOWN_PRUNE_LEN {
+            states.retain(|_, s| ts.saturating_sub(s.last_ts) <= cooldown_ms.max(0));
+        }
+        let state = states.entry(category.to_string()).or_insert(DumpCooldown { last_ts: i64::MIN, streak: 0 });
+        let within = ts.saturating_sub(state.last_ts) <= cooldown_ms.max(0);
+        state.streak = if within { state.streak.saturating_add(1) } else { 0 };
+        state.last_ts = state.last_ts.max(ts);
+        state.streak
+    };
+    if streak == 0 {
+        return price;
+    }
+    let multiplier = DUMP_DISCOUNT_STEP.powi(streak).max(MIN_DUMP_MULTIPLIER);
+    (price * multiplier).max(min_price())
+}
+
+// ==================== 开服软启动 (Soft Start) ====================
+
+/// 进程启动 (初始化) 时刻，毫秒；0 表示尚未记录，软启动不生效
+static BOOT_TS: AtomicI64 = AtomicI64::new(0);
 
-    // Tier 3: 2000 件以上 (60% 深度折扣)
-    if remaining > 0.0 {
-        total_value += remaining * (base_price * 0.60);
+/// 记录启动时刻 (由初始化 FFI 调用)
+pub fn record_boot_ts(ts: i64) {
+    BOOT_TS.store(ts, Ordering::Relaxed);
+}
+
+/// 已记录的启动时刻
+pub fn boot_ts() -> Option<i64> {
+    match BOOT_TS.load(Ordering::Relaxed) {
+        0 => None,
+        ts => Some(ts),
     }
+}
 
-    total_value / quantity_f64
+/// 开服软启动：启动后 `ramp_ms` 内将动态价格向 `base` 线性混合。
+///
+/// 混合权重在启动时为 0 (完全使用基准价)，经过 `ramp_ms` 后为 1 (完全使用动态价)；
+/// `ramp_ms <= 0` 或基准价非有限时直接返回动态价，动态价非有限时返回基准价。
+pub fn apply_soft_start(price: f64, base: f64, boot_ts: i64, current_ts: i64, ramp_ms: i64) -> f64 {
+    if ramp_ms <= 0 || !base.is_finite() {
+        return price;
+    }
+    if !price.is_finite() {
+        return base;
+    }
+    let elapsed = current_ts.saturating_sub(boot_ts);
+    let weight = (elapsed as f64 / ramp_ms as f64).clamp(0.0, 1.0);
+    weight.mul_add(price - base, base)
 }
 
 /// Apply mean-reversion recovery: pull prices back toward hist_avg when suppressed.
@@ -151,13 +390,13 @@ fn apply_recovery_pull(
     }
 
     // Single lock + single HashMap access via entry()
-    let mut states = RECOVERY_STATES.lock().unwrap();
+    let mut states = storage::lock(&RECOVERY_STATES);
     let state = states.entry("__global__".into()).or_insert_with(|| RecoveryIntegral {
         accumulated_deficit: 0.0,
         last_update_ts: current_ts,
     });
 
-    let deficit = ((target_price - raw_price) / target_price.max(0.01)).clamp(0.0, 1.0);
+    let deficit = ((target_price - raw_price) / target_price.max(min_price())).clamp(0.0, 1.0);
     state.accumulated_deficit = (state.accumulated_deficit + deficit * RECOVERY_INTEGRAL_GAIN)
         .min(RECOVERY_MAX_INTEGRAL);
     state.last_update_ts = current_ts;
@@ -169,6 +408,12 @@ fn apply_recovery_pull(
     (raw_price + moderated_recovery, true)
 }
 
+/// 有界定价的底价：历史均价的 62%，随波动率放宽，且不低于硬底线
+#[inline]
+fn bounded_floor(hist_avg: f64, vol_mult: f64) -> f64 {
+    (hist_avg * 0.62 * vol_mult).max(min_price())
+}
+
 /// 包含动态底价保护 + 均值回归恢复的最终价格演算 (v1.7.0)
 /// [v2.0] vol_mult pre-computed per batch; recovery uses static key (no alloc).
 pub fn compute_price_bounded_internal(
@@ -177,7 +422,7 @@ pub fn compute_price_bounded_internal(
 ) -> f64 {
     let raw_price = compute_price_behavioral_core(base_micros, n_eff, amt_micros, lambda, eps);
     let vol_mult = volatility::garch_volatility_multiplier("__global__");
-    let floor = (hist_avg * 0.62 * vol_mult).max(0.01);
+    let floor = bounded_floor(hist_avg, vol_mult);
 
     let mut price = raw_price.max(floor);
     let (recovered, _active) = apply_recovery_pull(price, hist_avg, vol_mult, 0);
@@ -186,13 +431,47 @@ pub fn compute_price_bounded_internal(
     price
 }
 
+/// 价格带保护：在底价之外增加对称的软上限，防止抢购潮把价格推到荒谬水平
+///
+/// floor = max(hist_avg × floor_frac, min_price)，ceiling = hist_avg × ceil_mult。
+/// 若上限低于底价 (hist_avg 缺失等)，以底价为准。
+#[allow(clippy::too_many_arguments)]
+pub fn compute_price_with_band(
+    base_micros: i64, n_eff: f64, amt_micros: i64, lambda: f64, eps: f64,
+    hist_avg: f64, floor_frac: f64, ceil_mult: f64,
+) -> f64 {
+    let raw_price = compute_price_behavioral_core(base_micros, n_eff, amt_micros, lambda, eps);
+    let floor = (hist_avg * floor_frac).max(min_price());
+    let ceiling = (hist_avg * ceil_mult).max(floor);
+    raw_price.clamp(floor, ceiling)
+}
+
+/// 线性定价模式：price = clamp(base × (1 − λ × (n_eff + amount)), floor, ceil)
+///
+/// 与行为核心相比没有非对称灵敏度、epsilon 与 tanh 软限幅，每件成交对价格的影响恒定，
+/// 便于管理员解释；代价是大额成交会直接撞到底价，而不是平滑逼近。
+/// floor 不低于全局硬底线，ceil 低于 floor 时以 floor 为准；非有限输入返回硬底线。
+pub fn compute_price_linear(
+    base_micros: i64, n_eff: f64, amt_micros: i64, lambda: f64, floor: f64, ceil: f64,
+) -> f64 {
+    let base = (base_micros as f64) / MICROS_SCALE;
+    let amount = (amt_micros as f64) / MICROS_SCALE;
+    let floor = if floor.is_finite() { floor.max(min_price()) } else { min_price() };
+    if !n_eff.is_finite() || !lambda.is_finite() {
+        return floor;
+    }
+    let ceiling = if ceil.is_nan() { floor } else { ceil.max(floor) };
+    let price = base * (1.0 - lambda * (n_eff + amount));
+    if price.is_finite() { price.clamp(floor, ceiling) } else { floor }
+}
+
 #[inline]
 pub fn compute_price_bounded_internal_cached(
     base_micros: i64, n_eff: f64, amt_micros: i64, lambda: f64, eps: f64,
     hist_avg: f64, vol_mult: f64
 ) -> f64 {
     let raw_price = compute_price_behavioral_core(base_micros, n_eff, amt_micros, lambda, eps);
-    let floor = (hist_avg * 0.62 * vol_mult).max(0.01);
+    let floor = bounded_floor(hist_avg, vol_mult);
     let price = raw_price.max(floor);
     let (recovered, _active) = apply_recovery_pull(price, hist_avg, vol_mult, 0);
     recovered.max(floor)
@@ -212,7 +491,78 @@ pub fn compute_price_humane_internal(base_micros: i64, n_eff: f64, amt_micros: i
     compute_price_behavioral_core(base_micros, n_eff, amt_micros, lambda, eps)
 }
 
+/// 连续交易的价格路径预测 (批量购买的边际价格曲线)
+///
+/// 依次将每一步交易量累加到 n_eff 上，返回每一步成交后的价格。
+/// 每步单独走行为核心，因此买入/卖出的非对称灵敏度按该步方向生效。
+/// `trade_steps` 为标准单位件数：正数为卖出，负数为买入。
+pub fn predict_price_path(
+    base_micros: i64, n_eff: f64, lambda: f64, eps: f64, trade_steps: &[f64],
+) -> Vec<f64> {
+    let mut cumulative_n = n_eff;
+    trade_steps.iter()
+        .map(|&step| {
+            let step = if step.is_finite() { step } else { 0.0 };
+            let price = compute_price_behavioral_core(
+                base_micros, cumulative_n, crate::to_micros_saturating(step), lambda, eps,
+            );
+            cumulative_n += step;
+            price
+        })
+        .collect()
+}
+
+/// `steps_to_floor` 的最大模拟步数
+pub const MAX_FLOOR_STEPS: i64 = 10_000;
+
+/// 砸盘深度：以每步 `step_amount` 件连续卖出，需要多少步成交价才会触及有界定价的底价
+///
+/// 每步按 `predict_price_path` 的方式累加 n_eff 走行为核心，底价与 `compute_price_bounded_internal` 相同，
+/// 其中波动率乘数由调用方传入 (通常取 `volatility::garch_volatility_multiplier`)。
+/// 当前参考价已在底价时返回 0；`step_amount` 非正/非有限，或 `MAX_FLOOR_STEPS` 步内无法触底时返回 -1。
+pub fn steps_to_floor(
+    base_micros: i64, n_eff: f64, lambda: f64, eps: f64, hist_avg: f64, step_amount: f64, vol_mult: f64,
+) -> i64 {
+    if !step_amount.is_finite() || step_amount <= 0.0 || !n_eff.is_finite() {
+        return -1;
+    }
+    let floor = bounded_floor(hist_avg, vol_mult);
+    if compute_price_final_internal(base_micros, n_eff, lambda, eps) <= floor {
+        return 0;
+    }
+
+    let step_micros = crate::to_micros_saturating(step_amount);
+    let mut cumulative_n = n_eff;
+    for step in 1..=MAX_FLOOR_STEPS {
+        if compute_price_behavioral_core(base_micros, cumulative_n, step_micros, lambda, eps) <= floor {
+            return step;
+        }
+        cumulative_n += step_amount;
+    }
+    -1
+}
+
+/// 买卖价差比例是否合法：有限且位于 [0, 1)
+pub fn validate_spread_pct(spread_pct: f64) -> bool {
+    spread_pct.is_finite() && (0.0..1.0).contains(&spread_pct)
+}
+
+/// 做市报价：以行为核心参考价为中间价，返回 `(买入价, 卖出价)`
+///
+/// 买入价 = mid · (1 + spread/2)，卖出价 = mid · (1 − spread/2)，两者均不低于硬底线。
+/// 非法的 `spread_pct` (见 `validate_spread_pct`) 视为 0，即买卖同价。
+pub fn compute_spread(base_micros: i64, n_eff: f64, lambda: f64, eps: f64, spread_pct: f64) -> (f64, f64) {
+    let half = if validate_spread_pct(spread_pct) { spread_pct / 2.0 } else { 0.0 };
+    let mid = compute_price_final_internal(base_micros, n_eff, lambda, eps);
+    let floor = min_price();
+    ((mid * (1.0 + half)).max(floor), (mid * (1.0 - half)).max(floor))
+}
+
 /// 批量价格演算内核 - 适配 v1.6.0 高精度上下文
+///
+/// # Safety
+/// - `ctx_ptr`、`cfg_ptr`、`hist_avgs_ptr`、`lambdas_ptr` 须指向至少 `count` 个连续可读的元素
+/// - `output_ptr` 须指向至少 `count` 个连续可写的 `c_double`，且不与输入重叠
 pub unsafe fn compute_batch_prices_internal(
     count: usize,
     neff: f64,
@@ -281,8 +631,8 @@ pub fn compute_player_sell_price(
     tau: f64,
 ) -> f64 {
     let base = (base_price_micros as f64) / 1_000_000.0;
-    if !base.is_finite() || base <= 0.0 { return 0.01; }
-    if !epsilon.is_finite() || epsilon <= 0.0 { return 0.01; }
+    if !base.is_finite() || base <= 0.0 { return min_price(); }
+    if !epsilon.is_finite() || epsilon <= 0.0 { return min_price(); }
 
     // Sum decayed per-player sell volume
     let n_effective: f64 = sell_history.iter()
@@ -290,7 +640,7 @@ pub fn compute_player_sell_price(
         .sum();
 
     let raw_price = base * epsilon * (-lambda * n_effective).exp();
-    raw_price.max(base * SYSTEM_BID_RATIO).max(0.01)
+    raw_price.max(base * SYSTEM_BID_RATIO).max(min_price())
 }
 
 /// Compute the System Bid — the guaranteed minimum buy price.
@@ -299,7 +649,7 @@ pub fn compute_player_sell_price(
 /// Modeled after OSRS High Alchemy and EVE reprocessing values.
 pub fn compute_system_bid(base_price_micros: i64, hist_avg: f64) -> f64 {
     let base = (base_price_micros as f64) / 1_000_000.0;
-    (base * SYSTEM_BID_RATIO).max(hist_avg * 0.20).max(0.01)
+    (base * SYSTEM_BID_RATIO).max(hist_avg * 0.20).max(min_price())
 }
 
 // ==================== 单元测试 ====================
@@ -412,6 +762,61 @@ mod tests {
         assert!((result - 10.0).abs() < 1e-6, "buy orders should never trigger tier discount");
     }
 
+    #[test]
+    fn test_tier_price_bulk_buy_premium() {
+        let result = compute_tier_price_with(10.0, 2000.0, (1.15, 1.4));
+        // tier 1: 500 * 10 = 5000; tier 2: 1500 * 11.5 = 17250
+        let expected = (500.0 * 10.0 + 1500.0 * 11.5) / 2000.0;
+        assert!((result - expected).abs() < 1e-9);
+        assert!(result > 10.0, "bulk buy should average above base");
+
+        let deeper = compute_tier_price_with(10.0, 5000.0, (1.15, 1.4));
+        assert!(deeper > result, "third tier must escalate further");
+        assert_eq!(compute_tier_price_with(10.0, 400.0, (1.15, 1.4)), 10.0);
+
+        assert!(!set_buy_tier_multipliers(0.9, 1.2), "buy tiers may not discount");
+        assert!(!set_buy_tier_multipliers(1.4, 1.15), "tiers must be ascending");
+    }
+
+    #[test]
+    fn test_tier_table_reproduces_default_three_tiers() {
+        let breakpoints = [500.0, 2000.0];
+        let sell_rates = [1.0, 0.85, 0.60];
+        for qty in [0.0, 400.0, 500.0, 1200.0, 2000.0, 3000.0, 50_000.0] {
+            let table = compute_tier_price_table(10.0, qty, true, &breakpoints, &sell_rates);
+            let fixed = compute_tier_price_internal(10.0, qty, true);
+            assert!((table - fixed).abs() < 1e-12, "qty {}: table {} vs 3-tier {}", qty, table, fixed);
+        }
+
+        // 五档卖出表
+        let five = compute_tier_price_table(10.0, 1000.0, true, &[100.0, 200.0, 400.0, 800.0], &[1.0, 0.9, 0.8, 0.7, 0.6]);
+        let expected = (100.0 * 10.0 + 100.0 * 9.0 + 200.0 * 8.0 + 400.0 * 7.0 + 200.0 * 6.0) / 1000.0;
+        assert!((five - expected).abs() < 1e-9);
+
+        assert!(!validate_tier_table(&[500.0, 2000.0], &[1.0, 0.85], true), "length mismatch");
+        assert!(!validate_tier_table(&[2000.0, 500.0], &sell_rates, true), "breakpoints must ascend");
+        assert!(!validate_tier_table(&breakpoints, &sell_rates, false), "buy tiers may not discount");
+        assert_eq!(compute_tier_price_table(10.0, 3000.0, true, &[2000.0, 500.0], &sell_rates), 10.0);
+    }
+
+    #[test]
+    fn test_wider_tanh_scale_steepens_large_volume_response() {
+        // base = 1,000,000，卖出后总供应 200 件 × 0.1 × 0.6 → 原始指数 -12
+        let (base, n_eff, amount) = (1_000_000_000_000, 190.0, 10_000_000);
+        let default = compute_price_core_ex(base, n_eff, amount, 0.1, 1.0, 100.0, 10.0);
+        let wide = compute_price_core_ex(base, n_eff, amount, 0.1, 1.0, 100.0, 50.0);
+
+        assert_eq!(default, compute_price_humane_internal(base, n_eff, amount, 0.1, 1.0));
+        assert!(wide < default, "wider soft clamp should let the price fall further: {} vs {}", wide, default);
+        let expected_wide = 1_000_000.0 * (50.0 * (-12.0_f64 / 50.0).tanh()).exp();
+        assert!((wide - expected_wide).abs() / expected_wide < 1e-12);
+
+        // 非法参数回退为默认值
+        assert!(!validate_exponent_clamp(-100.0, 10.0));
+        assert!(!validate_exponent_clamp(100.0, 0.0));
+        assert_eq!(compute_price_core_ex(base, n_eff, amount, 0.1, 1.0, 100.0, f64::NAN), default);
+    }
+
     #[test]
     fn test_tier_price_zero_quantity() {
         let result = compute_tier_price_internal(10.0, -1.0, true);
@@ -446,6 +851,24 @@ mod tests {
         assert!(price >= 0.01, "absolute floor of 0.01 must be respected");
     }
 
+    #[test]
+    fn test_price_band_clamps_up_to_floor() {
+        // 巨大的 n_eff 使原始价格趋近 0.01，应被抬升到 hist_avg × 0.5
+        let price = compute_price_with_band(1_000_000, 1_000_000.0, 0, 0.01, 1.0, 4.0, 0.5, 3.0);
+        assert!((price - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_price_band_clamps_down_to_ceiling() {
+        // 大量买入 (负 n_eff) 推高价格，应被压到 hist_avg × 3.0
+        let price = compute_price_with_band(10_000_000, -500.0, -1_000_000, 0.01, 1.0, 4.0, 0.5, 3.0);
+        assert!((price - 12.0).abs() < 1e-9);
+
+        // 带内价格保持不变
+        let inside = compute_price_with_band(10_000_000, 0.0, 0, 0.01, 1.0, 8.0, 0.5, 3.0);
+        assert!((inside - compute_price_final_internal(10_000_000, 0.0, 0.01, 1.0)).abs() < 1e-9);
+    }
+
     // --- batch ---
 
     #[test]
@@ -493,4 +916,158 @@ mod tests {
         // Both are valid prices; asymmetry softens the sell impact
         assert!(base > 0.01 && with_trade > 0.01, "all prices should be above floor");
     }
-}
\ No newline at end of file
+
+    // --- price path ---
+
+    #[test]
+    fn test_price_path_matches_single_cumulative_trade() {
+        // 64 次卖出各 1 件，最后一步价格应等于一次性卖出 64 件的价格
+        let steps = vec![1.0; 64];
+        let path = predict_price_path(10_000_000, 100.0, 0.01, 1.0, &steps);
+        assert_eq!(path.len(), 64);
+        assert!(path.windows(2).all(|w| w[1] < w[0]), "selling should push the price down monotonically");
+
+        let bulk = compute_price_humane_internal(10_000_000, 100.0 + 63.0, 1_000_000, 0.01, 1.0);
+        assert!((path[63] - bulk).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_price_path_keeps_asymmetry() {
+        // 卖出步使用 0.6x 灵敏度，价格应高于同等供应量下的全灵敏度报价
+        let sell = predict_price_path(10_000_000, 100.0, 0.01, 1.0, &[5.0]);
+        let full_lambda = compute_price_final_internal(10_000_000, 105.0, 0.01, 1.0);
+        assert!(sell[0] > full_lambda);
+
+        let buy = predict_price_path(10_000_000, 100.0, 0.01, 1.0, &[-5.0]);
+        let at_95 = compute_price_final_internal(10_000_000, 95.0, 0.01, 1.0);
+        assert!((buy[0] - at_95).abs() < 1e-9);
+        assert!(predict_price_path(10_000_000, 100.0, 0.01, 1.0, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_linear_pricing_is_monotonic_and_bounded() {
+        // λ = 0.001：每件供给压低 0.1% 基础价
+        let price = |n_eff: f64| compute_price_linear(100_000_000, n_eff, 0, 0.001, 10.0, 150.0);
+        assert!((price(0.0) - 100.0).abs() < 1e-9);
+        assert!((price(100.0) - 90.0).abs() < 1e-9);
+        assert!((price(200.0) - 80.0).abs() < 1e-9);
+
+        // 本次卖出量与 n_eff 等价
+        assert_eq!(compute_price_linear(100_000_000, 100.0, 100_000_000, 0.001, 10.0, 150.0), price(200.0));
+
+        // 底价与上限
+        assert_eq!(price(5_000.0), 10.0);
+        assert_eq!(price(-1_000.0), 150.0);
+        assert_eq!(price(f64::NAN), 10.0);
+    }
+
+    #[test]
+    fn test_steps_to_floor_known_depth() {
+        // 卖出灵敏度 0.006：第 k 步价格约为 e^(-0.06k)，k = 8 时首次跌破 0.62
+        let steps = steps_to_floor(1_000_000, 0.0, 0.01, 1.0, 1.0, 10.0, 1.0);
+        assert_eq!(steps, 8);
+        let path = predict_price_path(1_000_000, 0.0, 0.01, 1.0, &vec![10.0; steps as usize]);
+        assert!(path[steps as usize - 1] <= 0.62);
+        assert!(path[..steps as usize - 1].iter().all(|&p| p > 0.62));
+        // 波动率乘数压低底价，需要更多步才能触底
+        assert!(steps_to_floor(1_000_000, 0.0, 0.01, 1.0, 1.0, 10.0, 0.5) > steps);
+
+        // 已在底价、无效步长、步数上限内无法触底
+        assert_eq!(steps_to_floor(1_000_000, 0.0, 0.01, 1.0, 10.0, 10.0, 1.0), 0);
+        assert_eq!(steps_to_floor(1_000_000, 0.0, 0.01, 1.0, 1.0, 0.0, 1.0), -1);
+        assert_eq!(steps_to_floor(1_000_000, 0.0, 0.0, 1.0, 1.0, 10.0, 1.0), -1);
+    }
+
+    #[test]
+    fn test_spread_brackets_mid_price() {
+        let mid = compute_price_final_internal(2_000_000, 50.0, 0.01, 1.0);
+        let (buy, sell) = compute_spread(2_000_000, 50.0, 0.01, 1.0, 0.1);
+        assert!(buy > sell);
+        assert!(((buy + sell) / 2.0 - mid).abs() < 1e-12);
+        assert!((buy - mid * 1.05).abs() < 1e-12);
+
+        // 中间价已在底线时卖出价不会跌破底线
+        let (buy, sell) = compute_spread(10_000, 1e9, 1.0, 1.0, 0.5);
+        assert_eq!(sell, min_price());
+        assert!(buy > sell);
+
+        assert!(!validate_spread_pct(1.0) && !validate_spread_pct(-0.1));
+        let (buy, sell) = compute_spread(2_000_000, 50.0, 0.01, 1.0, f64::NAN);
+        assert_eq!((buy, sell), (mid, mid));
+    }
+
+    #[test]
+    fn test_price_trace_matches_core_output() {
+        for &(n_eff, amount) in &[(50.0, 10_000_000i64), (50.0, -10_000_000), (1e6, 0), (0.0, 0)] {
+            let trace = trace_price(2_000_000, n_eff, amount, 0.01, 1.2);
+            assert_eq!(trace.final_price, compute_price_humane_internal(2_000_000, n_eff, amount, 0.01, 1.2));
+            assert_eq!(trace.total_n, n_eff + amount as f64 / MICROS_SCALE);
+            assert!(trace.clamped_exponent.abs() <= trace.raw_exponent.abs());
+        }
+        assert_eq!(trace_price(2_000_000, 50.0, 1, 0.01, 1.0).adj_lambda, 0.01 * 0.6);
+
+        let bad = trace_price(2_000_000, f64::NAN, 0, 0.01, 1.0);
+        assert_eq!(bad.final_price, min_price());
+        assert_eq!(bad.raw_exponent, 0.0);
+    }
+
+    #[test]
+    fn test_soft_start_blends_toward_base_during_ramp() {
+        let boot = 1_000_000i64;
+        let ramp = 60_000i64;
+        let (base, dynamic) = (100.0, 40.0);
+
+        assert_eq!(apply_soft_start(dynamic, base, boot, boot, ramp), base);
+        let mid = apply_soft_start(dynamic, base, boot, boot + ramp / 2, ramp);
+        assert!(dynamic < mid && mid < base, "mid-ramp price {} should sit between", mid);
+        assert!((mid - 70.0).abs() < 1e-9);
+        assert_eq!(apply_soft_start(dynamic, base, boot, boot + ramp, ramp), dynamic);
+        assert_eq!(apply_soft_start(dynamic, base, boot, boot + 10 * ramp, ramp), dynamic);
+
+        // 关闭或时间回拨
+        assert_eq!(apply_soft_start(dynamic, base, boot, boot, 0), dynamic);
+        assert_eq!(apply_soft_start(dynamic, base, boot, boot - 5_000, ramp), base);
+        assert_eq!(apply_soft_start(f64::NAN, base, boot, boot + ramp, ramp), base);
+    }
+
+    #[test]
+    fn test_dump_cooldown_penalizes_back_to_back_sells() {
+        let category = "test_dump_cooldown_ores";
+        let t0 = 1_700_000_000_000i64;
+
+        assert_eq!(apply_dump_cooldown(100.0, category, t0, 60_000), 100.0);
+        let second = apply_dump_cooldown(100.0, category, t0 + 1_000, 60_000);
+        let third = apply_dump_cooldown(100.0, category, t0 + 2_000, 60_000);
+        assert!((second - 90.0).abs() < 1e-9);
+        assert!(third < second, "repeat dumps should get worse: {} vs {}", third, second);
+
+        // 冷却过后恢复原价；连续折扣有下限
+        assert_eq!(apply_dump_cooldown(100.0, category, t0 + 200_000, 60_000), 100.0);
+        let mut floor = 100.0;
+        for i in 1..20 {
+            floor = apply_dump_cooldown(100.0, category, t0 + 200_000 + i, 60_000);
+        }
+        assert!((floor - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dump_cooldown_prunes_expired_categories() {
+        // 时间戳远早于其他测试，清除不会波及它们的类别 (时间回拨视为仍在冷却期内)
+        for i in 0..DUMP_COOLDOWN_PRUNE_LEN {
+            apply_dump_cooldown(100.0, &format!("test_dump_prune_{}", i), 1_000, 60_000);
+        }
+        assert_eq!(apply_dump_cooldown(100.0, "test_dump_prune_fresh", 1_000_000, 60_000), 100.0);
+
+        let states = DUMP_COOLDOWNS.read().unwrap_or_else(|e| e.into_inner());
+        assert!(!states.contains_key("test_dump_prune_0"));
+        assert!(states.contains_key("test_dump_prune_fresh"));
+        assert!(states.len() < DUMP_COOLDOWN_PRUNE_LEN);
+    }
+
+    #[test]
+    fn test_set_min_price_rejects_non_positive() {
+        assert!(!set_min_price(0.0));
+        assert!(!set_min_price(-1.0));
+        assert!(!set_min_price(f64::NAN));
+    }
+}
@@ -0,0 +1,121 @@
+// ==================================================
+// FILE: ecobridge-rust/src/economy/settlement.rs
+// ==================================================
+
+//! Multilateral Settlement Netting Module
+//!
+//! 会话结束时玩家之间往往存在大量交叉应付款 (A→B, B→C, C→A ...)。
+//! 本模块先计算每个参与方的净头寸，再以贪心方式将最大债务方与最大债权方配对，
+//! 输出至多 `参与方数 - 1` 笔实际结算转账，显著减少转账笔数与总流转金额。
+//!
+//! # 精度协议说明
+//! 所有金额均为 `i64 Micros`，净头寸累加使用饱和运算，不经过浮点。
+
+use crate::models::PairTransfer;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// 计算每个参与方的净头寸 (正数 = 应收，负数 = 应付)，按 id 升序返回。
+/// 自转账与非正金额被忽略。
+pub fn compute_net_positions(transfers: &[PairTransfer]) -> Vec<(i64, i64)> {
+    let mut positions: HashMap<i64, i64> = HashMap::new();
+    for t in transfers {
+        if t.amount_micros <= 0 || t.from_id == t.to_id {
+            continue;
+        }
+        let from = positions.entry(t.from_id).or_insert(0);
+        *from = from.saturating_sub(t.amount_micros);
+        let to = positions.entry(t.to_id).or_insert(0);
+        *to = to.saturating_add(t.amount_micros);
+    }
+
+    let mut sorted: Vec<(i64, i64)> = positions.into_iter().filter(|&(_, net)| net != 0).collect();
+    sorted.sort_unstable_by_key(|&(id, _)| id);
+    sorted
+}
+
+/// 轧差结算：返回与原始应付款等价的最小化转账集合。
+///
+/// 每一步将当前剩余应付最多的一方与应收最多的一方配对，结算二者较小值，
+/// 因此每一步至少清零一方，结果笔数不超过 `参与方数 - 1`。
+pub fn net_settle(transfers: &[PairTransfer]) -> Vec<PairTransfer> {
+    let positions = compute_net_positions(transfers);
+
+    let mut debtors: Vec<(i64, i64)> = positions.iter()
+        .filter(|&&(_, net)| net < 0)
+        .map(|&(id, net)| (id, net.saturating_neg()))
+        .collect();
+    let mut creditors: Vec<(i64, i64)> = positions.iter()
+        .filter(|&&(_, net)| net > 0)
+        .copied()
+        .collect();
+
+    // 大额优先；稳定排序保证同额时按 id 升序，结果可复现
+    debtors.sort_by_key(|&(_, amount)| Reverse(amount));
+    creditors.sort_by_key(|&(_, amount)| Reverse(amount));
+
+    let mut settled = Vec::with_capacity(debtors.len() + creditors.len());
+    let (mut di, mut ci) = (0, 0);
+    while di < debtors.len() && ci < creditors.len() {
+        let amount = debtors[di].1.min(creditors[ci].1);
+        settled.push(PairTransfer {
+            from_id: debtors[di].0,
+            to_id: creditors[ci].0,
+            amount_micros: amount,
+        });
+        debtors[di].1 -= amount;
+        creditors[ci].1 -= amount;
+        if debtors[di].1 == 0 { di += 1; }
+        if creditors[ci].1 == 0 { ci += 1; }
+    }
+    settled
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(from_id: i64, to_id: i64, amount_micros: i64) -> PairTransfer {
+        PairTransfer { from_id, to_id, amount_micros }
+    }
+
+    #[test]
+    fn test_cycle_nets_to_zero_transfers() {
+        // A owes B owes C owes A, equal amounts
+        let transfers = [pair(1, 2, 5_000_000), pair(2, 3, 5_000_000), pair(3, 1, 5_000_000)];
+        assert!(net_settle(&transfers).is_empty(), "a balanced cycle should need no transfers");
+    }
+
+    #[test]
+    fn test_imbalanced_set_nets_correctly() {
+        // A→B 10, B→C 4, A→C 6  =>  A: -16, B: +6, C: +10
+        let transfers = [pair(1, 2, 10_000_000), pair(2, 3, 4_000_000), pair(1, 3, 6_000_000)];
+        let settled = net_settle(&transfers);
+
+        assert_eq!(settled.len(), 2);
+        assert!(settled.contains(&pair(1, 3, 10_000_000)));
+        assert!(settled.contains(&pair(1, 2, 6_000_000)));
+
+        // 结算结果必须与原始集合产生相同的净头寸
+        assert_eq!(compute_net_positions(&settled), compute_net_positions(&transfers));
+    }
+
+    #[test]
+    fn test_self_and_non_positive_transfers_ignored() {
+        let transfers = [pair(1, 1, 5_000_000), pair(1, 2, 0), pair(2, 1, -3_000_000)];
+        assert!(net_settle(&transfers).is_empty());
+    }
+
+    #[test]
+    fn test_settlement_never_exceeds_participants_minus_one() {
+        let transfers: Vec<PairTransfer> = (0..20)
+            .map(|i| pair(i % 7, (i * 3 + 1) % 7, (i + 1) * 1_000_000))
+            .collect();
+        let settled = net_settle(&transfers);
+        let participants = compute_net_positions(&transfers).len();
+        assert!(settled.len() < participants.max(1));
+        assert_eq!(compute_net_positions(&settled), compute_net_positions(&transfers));
+    }
+}
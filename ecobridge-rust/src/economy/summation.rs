@@ -11,8 +11,10 @@
 //! - [v1.1] 优化: 使用二分查找降至 O(logN + M)。
 
 use crate::models::HistoryRecord;
+use crate::storage;
 use std::collections::HashMap;
-use std::sync::{RwLock, LazyLock};
+use std::sync::{Mutex, LazyLock};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering};
 
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
@@ -22,20 +24,81 @@ use rayon::prelude::*;
 
 // ==================== 工业级常量定义 ====================
 
-const PARALLEL_THRESHOLD: usize = 750;
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 750;
 const MS_PER_DAY: f64 = 86_400_000.0;
-const MAX_FUTURE_TOLERANCE: i64 = 60_000;
+const DEFAULT_FUTURE_TOLERANCE_MS: i64 = 60_000;
+const DEFAULT_NEFF_CACHE_TTL_MS: i64 = 0;
+const NEFF_CACHE_MAX_ENTRIES: usize = 4096;
+const TAU_BUCKET_SCALE: f64 = 1_000_000.0;
 const MICROS_SCALE: f64 = 1_000_000.0; // [v1.6.0] 精度缩放因子
-const GLOBAL_MARKET_KEY: &str = "__global__";
+pub const GLOBAL_MARKET_KEY: &str = "__global__";
 
-// 内存管理阈值
-const MAX_HISTORY_SIZE: usize = 500_000;
-const PRUNE_TO_SIZE: usize = 400_000;
+/// 未来时间戳容忍窗口 (ms)，可在运行时调整以应对集群时钟漂移
+static FUTURE_TOLERANCE_MS: AtomicI64 = AtomicI64::new(DEFAULT_FUTURE_TOLERANCE_MS);
 
-// ==================== 全局内存态 (Hot Memory Layer) ====================
+/// 设置未来时间戳容忍窗口；负值被拒绝并返回 false
+pub fn set_future_tolerance_ms(tolerance_ms: i64) -> bool {
+    if tolerance_ms < 0 {
+        return false;
+    }
+    FUTURE_TOLERANCE_MS.store(tolerance_ms, Ordering::Relaxed);
+    true
+}
+
+#[inline]
+pub fn future_tolerance_ms() -> i64 {
+    FUTURE_TOLERANCE_MS.load(Ordering::Relaxed)
+}
+
+/// 标量路径启用 rayon 并行求和的最小记录数，可按硬件调优
+static PARALLEL_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_PARALLEL_THRESHOLD);
+
+/// 设置并行阈值；0 被拒绝并返回 false
+pub fn set_parallel_threshold(threshold: usize) -> bool {
+    if threshold == 0 {
+        return false;
+    }
+    PARALLEL_THRESHOLD.store(threshold, Ordering::Relaxed);
+    true
+}
+
+#[inline]
+pub fn parallel_threshold() -> usize {
+    PARALLEL_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// N_eff 读穿缓存 TTL (ms)，0 表示关闭缓存 (默认)
+///
+/// 缓存不随追加失效：开启后 TTL 内的连续成交会读到追加前的 N_eff。
+static NEFF_CACHE_TTL_MS: AtomicI64 = AtomicI64::new(DEFAULT_NEFF_CACHE_TTL_MS);
+
+/// 设置 N_eff 缓存 TTL；负值被拒绝并返回 false
+pub fn set_neff_cache_ttl_ms(ttl_ms: i64) -> bool {
+    if ttl_ms < 0 {
+        return false;
+    }
+    NEFF_CACHE_TTL_MS.store(ttl_ms, Ordering::Relaxed);
+    true
+}
+
+#[inline]
+pub fn neff_cache_ttl_ms() -> i64 {
+    NEFF_CACHE_TTL_MS.load(Ordering::Relaxed)
+}
+
+/// 衰减半衰期 (天)：权重 `exp(-Δt / (tau·MS_PER_DAY))` 每 `tau·ln2` 天减半
+#[inline]
+pub fn tau_to_halflife_days(tau: f64) -> f64 {
+    tau * std::f64::consts::LN_2
+}
+
+/// `tau_to_halflife_days` 的逆运算
+#[inline]
+pub fn halflife_to_tau(halflife_days: f64) -> f64 {
+    halflife_days / std::f64::consts::LN_2
+}
 
-static HOT_HISTORY_BY_KEY: LazyLock<RwLock<HashMap<String, Vec<HistoryRecord>>>> =
-    LazyLock::new(|| RwLock::new(HashMap::new()));
+// ==================== 全局内存态 (Hot Memory Layer) ====================
 
 /// [v2.0] Hot store is now populated by Java via ecobridge_append_trade_to_memory FFI.
 /// This function is a no-op; kept for backward compatibility.
@@ -44,29 +107,88 @@ pub fn hydrate_hot_store() {
     // No action needed — the hot store is populated lazily.
 }
 
-/// 实时双写逻辑
+/// 实时写入：与 FFI 追加共用 `storage` 热存储 (按键分桶 + 全局聚合键)
 /// @param amount 这里的 amount 为原始 double，内部转换为 i64 Micros 存储
 pub fn append_trade_to_memory(ts: i64, amount: f64, market_key: &str) {
-    let mut lock = HOT_HISTORY_BY_KEY.write().unwrap();
-
-    let amount_micros = (amount * MICROS_SCALE) as i64;
-    let push_record = |bucket: &mut Vec<HistoryRecord>| {
-        bucket.push(HistoryRecord {
-            timestamp: ts,
-            amount_micros,
-        });
-        if bucket.len() > MAX_HISTORY_SIZE {
-            let remove_count = bucket.len() - PRUNE_TO_SIZE;
-            bucket.drain(0..remove_count);
+    storage::append_to_memory(ts, amount, market_key);
+}
+
+/// 校验模式：N_eff 跳过 AVX2 分支改走标量路径 (`calculate_volume_scalar`)，用于与 SIMD 路径对账
+static NEFF_REFERENCE_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_neff_reference_mode(enabled: bool) {
+    NEFF_REFERENCE_MODE.store(enabled, Ordering::Relaxed);
+}
+
+#[inline]
+pub fn neff_reference_mode() -> bool {
+    NEFF_REFERENCE_MODE.load(Ordering::Relaxed)
+}
+
+/// 诊断开关：关闭后 `calculate_volume_in_memory` 跳过 AVX2 分支，改走标量实现
+static SIMD_ENABLED: AtomicBool = AtomicBool::new(true);
+
+pub fn set_simd_enabled(enabled: bool) {
+    SIMD_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+#[inline]
+pub fn simd_enabled() -> bool {
+    SIMD_ENABLED.load(Ordering::Relaxed)
+}
+
+// ==================== N_eff 读穿缓存 ====================
+
+/// 缓存键：(市场键, 量化后的 tau)
+type NeffCacheKey = (String, i64);
+
+/// 缓存值：(计算时刻 current_ts, N_eff, 写入时的代数)
+type NeffCacheEntry = (i64, f64, u64);
+
+static NEFF_CACHE: LazyLock<Mutex<HashMap<NeffCacheKey, NeffCacheEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 历史数据代数：purge / rehydrate / seed 后递增，记忆化的派生值据此判断是否过期
+static NEFF_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[inline]
+pub fn neff_generation() -> u64 {
+    NEFF_GENERATION.load(Ordering::Acquire)
+}
+
+/// 历史数据被批量改写后调用：递增代数，使 N_eff 缓存与市场状态句柄中的旧值不再命中
+pub fn invalidate_neff_cache() {
+    NEFF_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// 热点物品每秒会以几乎相同的 current_ts 查询数十次。
+/// 在 TTL 内（按调用方传入的逻辑时间计）直接返回上次结果，允许轻微陈旧。
+fn query_neff_cached(current_ts: i64, tau: f64, market_key: &str, ttl_ms: i64) -> f64 {
+    if ttl_ms <= 0 || !tau.is_finite() {
+        return query_neff_uncached(current_ts, tau, market_key);
+    }
+
+    let key = (market_key.to_string(), (tau * TAU_BUCKET_SCALE).round() as i64);
+    let generation = neff_generation();
+    if let Ok(cache) = NEFF_CACHE.lock() {
+        if let Some(&(cached_ts, value, cached_gen)) = cache.get(&key) {
+            let age = current_ts.saturating_sub(cached_ts);
+            if cached_gen == generation && (0..ttl_ms).contains(&age) {
+                return value;
+            }
         }
-    };
+    }
 
-    let bucket = lock.entry(market_key.to_string()).or_insert_with(|| Vec::with_capacity(1024));
-    push_record(bucket);
+    let value = query_neff_uncached(current_ts, tau, market_key);
 
-    // Keep a global aggregate key for compatibility and diagnostics.
-    let global_bucket = lock.entry(GLOBAL_MARKET_KEY.to_string()).or_insert_with(|| Vec::with_capacity(4096));
-    push_record(global_bucket);
+    if let Ok(mut cache) = NEFF_CACHE.lock() {
+        if cache.len() >= NEFF_CACHE_MAX_ENTRIES && !cache.contains_key(&key) {
+            cache.clear();
+        }
+        // 计算期间发生失效时，以旧代数写入的结果不会再被命中
+        cache.insert(key, (current_ts, value, generation));
+    }
+    value
 }
 
 // ==================== 核心接口 ====================
@@ -76,17 +198,25 @@ pub fn query_neff_internal(
     tau: f64,
     market_key: &str,
 ) -> f64 {
-    let lock = HOT_HISTORY_BY_KEY.read().unwrap();
-    if let Some(history) = lock.get(market_key) {
-        return calculate_volume_in_memory(history, current_ts, tau);
+    query_neff_cached(current_ts, tau, market_key, neff_cache_ttl_ms())
+}
+
+fn query_neff_uncached(current_ts: i64, tau: f64, market_key: &str) -> f64 {
+    if neff_reference_mode() {
+        return storage::with_history(Some(market_key), |history| calculate_volume_scalar(history, current_ts, tau));
     }
-    0.0
+    storage::with_history(Some(market_key), |history| calculate_volume_in_memory(history, current_ts, tau))
 }
 
 pub fn query_neff_global_internal(current_ts: i64, tau: f64) -> f64 {
     query_neff_internal(current_ts, tau, GLOBAL_MARKET_KEY)
 }
 
+/// 按方向拆分的 N_eff：返回 `(买入量, 卖出量)`，均为非负的衰减累积量
+pub fn query_neff_split_internal(current_ts: i64, tau: f64, market_key: &str) -> (f64, f64) {
+    storage::with_history(Some(market_key), |history| calculate_volume_split(history, current_ts, tau))
+}
+
 // ==================== 内存计算实现 (Binary Search + SIMD) ====================
 
 #[inline]
@@ -95,12 +225,38 @@ pub fn calculate_volume_in_memory(
     current_time: i64,
     tau: f64,
 ) -> f64 {
-    if history.is_empty() || tau <= 0.0 {
+    calculate_volume_with_tolerance(history, current_time, tau, future_tolerance_ms())
+}
+
+/// 与 `calculate_volume_in_memory` 相同，但显式指定未来时间戳容忍窗口
+pub fn calculate_volume_with_tolerance(
+    history: &[HistoryRecord],
+    current_time: i64,
+    tau: f64,
+    future_tolerance_ms: i64,
+) -> f64 {
+    calculate_volume_impl(history, current_time, tau, future_tolerance_ms, simd_enabled())
+}
+
+/// 与 `calculate_volume_in_memory` 相同，但始终走标量路径 (校验模式的对账基准)
+pub fn calculate_volume_scalar(history: &[HistoryRecord], current_time: i64, tau: f64) -> f64 {
+    calculate_volume_impl(history, current_time, tau, future_tolerance_ms(), false)
+}
+
+fn calculate_volume_impl(
+    history: &[HistoryRecord],
+    current_time: i64,
+    tau: f64,
+    future_tolerance_ms: i64,
+    use_simd: bool,
+) -> f64 {
+    // 非有限 tau (NaN / inf) 与非正 tau 一样视为无效，与参考实现保持一致
+    if history.is_empty() || !tau.is_finite() || tau <= 0.0 {
         return 0.0;
     }
 
-    let valid_future_limit = current_time + MAX_FUTURE_TOLERANCE;
-    let valid_past_limit = current_time - (tau * MS_PER_DAY * 10.0) as i64;
+    let valid_future_limit = current_time.saturating_add(future_tolerance_ms.max(0));
+    let valid_past_limit = current_time.saturating_sub((tau * MS_PER_DAY * 10.0) as i64);
 
     let start_idx = history.partition_point(|r| r.timestamp < valid_past_limit);
     let relevant_slice = &history[start_idx..];
@@ -109,12 +265,13 @@ pub fn calculate_volume_in_memory(
         return 0.0;
     }
 
-    let t_min = relevant_slice[0].timestamp;
+    // 参考点不晚于 current_time：乱序数据中首条若为远未来记录，基准乘数会溢出为 inf
+    let t_min = relevant_slice[0].timestamp.min(current_time);
     let lambda = 1.0 / (tau * MS_PER_DAY);
-    let base_multiplier = (-(current_time - t_min) as f64 * lambda).exp();
+    let base_multiplier = (-(current_time.saturating_sub(t_min) as f64) * lambda).exp();
 
     #[cfg(target_arch = "x86_64")]
-    if is_x86_feature_detected!("avx2") {
+    if use_simd && is_x86_feature_detected!("avx2") {
         let sum_partial = unsafe { 
             compute_partial_simd(relevant_slice, t_min, lambda, valid_future_limit, valid_past_limit) 
         };
@@ -124,6 +281,14 @@ pub fn calculate_volume_in_memory(
     }
 
     // Fallback: 标量实现 (针对 slice)
+    let sum_partial = sum_partial_scalar(relevant_slice, t_min, lambda, valid_future_limit);
+
+    let result = (sum_partial / MICROS_SCALE) * base_multiplier;
+    if result.is_finite() { result } else { 0.0 }
+}
+
+/// 标量部分和：记录数达到并行阈值时交给 rayon
+fn sum_partial_scalar(slice: &[HistoryRecord], t_min: i64, lambda: f64, valid_future_limit: i64) -> f64 {
     let compute_partial = |rec: &HistoryRecord| -> f64 {
         if rec.timestamp > valid_future_limit {
             return 0.0; 
@@ -132,17 +297,130 @@ pub fn calculate_volume_in_memory(
         (rec.amount_micros as f64) * (dt_rel * lambda).exp()
     };
 
-    let sum_partial: f64 = if relevant_slice.len() >= PARALLEL_THRESHOLD {
+    if slice.len() >= parallel_threshold() {
         #[cfg(feature = "parallel")]
-        { relevant_slice.par_iter().map(compute_partial).sum() }
+        { slice.par_iter().map(compute_partial).sum() }
         #[cfg(not(feature = "parallel"))]
-        { relevant_slice.iter().map(compute_partial).sum() }
+        { slice.iter().map(compute_partial).sum() }
     } else {
-        relevant_slice.iter().map(compute_partial).sum()
-    };
+        slice.iter().map(compute_partial).sum()
+    }
+}
 
-    let result = (sum_partial / MICROS_SCALE) * base_multiplier;
-    if result.is_finite() { result } else { 0.0 }
+/// 方向拆分版的衰减成交量：返回 `(买入量, 卖出量)`
+///
+/// 符号约定与定价核心一致 (正数为卖出，负数为买入)，两侧均以绝对值累加，
+/// 因此 `卖出量 − 买入量` 即 `calculate_volume_in_memory` 的净值。
+/// 时间窗口与未来容忍规则相同；此路径为标量实现，不走 SIMD。
+pub fn calculate_volume_split(
+    history: &[HistoryRecord],
+    current_time: i64,
+    tau: f64,
+) -> (f64, f64) {
+    if history.is_empty() || !tau.is_finite() || tau <= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let valid_future_limit = current_time.saturating_add(future_tolerance_ms().max(0));
+    let valid_past_limit = current_time.saturating_sub((tau * MS_PER_DAY * 10.0) as i64);
+    let lambda = 1.0 / (tau * MS_PER_DAY);
+
+    let start_idx = history.partition_point(|r| r.timestamp < valid_past_limit);
+    let (mut buy, mut sell) = (0.0_f64, 0.0_f64);
+    for rec in &history[start_idx..] {
+        if rec.timestamp > valid_future_limit {
+            continue;
+        }
+        let weight = (-(current_time.saturating_sub(rec.timestamp) as f64) * lambda).exp();
+        let amount = rec.amount_micros as f64 / MICROS_SCALE;
+        if amount >= 0.0 {
+            sell += amount * weight;
+        } else {
+            buy -= amount * weight;
+        }
+    }
+
+    let finite_or_zero = |v: f64| if v.is_finite() { v } else { 0.0 };
+    (finite_or_zero(buy), finite_or_zero(sell))
+}
+
+pub const DECAY_MODEL_EXPONENTIAL: i32 = 0;
+pub const DECAY_MODEL_LINEAR: i32 = 1;
+pub const DECAY_MODEL_POWER_LAW: i32 = 2;
+
+/// 成交量随记录年龄衰减的权重核 (年龄以 tau 为单位)
+///
+/// - `Exponential`: `exp(-age/τ)`，默认模型，与 `calculate_volume_in_memory` 完全一致
+/// - `Linear`: `max(0, 1 - age/2τ)`，在 2τ 处归零，总记忆量与指数模型相同
+/// - `PowerLaw`: `(1 + age/τ)^-exponent`，长尾衰减，老记录保留更多影响
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DecayModel {
+    #[default]
+    Exponential,
+    Linear,
+    PowerLaw { exponent: f64 },
+}
+
+impl DecayModel {
+    /// 从 FFI 编号构造；未知编号或非正/非有限幂指数返回 None
+    pub fn from_raw(kind: i32, exponent: f64) -> Option<Self> {
+        match kind {
+            DECAY_MODEL_EXPONENTIAL => Some(DecayModel::Exponential),
+            DECAY_MODEL_LINEAR => Some(DecayModel::Linear),
+            DECAY_MODEL_POWER_LAW if exponent.is_finite() && exponent > 0.0 => {
+                Some(DecayModel::PowerLaw { exponent })
+            }
+            _ => None,
+        }
+    }
+
+    /// 年龄为 `age_tau` 个 tau 时的权重；未来记录 (年龄为负) 按年龄 0 计
+    #[inline]
+    fn weight(self, age_tau: f64) -> f64 {
+        let age = age_tau.max(0.0);
+        match self {
+            DecayModel::Exponential => (-age).exp(),
+            DecayModel::Linear => (1.0 - age / 2.0).max(0.0),
+            DecayModel::PowerLaw { exponent } => (1.0 + age).powf(-exponent),
+        }
+    }
+}
+
+/// 按指定衰减模型累计成交量；时间窗口与未来容忍规则与 `calculate_volume_in_memory` 相同。
+/// 指数模型直接走原有 (SIMD) 路径，其余模型为标量实现。
+pub fn calculate_volume_model(
+    history: &[HistoryRecord],
+    current_time: i64,
+    tau: f64,
+    model: DecayModel,
+) -> f64 {
+    if model == DecayModel::Exponential {
+        return calculate_volume_in_memory(history, current_time, tau);
+    }
+    if history.is_empty() || !tau.is_finite() || tau <= 0.0 {
+        return 0.0;
+    }
+
+    let valid_future_limit = current_time.saturating_add(future_tolerance_ms().max(0));
+    let valid_past_limit = current_time.saturating_sub((tau * MS_PER_DAY * 10.0) as i64);
+    let tau_ms = tau * MS_PER_DAY;
+
+    let start_idx = history.partition_point(|r| r.timestamp < valid_past_limit);
+    let sum: f64 = history[start_idx..]
+        .iter()
+        .filter(|rec| rec.timestamp <= valid_future_limit)
+        .map(|rec| {
+            let age_tau = current_time.saturating_sub(rec.timestamp) as f64 / tau_ms;
+            (rec.amount_micros as f64 / MICROS_SCALE) * model.weight(age_tau)
+        })
+        .sum();
+
+    if sum.is_finite() { sum } else { 0.0 }
+}
+
+/// 按指定衰减模型查询 N_eff (不经过 N_eff 缓存)
+pub fn query_neff_model_internal(current_ts: i64, tau: f64, market_key: &str, model: DecayModel) -> f64 {
+    storage::with_history(Some(market_key), |history| calculate_volume_model(history, current_ts, tau, model))
 }
 
 /// [v2.0] Cold path: placeholder for future metric export.
@@ -171,13 +449,13 @@ unsafe fn compute_partial_simd(
     let remainder = chunks.remainder();
 
     for chunk in chunks {
-        let t0 = chunk[0].timestamp;
-        let t3 = chunk[3].timestamp;
+        // 不能只检查首尾：跨服合并的记录可能乱序，中间元素同样可能越界
+        let has_dirty = chunk.iter().any(|r| r.timestamp > valid_future || r.timestamp < valid_past);
         
-        if t3 > valid_future || t0 < valid_past {
+        if has_dirty {
             for r in chunk {
                 if r.timestamp <= valid_future && r.timestamp >= valid_past {
-                     let dt = (r.timestamp - t_min) as f64;
+                     let dt = r.timestamp.saturating_sub(t_min) as f64;
                      let val = (r.amount_micros as f64) * (dt * lambda).exp();
                      let v_val = _mm256_set_pd(0.0, 0.0, 0.0, val);
                      sum_vec = _mm256_add_pd(sum_vec, v_val);
@@ -186,15 +464,6 @@ unsafe fn compute_partial_simd(
             continue;
         }
 
-        // [v2.0] Fast polynomial exp approximation for AVX2 path.
-        // exp(x) ≈ P3(x) = 1 + x*(1 + x*(0.5 + x/6)) for x ∈ [-10, 10]
-        // Max relative error < 0.5% for x ∈ [-2, 2], acceptable for economic use.
-        #[inline]
-        fn fast_exp(x: f64) -> f64 {
-            let x_clamped = x.clamp(-10.0, 10.0);
-            1.0 + x_clamped * (1.0 + x_clamped * (0.5 + x_clamped / 6.0))
-        }
-
         // 核心向量化计算路径
         let v_ts = _mm256_set_pd(
             chunk[3].timestamp as f64,
@@ -212,14 +481,19 @@ unsafe fn compute_partial_simd(
         let v_dt = _mm256_sub_pd(v_ts, v_tmin);
         let v_exponent = _mm256_mul_pd(v_dt, v_lambda);
 
-        // [v2.0] Use polynomial approximation instead of serial exp() calls
-        let mut arr = [0.0f64; 4];
-        _mm256_storeu_pd(arr.as_mut_ptr(), v_exponent);
-        arr[0] = fast_exp(arr[0]);
-        arr[1] = fast_exp(arr[1]);
-        arr[2] = fast_exp(arr[2]);
-        arr[3] = fast_exp(arr[3]);
-        let v_exp = _mm256_loadu_pd(arr.as_ptr());
+        #[cfg(feature = "fast-exp")]
+        let v_exp = exp_pd(v_exponent);
+
+        // 精确模式：逐通道调用标量 exp
+        #[cfg(not(feature = "fast-exp"))]
+        let v_exp = {
+            let mut arr = [0.0f64; 4];
+            _mm256_storeu_pd(arr.as_mut_ptr(), v_exponent);
+            for v in arr.iter_mut() {
+                *v = v.exp();
+            }
+            _mm256_loadu_pd(arr.as_ptr())
+        };
 
         let v_partial = _mm256_mul_pd(v_amount, v_exp);
         sum_vec = _mm256_add_pd(sum_vec, v_partial);
@@ -231,7 +505,7 @@ unsafe fn compute_partial_simd(
 
     for rec in remainder {
         if rec.timestamp <= valid_future && rec.timestamp >= valid_past {
-            let dt = (rec.timestamp - t_min) as f64;
+            let dt = rec.timestamp.saturating_sub(t_min) as f64;
             total += (rec.amount_micros as f64) * (dt * lambda).exp();
         }
     }
@@ -239,6 +513,60 @@ unsafe fn compute_partial_simd(
     total
 }
 
+/// AVX2 向量化 exp (全程驻留寄存器)
+///
+/// 采用 Cody-Waite 区间约简 x = n·ln2 + r (|r| ≤ ln2/2)，
+/// 对 r 使用 11 阶 Taylor 多项式 (截断误差 < 1e-14)，
+/// 再通过直接拼接指数位构造 2^n。输入被钳位到 [-708, 709] 以避免次正规数与溢出。
+#[cfg(all(target_arch = "x86_64", feature = "fast-exp"))]
+#[target_feature(enable = "avx2")]
+unsafe fn exp_pd(x: __m256d) -> __m256d {
+    const LN2_HI: f64 = 6.931_471_803_691_238e-1;
+    const LN2_LO: f64 = 1.908_214_929_270_587_7e-10;
+    // 1.5 * 2^52：加上后尾数低位即为取整结果
+    const ROUND_MAGIC: f64 = 6_755_399_441_055_744.0;
+    const COEFFS: [f64; 12] = [
+        1.0 / 39_916_800.0, // 1/11!
+        1.0 / 3_628_800.0,  // 1/10!
+        1.0 / 362_880.0,    // 1/9!
+        1.0 / 40_320.0,     // 1/8!
+        1.0 / 5_040.0,      // 1/7!
+        1.0 / 720.0,        // 1/6!
+        1.0 / 120.0,        // 1/5!
+        1.0 / 24.0,         // 1/4!
+        1.0 / 6.0,          // 1/3!
+        0.5,                // 1/2!
+        1.0,                // 1/1!
+        1.0,                // 1/0!
+    ];
+
+    let x = _mm256_min_pd(_mm256_max_pd(x, _mm256_set1_pd(-708.0)), _mm256_set1_pd(709.0));
+
+    let n = _mm256_round_pd(
+        _mm256_mul_pd(x, _mm256_set1_pd(std::f64::consts::LOG2_E)),
+        _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC,
+    );
+    let r = _mm256_sub_pd(x, _mm256_mul_pd(n, _mm256_set1_pd(LN2_HI)));
+    let r = _mm256_sub_pd(r, _mm256_mul_pd(n, _mm256_set1_pd(LN2_LO)));
+
+    let mut p = _mm256_set1_pd(COEFFS[0]);
+    for &c in &COEFFS[1..] {
+        p = _mm256_add_pd(_mm256_mul_pd(p, r), _mm256_set1_pd(c));
+    }
+
+    // 2^n = ((n + 1023) << 52) 按位解释为 f64
+    let magic = _mm256_set1_pd(ROUND_MAGIC);
+    let n_int = _mm256_sub_epi64(
+        _mm256_castpd_si256(_mm256_add_pd(n, magic)),
+        _mm256_castpd_si256(magic),
+    );
+    let pow2n = _mm256_castsi256_pd(_mm256_slli_epi64::<52>(
+        _mm256_add_epi64(n_int, _mm256_set1_epi64x(1023)),
+    ));
+
+    _mm256_mul_pd(p, pow2n)
+}
+
 // ==================== 单元测试 ====================
 
 #[cfg(test)]
@@ -250,6 +578,17 @@ mod tests {
         HistoryRecord { timestamp: ts, amount_micros }
     }
 
+    #[test]
+    fn test_tau_halflife_round_trip() {
+        let tau = halflife_to_tau(7.0);
+        assert!((tau - 10.098_865_286_222_745).abs() < 1e-9);
+        assert!((tau_to_halflife_days(tau) - 7.0).abs() < 1e-12);
+
+        // 经过一个半衰期后权重恰为 1/2
+        let dt = tau_to_halflife_days(tau) * MS_PER_DAY;
+        assert!(((-dt / (tau * MS_PER_DAY)).exp() - 0.5).abs() < 1e-12);
+    }
+
     #[test]
     fn test_empty_history_returns_zero() {
         let history: Vec<HistoryRecord> = vec![];
@@ -292,6 +631,51 @@ mod tests {
         assert!(result > 0.0);
     }
 
+    #[test]
+    fn test_extreme_timestamps_do_not_overflow() {
+        let now = 2_000_000_000i64;
+        let normal = vec![make_record(now - 1_000, 1_000_000), make_record(now, 2_000_000)];
+        let expected = calculate_volume_in_memory(&normal, now, 7.0);
+
+        // 乱序且含极端时间戳的对抗性输入 (长度覆盖 SIMD 块与余数)
+        let mut adversarial = vec![
+            make_record(i64::MAX, 5_000_000),
+            make_record(i64::MIN, 5_000_000),
+        ];
+        adversarial.extend_from_slice(&normal);
+        adversarial.push(make_record(i64::MAX - 1, 5_000_000));
+
+        let result = calculate_volume_in_memory(&adversarial, now, 7.0);
+        assert!((result - expected).abs() < 1e-9, "outliers must be ignored: {} vs {}", result, expected);
+
+        for current in [i64::MIN, i64::MAX, 0] {
+            for tau in [1e-9, 7.0, 1e9] {
+                let v = calculate_volume_in_memory(&adversarial, current, tau);
+                assert!(v.is_finite(), "current={} tau={} gave {}", current, tau, v);
+                let (buy, sell) = calculate_volume_split(&adversarial, current, tau);
+                assert!(buy.is_finite() && sell.is_finite());
+            }
+        }
+    }
+
+    #[test]
+    fn test_future_tolerance_admits_skewed_records() {
+        let now = 2_000_000_000i64;
+        let history = vec![
+            make_record(now - 1_000, 1_000_000),
+            make_record(now + 90_000, 1_000_000), // 90s ahead: outside the default window
+        ];
+        let strict = calculate_volume_with_tolerance(&history, now, 7.0, DEFAULT_FUTURE_TOLERANCE_MS);
+        let relaxed = calculate_volume_with_tolerance(&history, now, 7.0, 120_000);
+        assert!(relaxed > strict, "a wider tolerance should admit the near-future record");
+    }
+
+    #[test]
+    fn test_negative_future_tolerance_rejected() {
+        assert!(!set_future_tolerance_ms(-1));
+        assert!(future_tolerance_ms() >= 0);
+    }
+
     #[test]
     fn test_binary_search_start_finds_correct_index() {
         let now = 2_000_000_000i64;
@@ -312,4 +696,227 @@ mod tests {
         let result = calculate_volume_in_memory(&history, 1_000_001_000, 0.0001);
         assert!(result.is_finite(), "result should always be finite");
     }
+
+    #[test]
+    fn test_volume_split_separates_directions() {
+        let now = 1_000_000_000i64;
+        let history = vec![
+            make_record(now - 2 * MS_PER_DAY as i64, 4_000_000),
+            make_record(now - MS_PER_DAY as i64, -3_000_000),
+            make_record(now, 2_000_000),
+            make_record(now, -1_000_000),
+        ];
+        let (buy, sell) = calculate_volume_split(&history, now, 1.0);
+
+        let e1 = (-1.0_f64).exp();
+        let e2 = (-2.0_f64).exp();
+        assert!((sell - (4.0 * e2 + 2.0)).abs() < 1e-9, "sell = {}", sell);
+        assert!((buy - (3.0 * e1 + 1.0)).abs() < 1e-9, "buy = {}", buy);
+
+        // 卖出 − 买入 等于方向合并后的净 N_eff
+        let net = calculate_volume_with_tolerance(&history, now, 1.0, future_tolerance_ms());
+        assert!((sell - buy - net).abs() < 1e-9);
+
+        assert_eq!(calculate_volume_split(&history, now, 0.0), (0.0, 0.0));
+        assert_eq!(calculate_volume_split(&[], now, 1.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_decay_models_weight_history_differently() {
+        let now = 1_000_000_000i64;
+        let day = MS_PER_DAY as i64;
+        let history = vec![make_record(now - 3 * day, 1_000_000), make_record(now - day, 1_000_000)];
+
+        let exp = calculate_volume_model(&history, now, 1.0, DecayModel::Exponential);
+        let linear = calculate_volume_model(&history, now, 1.0, DecayModel::Linear);
+        let power = calculate_volume_model(&history, now, 1.0, DecayModel::PowerLaw { exponent: 2.0 });
+
+        assert_eq!(exp, calculate_volume_in_memory(&history, now, 1.0));
+        assert!((exp - ((-3.0_f64).exp() + (-1.0_f64).exp())).abs() < 1e-9, "exp = {}", exp);
+        // 线性核在 2τ 处归零：3 天前的记录不再计入
+        assert!((linear - 0.5).abs() < 1e-9, "linear = {}", linear);
+        // 幂律长尾：(1+3)^-2 + (1+1)^-2
+        assert!((power - (1.0 / 16.0 + 0.25)).abs() < 1e-9, "power = {}", power);
+        assert!(exp != linear && linear != power && power != exp);
+
+        assert_eq!(DecayModel::from_raw(DECAY_MODEL_POWER_LAW, 0.0), None);
+        assert_eq!(DecayModel::from_raw(7, 1.0), None);
+        assert_eq!(calculate_volume_model(&history, now, 0.0, DecayModel::Linear), 0.0);
+    }
+
+    #[test]
+    fn test_neff_cache_serves_stale_value_within_ttl() {
+        let _state = crate::test_support::shared();
+        let key = "test_neff_cache_ttl";
+        let t0 = crate::test_support::isolated_ts();
+        append_trade_to_memory(t0 - 1_000, 10.0, key);
+
+        let first = query_neff_cached(t0, 7.0, key, 1_000);
+        assert!(first > 0.0);
+
+        // 新成交写入后，TTL 内仍返回缓存值
+        append_trade_to_memory(t0, 10.0, key);
+        let within = query_neff_cached(t0 + 500, 7.0, key, 1_000);
+        assert_eq!(within, first, "cache hit should return the stored value");
+
+        // TTL 过期后重新计算
+        let expired = query_neff_cached(t0 + 1_500, 7.0, key, 1_000);
+        assert!(expired > first, "expired entry should be recomputed, got {} vs {}", expired, first);
+    }
+
+    #[test]
+    fn test_simd_path_agrees_with_scalar_reference() {
+        let _state = crate::test_support::shared();
+        let key = "test_neff_reference_agreement";
+        let t0 = crate::test_support::isolated_ts();
+        for i in 0..1_000i64 {
+            append_trade_to_memory(t0 - (1_000 - i) * 60_000, ((i % 7) as f64) - 3.0, key);
+        }
+
+        let fast = query_neff_uncached(t0, 7.0, key);
+        let reference = storage::with_history(Some(key), |h| calculate_volume_scalar(h, t0, 7.0));
+        assert!(fast.abs() > 0.0);
+        assert!((fast - reference).abs() <= 1e-9 * reference.abs().max(1.0),
+            "paths disagree: {} vs {}", fast, reference);
+    }
+
+    #[test]
+    fn test_purge_invalidates_neff_cache() {
+        let _state = crate::test_support::exclusive();
+        let key = "test_neff_cache_purge";
+        let t0 = crate::test_support::isolated_ts();
+        append_trade_to_memory(t0 - 1_000, 10.0, key);
+
+        let first = query_neff_cached(t0, 7.0, key, 60_000);
+        append_trade_to_memory(t0, 10.0, key);
+        assert_eq!(query_neff_cached(t0 + 10, 7.0, key, 60_000), first);
+
+        // purge 不删除任何记录，但仍使缓存失效
+        let generation = neff_generation();
+        crate::storage::purge_before(i64::MIN);
+        assert!(neff_generation() > generation);
+        let after = query_neff_cached(t0 + 20, 7.0, key, 60_000);
+        assert!(after > first, "purge should force a recompute, got {} vs {}", after, first);
+    }
+
+    #[test]
+    fn test_parallel_threshold_selects_path() {
+        let _state = crate::test_support::exclusive();
+        let history: Vec<HistoryRecord> = (0..5_000)
+            .map(|i| make_record(i * 1_000, 1_000_000 * (i % 11 - 5)))
+            .collect();
+
+        assert!(!set_parallel_threshold(0));
+        assert!(set_parallel_threshold(usize::MAX));
+        let sequential = sum_partial_scalar(&history, 0, 1e-9, i64::MAX);
+
+        assert!(set_parallel_threshold(5));
+        let parallel = sum_partial_scalar(&history, 0, 1e-9, i64::MAX);
+        assert!(set_parallel_threshold(DEFAULT_PARALLEL_THRESHOLD));
+
+        let rel = (parallel - sequential).abs() / sequential.abs().max(1.0);
+        assert!(rel < 1e-12, "parallel {} vs sequential {}", parallel, sequential);
+    }
+
+    #[test]
+    fn test_default_ttl_sees_back_to_back_sales() {
+        let _state = crate::test_support::shared();
+        let key = "test_neff_default_ttl";
+        let t0 = crate::test_support::isolated_ts();
+        append_trade_to_memory(t0 - 1_000, 10.0, key);
+        let first = query_neff_internal(t0, 7.0, key);
+
+        append_trade_to_memory(t0, 10.0, key);
+        let second = query_neff_internal(t0, 7.0, key);
+        assert!(second > first, "the second sale must see the first: {} vs {}", second, first);
+    }
+
+    #[test]
+    fn test_negative_neff_cache_ttl_rejected() {
+        assert!(!set_neff_cache_ttl_ms(-5));
+        assert!(neff_cache_ttl_ms() >= 0);
+    }
+
+    /// 标量参考实现：与 compute_partial_simd 使用相同的过滤条件
+    fn scalar_partial(history: &[HistoryRecord], t_min: i64, lambda: f64, valid_future: i64, valid_past: i64) -> f64 {
+        history.iter()
+            .filter(|r| r.timestamp <= valid_future && r.timestamp >= valid_past)
+            .map(|r| (r.amount_micros as f64) * (((r.timestamp - t_min) as f64) * lambda).exp())
+            .sum()
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_simd_remainder_matches_scalar() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+
+        let now = 1_700_000_000_000i64;
+        let tau = 7.0;
+        let lambda = 1.0 / (tau * MS_PER_DAY);
+        let valid_future = now + DEFAULT_FUTURE_TOLERANCE_MS;
+        let valid_past = now - (tau * MS_PER_DAY * 10.0) as i64;
+
+        for len in [1usize, 2, 3, 5, 7] {
+            // 每隔两条混入一条超出未来容忍窗口的脏时间戳
+            let history: Vec<HistoryRecord> = (0..len)
+                .map(|i| {
+                    let ts = if i % 3 == 2 { now + 600_000 } else { now - 60_000 + i as i64 * 1_000 };
+                    make_record(ts, 1_000_000 * (i as i64 + 1))
+                })
+                .collect();
+            let t_min = history[0].timestamp;
+
+            let simd = unsafe { compute_partial_simd(&history, t_min, lambda, valid_future, valid_past) };
+            let scalar = scalar_partial(&history, t_min, lambda, valid_future, valid_past);
+            let rel = (simd - scalar).abs() / scalar.abs().max(1.0);
+            assert!(rel < 1e-9, "len {}: simd {} vs scalar {}", len, simd, scalar);
+        }
+    }
+
+    #[test]
+    fn test_simd_switch_agrees_with_scalar_path() {
+        let _state = crate::test_support::exclusive();
+        let now = 1_700_000_000_000i64;
+        let history: Vec<HistoryRecord> = (0..37)
+            .map(|i| make_record(now - 3_600_000 * i as i64, 1_000_000 * (i as i64 % 5 - 2)))
+            .rev()
+            .collect();
+
+        let with_simd = calculate_volume_in_memory(&history, now, 7.0);
+        set_simd_enabled(false);
+        assert!(!simd_enabled());
+        let scalar = calculate_volume_in_memory(&history, now, 7.0);
+        set_simd_enabled(true);
+
+        let rel = (with_simd - scalar).abs() / scalar.abs().max(1.0);
+        assert!(rel < 1e-9, "simd {} vs scalar {}", with_simd, scalar);
+    }
+
+    #[cfg(all(target_arch = "x86_64", feature = "fast-exp"))]
+    #[test]
+    fn test_vectorized_exp_error_bound() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+
+        // 覆盖求和路径的有效指数域 [0, 10+] 以及两侧的宽区间
+        let mut max_rel = 0.0f64;
+        let mut x = -50.0;
+        while x <= 50.0 {
+            let xs = [x, x + 0.0123, x + 0.25, x + 0.5];
+            let mut out = [0.0f64; 4];
+            unsafe {
+                let v = exp_pd(_mm256_loadu_pd(xs.as_ptr()));
+                _mm256_storeu_pd(out.as_mut_ptr(), v);
+            }
+            for (xi, yi) in xs.iter().zip(out.iter()) {
+                let exact = xi.exp();
+                max_rel = max_rel.max((yi - exact).abs() / exact);
+            }
+            x += 0.37;
+        }
+        assert!(max_rel < 1e-12, "max relative error {} exceeds 1e-12", max_rel);
+    }
 }
@@ -17,6 +17,7 @@
 use std::sync::Mutex;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use crate::storage;
 
 /// GARCH(1,1) state for a single market or asset.
 #[derive(Debug, Clone)]
@@ -56,14 +57,14 @@ pub fn garch_init(key: &str, alpha: f64, beta: f64, omega: f64) {
     if alpha + beta >= 1.0 {
         return; // non-stationary
     }
-    let mut states = GARCH_STATES.lock().unwrap();
+    let mut states = storage::lock(&GARCH_STATES);
     states.insert(key.to_string(), GarchState::new(alpha, beta, omega));
 }
 
 /// Update the GARCH model with a new return observation.
 /// Returns the current (updated) volatility (sigma, not sigma^2).
 pub fn garch_update(key: &str, return_val: f64) -> f64 {
-    let mut states = GARCH_STATES.lock().unwrap();
+    let mut states = storage::lock(&GARCH_STATES);
     let state = states.entry(key.to_string()).or_insert_with(|| {
         GarchState::new(0.05, 0.90, 1e-6)
     });
@@ -89,7 +90,7 @@ pub fn garch_update(key: &str, return_val: f64) -> f64 {
 
 /// N-step ahead GARCH forecast. Returns the predicted volatility at horizon H.
 pub fn garch_forecast(key: &str, steps: u32) -> f64 {
-    let states = GARCH_STATES.lock().unwrap();
+    let states = storage::lock(&GARCH_STATES);
     let state = match states.get(key) {
         Some(s) => s,
         None => return 0.0,
@@ -112,7 +113,7 @@ pub fn garch_forecast(key: &str, steps: u32) -> f64 {
 
 /// Free the GARCH state for a given key.
 pub fn garch_free(key: &str) {
-    let mut states = GARCH_STATES.lock().unwrap();
+    let mut states = storage::lock(&GARCH_STATES);
     states.remove(key);
 }
 
@@ -131,6 +132,7 @@ pub fn garch_volatility_multiplier(key: &str) -> f64 {
 // ==================== 单元测试 ====================
 
 #[cfg(test)]
+#[allow(clippy::manual_range_contains)]
 mod tests {
     use super::*;
 
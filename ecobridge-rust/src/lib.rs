@@ -2,12 +2,12 @@
 // FILE: ecobridge-rust/src/lib.rs
 // ==================================================
 
-use libc::{c_char, c_double, c_int, c_longlong}; 
+use libc::{c_char, c_double, c_int, c_longlong, c_ulonglong}; 
 use std::ffi::CStr;
 use std::panic::{self, AssertUnwindSafe};
 use std::collections::HashMap;
 use std::sync::{RwLock, LazyLock};
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::atomic::{AtomicI32, AtomicI64, AtomicU64, AtomicUsize, Ordering};
 use std::ptr;
 
 // -----------------------------------------------------------------------------
@@ -15,16 +15,25 @@ use std::ptr;
 // -----------------------------------------------------------------------------
 pub mod models;
 pub mod economy {
+    pub mod analytics;
     pub mod control;
     pub mod environment;
     pub mod forecast;
     pub mod kalman;
     pub mod macro_eco;
+    pub mod market_state;
     pub mod mpc;
+    pub mod portfolio;
     pub mod pricing;
+    pub mod settlement;
     pub mod summation;
     pub mod volatility;
+
+    pub use analytics::{estimate_elasticity, price_trend, time_weighted_average};
+    pub use summation::{halflife_to_tau, tau_to_halflife_days};
 }
+pub mod api;
+pub mod logging;
 pub mod security;
 pub mod storage;
 
@@ -41,6 +50,7 @@ pub enum EconStatus {
     NullPointer = 1,
     InvalidLength = 2,
     InvalidValue = 3,
+    Duplicate = 4,
     NumericOverflow = 10,
     InternalError = 100,
     Panic = 101,
@@ -75,24 +85,128 @@ pub(crate) fn to_micros_saturating(value: f64) -> i64 {
 // FFI 安全屏障 (The Firewall)
 // -----------------------------------------------------------------------------
 
+/// 被拦截的 panic 累计次数
+static PANIC_COUNTER: AtomicU64 = AtomicU64::new(0);
+/// 超过该次数即视为系统不稳定 (可由 Java 侧调整)
+static PANIC_THRESHOLD: AtomicU64 = AtomicU64::new(100);
+
+/// Java 侧注册的 panic 回调：`(累计次数, UTF-8 消息)`
+pub type PanicCallback = extern "C" fn(count: u64, msg: *const c_char);
+
+/// 以地址形式存储回调 (0 表示未注册)
+static PANIC_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+fn report_panic(e: Box<dyn std::any::Any + Send>) {
+    let msg = if let Some(s) = e.downcast_ref::<&str>() {
+        *s
+    } else if let Some(s) = e.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        "Unknown panic"
+    };
+    log::error!("PANIC INTERCEPTED: {}", msg);
+
+    let count = PANIC_COUNTER.fetch_add(1, Ordering::Relaxed) + 1;
+    if count >= PANIC_THRESHOLD.load(Ordering::Relaxed) {
+        log::error!("{} panics intercepted, system instability imminent", count);
+    }
+
+    let addr = PANIC_CALLBACK.load(Ordering::Acquire);
+    if addr != 0 {
+        // SAFETY: 地址仅由 ecobridge_set_panic_callback 从合法的函数指针写入
+        let callback: PanicCallback = unsafe { std::mem::transmute::<usize, PanicCallback>(addr) };
+        let c_msg = std::ffi::CString::new(msg.replace('\0', " ")).unwrap_or_default();
+        // 回调自身不得再次引发 panic 穿越 FFI 边界
+        let _ = panic::catch_unwind(|| callback(count, c_msg.as_ptr()));
+    }
+}
+
 macro_rules! ffi_guard {
+    // 状态码模式：返回 EconStatus
     ($body:expr) => {{
         let result = panic::catch_unwind(AssertUnwindSafe($body));
         match result {
             Ok(status) => status as c_int,
             Err(e) => {
-                let msg = if let Some(s) = e.downcast_ref::<&str>() {
-                    *s
-                } else if let Some(s) = e.downcast_ref::<String>() {
-                    s.as_str()
-                } else {
-                    "Unknown panic"
-                };
-                eprintln!("[EcoBridge-Native] PANIC INTERCEPTED: {}", msg);
+                report_panic(e);
                 EconStatus::Panic as c_int
             }
         }
     }};
+    // 值返回模式：panic 时返回调用方指定的兜底值
+    ($fallback:expr, $body:expr) => {{
+        let result = panic::catch_unwind(AssertUnwindSafe($body));
+        match result {
+            Ok(value) => value,
+            Err(e) => {
+                report_panic(e);
+                $fallback
+            }
+        }
+    }};
+}
+
+/// 定价 FFI 的 panic 兜底模式：不写入输出 (默认，与旧版一致，仅返回 Panic)
+pub const PRICING_FALLBACK_NONE: c_int = 0;
+/// 定价 FFI 的 panic 兜底模式：向输出写入负数哨兵，便于 Java 侧识别并记录
+pub const PRICING_FALLBACK_SENTINEL: c_int = 1;
+/// 定价 FFI 的 panic 兜底模式：向输出写入 base
+pub const PRICING_FALLBACK_BASE: c_int = 2;
+/// 哨兵模式下写入的价格 (任何合法价格均为正)
+pub const PRICING_PANIC_SENTINEL: f64 = -1.0;
+
+static PRICING_FALLBACK_MODE: AtomicI32 = AtomicI32::new(PRICING_FALLBACK_NONE);
+
+/// 按当前兜底模式给出 panic 时写入的值；None 表示保持输出不变
+fn pricing_panic_fallback(base: f64) -> Option<f64> {
+    match PRICING_FALLBACK_MODE.load(Ordering::Relaxed) {
+        PRICING_FALLBACK_SENTINEL => Some(PRICING_PANIC_SENTINEL),
+        PRICING_FALLBACK_BASE => Some(base),
+        _ => None,
+    }
+}
+
+/// 单值定价 FFI 的屏障：在 ffi_guard! 基础上，panic 时按兜底模式写入 `out`
+macro_rules! pricing_guard {
+    ($out:expr, $base:expr, $body:expr) => {{
+        let status = ffi_guard!($body);
+        if status == EconStatus::Panic as c_int && !$out.is_null() {
+            if let Some(fallback) = pricing_panic_fallback($base) {
+                *$out = fallback;
+            }
+        }
+        status
+    }};
+}
+
+/// 在 FFI 入口处将 NaN/Inf 标量参数替换为兜底值：`sanitize_inputs!(x => 0.0, y => 1.0);`
+macro_rules! sanitize_inputs {
+    ($($name:ident => $fallback:expr),+ $(,)?) => {
+        $(let $name = models::sanitize_f64($name, $fallback);)+
+    };
+}
+
+/// 可空的市场键：所有接受可空 `market_key_ptr` 的 FFI 均以 NULL 表示全局聚合键 `__global__`
+/// (热存储中每笔成交都会同时写入该键)
+unsafe fn market_key_or_global(market_key_ptr: *const c_char) -> String {
+    if market_key_ptr.is_null() {
+        economy::summation::GLOBAL_MARKET_KEY.to_string()
+    } else {
+        CStr::from_ptr(market_key_ptr).to_string_lossy().into_owned()
+    }
+}
+
+/// 将 UTF-8 文本写入调用方提供的 C 缓冲区 (按字符边界截断，始终以 NUL 结尾)
+unsafe fn write_c_buffer(out_buf: *mut c_char, buf_len: usize, text: &str) {
+    if out_buf.is_null() || buf_len == 0 {
+        return;
+    }
+    let mut len = text.len().min(buf_len - 1);
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    ptr::copy_nonoverlapping(text.as_ptr() as *const c_char, out_buf, len);
+    *out_buf.add(len) = 0;
 }
 
 // -----------------------------------------------------------------------------
@@ -101,7 +215,7 @@ macro_rules! ffi_guard {
 
 #[no_mangle]
 pub extern "C" fn ecobridge_abi_version() -> c_int {
-    0x0009_0000 
+    0x000A_0000
 }
 
 #[no_mangle]
@@ -110,8 +224,45 @@ pub extern "C" fn ecobridge_version() -> *const c_char {
     VERSION.as_ptr() as *const c_char
 }
 
+// 编译期构建特性片段 (供 ecobridge_build_features 拼接为静态字符串)
+#[cfg(target_arch = "x86_64")]
+macro_rules! build_arch { () => { "x86_64" } }
+#[cfg(target_arch = "aarch64")]
+macro_rules! build_arch { () => { "aarch64" } }
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+macro_rules! build_arch { () => { "other" } }
+
+// x86_64 上 AVX2 路径按运行时检测分派；以 target-feature 编译时为静态启用
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+macro_rules! build_simd { () => { ",avx2" } }
+#[cfg(all(target_arch = "x86_64", not(target_feature = "avx2")))]
+macro_rules! build_simd { () => { ",avx2-dispatch" } }
+#[cfg(not(target_arch = "x86_64"))]
+macro_rules! build_simd { () => { "" } }
+
+#[cfg(feature = "parallel")]
+macro_rules! build_parallel { () => { ",parallel" } }
+#[cfg(not(feature = "parallel"))]
+macro_rules! build_parallel { () => { "" } }
+
+#[cfg(feature = "fast-exp")]
+macro_rules! build_fast_exp { () => { ",fast-exp" } }
+#[cfg(not(feature = "fast-exp"))]
+macro_rules! build_fast_exp { () => { "" } }
+
+/// 编译期确定的构建特性，逗号分隔 (如 "x86_64,avx2-dispatch,parallel,fast-exp")，
+/// 供插件在加载时打印，便于排查不同构建间的性能差异。返回静态字符串，调用方不得释放。
+#[no_mangle]
+pub extern "C" fn ecobridge_build_features() -> *const c_char {
+    static FEATURES: &str = concat!(build_arch!(), build_simd!(), build_parallel!(), build_fast_exp!(), "\0");
+    FEATURES.as_ptr() as *const c_char
+}
+
+/// 初始化线程池并安装原生日志器，记录启动时刻供开服软启动使用
 #[no_mangle]
 pub extern "C" fn ecobridge_init_threading(num_threads: c_int) -> c_int {
+    logging::install();
+    economy::pricing::record_boot_ts(storage::now_ms());
     let config = rayon::ThreadPoolBuilder::new().num_threads(num_threads as usize);
     match config.build_global() {
         Ok(_) => EconStatus::Ok as c_int,
@@ -124,6 +275,8 @@ pub extern "C" fn ecobridge_init_threading(num_threads: c_int) -> c_int {
 // 2. 内存热存储 (v2.0 — H2 migration, DB layer is now Java)
 // -----------------------------------------------------------------------------
 
+/// # Safety
+/// `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_append_trade_to_memory(
     ts: c_longlong,
@@ -134,12 +287,85 @@ pub unsafe extern "C" fn ecobridge_append_trade_to_memory(
         if market_key_ptr.is_null() {
             return EconStatus::NullPointer;
         }
-        let market_key = CStr::from_ptr(market_key_ptr).to_string_lossy().into_owned();
+        let raw_key = CStr::from_ptr(market_key_ptr);
+        if raw_key.to_bytes().len() > storage::max_key_len() {
+            storage::record_dropped();
+            return EconStatus::InvalidLength;
+        }
+        let market_key = raw_key.to_string_lossy().into_owned();
         storage::append_to_memory(ts, amount, &market_key);
         EconStatus::Ok
     })
 }
 
+/// 按 JSON 元数据中的 `category` 字段归档成交 (如 `{"category":"ores"}`)。
+/// 元数据无法解析或缺少分类时记入 "uncategorized"，成交量不会丢失；写入成功即返回 `Ok`，
+/// 是否回退到 "uncategorized" 通过 `out_fallback` 回报 (1 = 已回退，0 = 使用元数据分类)。
+///
+/// # Safety
+/// - `meta_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_fallback` 为空 (不关心回退) 或指向可写的 `c_int`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_append_trade_with_meta(
+    ts: c_longlong,
+    amount: c_double,
+    meta_ptr: *const c_char,
+    out_fallback: *mut c_int,
+) -> c_int {
+    ffi_guard!(|| {
+        if meta_ptr.is_null() {
+            return EconStatus::NullPointer;
+        }
+        let meta = CStr::from_ptr(meta_ptr).to_string_lossy();
+        let categorized = storage::append_to_memory_with_meta(ts, amount, &meta);
+        if !out_fallback.is_null() {
+            *out_fallback = c_int::from(!categorized);
+        }
+        EconStatus::Ok
+    })
+}
+
+/// 幂等追加：`dedup_key` 在近期窗口内出现过时返回 `Duplicate` 且不写入
+///
+/// # Safety
+/// `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_append_trade_idempotent(
+    ts: c_longlong,
+    amount: c_double,
+    market_key_ptr: *const c_char,
+    dedup_key: c_ulonglong,
+) -> c_int {
+    ffi_guard!(|| {
+        if market_key_ptr.is_null() {
+            return EconStatus::NullPointer;
+        }
+        let raw_key = CStr::from_ptr(market_key_ptr);
+        if raw_key.to_bytes().len() > storage::max_key_len() {
+            storage::record_dropped();
+            return EconStatus::InvalidLength;
+        }
+        let market_key = raw_key.to_string_lossy().into_owned();
+        if storage::append_to_memory_idempotent(ts, amount, &market_key, dedup_key) {
+            EconStatus::Ok
+        } else {
+            EconStatus::Duplicate
+        }
+    })
+}
+
+/// 设置去重窗口容量 (默认 65536)；0 视为非法
+#[no_mangle]
+pub extern "C" fn ecobridge_set_dedup_capacity(capacity: usize) -> c_int {
+    if storage::set_dedup_capacity(capacity) {
+        EconStatus::Ok as c_int
+    } else {
+        EconStatus::InvalidValue as c_int
+    }
+}
+
+/// # Safety
+/// `records_ptr` 为空或指向至少 `count` 个连续可读的 `HistoryRecord`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_bulk_load_history(
     records_ptr: *const HistoryRecord,
@@ -154,6 +380,243 @@ pub unsafe extern "C" fn ecobridge_bulk_load_history(
     })
 }
 
+/// 从种子文件 (每行 `timestamp_ms,amount` 的 CSV) 预热全局热存储，供无历史的新服冷启动。
+/// 返回载入条数；路径非法返回 -InvalidValue，文件无法读取返回 -InternalError。
+///
+/// # Safety
+/// `path_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_seed_history(path_ptr: *const c_char) -> c_longlong {
+    ffi_guard!(-(EconStatus::Panic as c_longlong), || {
+        if path_ptr.is_null() { return -(EconStatus::NullPointer as c_longlong); }
+        let path = match CStr::from_ptr(path_ptr).to_str() {
+            Ok(p) if !p.trim().is_empty() => p.trim(),
+            _ => return -(EconStatus::InvalidValue as c_longlong),
+        };
+        match storage::seed_history_from_file(path) {
+            Ok(n) => n as c_longlong,
+            Err(e) => {
+                log::warn!("failed to read seed file {}: {}", path, e);
+                -(EconStatus::InternalError as c_longlong)
+            }
+        }
+    })
+}
+
+/// 运行时重新同步全局热存储：以 Java 侧从 H2 重新查询的记录整体替换内存历史。
+/// 返回保留的记录数，参数非法时返回负的 EconStatus。
+///
+/// # Safety
+/// `records_ptr` 为空或指向至少 `count` 个连续可读的 `HistoryRecord`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_rehydrate_history(
+    records_ptr: *const HistoryRecord,
+    count: u64,
+) -> c_longlong {
+    ffi_guard!(-(EconStatus::Panic as c_longlong), || {
+        if count > 1_000_000 { return -(EconStatus::InvalidLength as c_longlong); }
+        if count > 0 && records_ptr.is_null() { return -(EconStatus::NullPointer as c_longlong); }
+        let slice = if count == 0 { &[][..] } else { std::slice::from_raw_parts(records_ptr, count as usize) };
+        storage::rehydrate_history(slice) as c_longlong
+    })
+}
+
+/// 后台重建全局热存储，避免启动时阻塞：记录在返回前已复制，调用方可立即释放缓冲区。
+/// 已有后台重建在进行或线程无法启动时返回 InternalError (此时应改用同步的 ecobridge_rehydrate_history)。
+/// 重建期间 ecobridge_is_hydration_complete 返回 0，Java 侧应暂时走 H2 查询路径。
+///
+/// # Safety
+/// `records_ptr` 为空或指向至少 `count` 个连续可读的 `HistoryRecord`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_rehydrate_history_async(
+    records_ptr: *const HistoryRecord,
+    count: u64,
+) -> c_int {
+    ffi_guard!(|| {
+        if count > 1_000_000 { return EconStatus::InvalidLength; }
+        if count > 0 && records_ptr.is_null() { return EconStatus::NullPointer; }
+        let records = if count == 0 {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(records_ptr, count as usize).to_vec()
+        };
+        if storage::rehydrate_history_async(records) {
+            EconStatus::Ok
+        } else {
+            EconStatus::InternalError
+        }
+    })
+}
+
+/// 后台重建是否已完成 (1 = 完成或未进行，0 = 加载中)
+#[no_mangle]
+pub extern "C" fn ecobridge_is_hydration_complete() -> c_int {
+    storage::is_hydration_complete() as c_int
+}
+
+/// 数据保留：清除内存热存储中早于 `cutoff_ts` 的记录，返回全局存储中被移除的条数
+#[no_mangle]
+pub extern "C" fn ecobridge_purge_before(cutoff_ts: c_longlong) -> c_longlong {
+    ffi_guard!(-(EconStatus::Panic as c_longlong), || {
+        storage::purge_before(cutoff_ts) as c_longlong
+    })
+}
+
+/// 打开全局热存储的游标 (最近 `days` 天)。`days < 0` 时返回 NULL。
+/// 游标不持有锁，但必须调用 `ecobridge_history_cursor_close` 释放。
+#[no_mangle]
+pub extern "C" fn ecobridge_history_cursor_open(days: c_int) -> *mut storage::HistoryCursor {
+    ffi_guard!(ptr::null_mut(), || {
+        if days < 0 { return ptr::null_mut(); }
+        Box::into_raw(Box::new(storage::HistoryCursor::open_days(days)))
+    })
+}
+
+/// 读取下一条记录：1 = 已写入 `out_record`，0 = 已结束，-1 = 错误
+///
+/// # Safety
+/// - `cursor` 为空或为 `ecobridge_history_cursor_open` 返回且尚未释放的句柄
+/// - `out_record` 为空或指向可写的 `HistoryRecord`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_history_cursor_next(
+    cursor: *mut storage::HistoryCursor,
+    out_record: *mut HistoryRecord,
+) -> c_int {
+    ffi_guard!(-1, || {
+        if cursor.is_null() || out_record.is_null() { return -1; }
+        match (*cursor).next() {
+            Some(record) => {
+                ptr::write(out_record, record);
+                1
+            }
+            None => 0,
+        }
+    })
+}
+
+/// # Safety
+/// `cursor` 为空或为 `ecobridge_history_cursor_open` 返回且尚未释放的句柄，调用后不得再使用。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_history_cursor_close(cursor: *mut storage::HistoryCursor) {
+    if cursor.is_null() { return; }
+    ffi_guard!((), || drop(Box::from_raw(cursor)))
+}
+
+/// 最近 `days` 天的逐日成交汇总 (UTC，由旧到新)。`out_stats` 须可容纳 `capacity` 个元素；
+/// 天数超过容量时只写入最近的 `capacity` 天。返回写入条数，参数非法时返回负的 EconStatus。
+///
+/// # Safety
+/// `out_stats` 为空或指向至少 `capacity` 个连续可写的 `DailyStat`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_query_daily_rollup(
+    days: c_int,
+    out_stats: *mut DailyStat,
+    capacity: usize,
+) -> c_longlong {
+    ffi_guard!(-(EconStatus::Panic as c_longlong), || {
+        if out_stats.is_null() { return -(EconStatus::NullPointer as c_longlong); }
+        if days < 0 { return -(EconStatus::InvalidValue as c_longlong); }
+        let stats = storage::query_daily_rollup(days);
+        let recent = &stats[stats.len().saturating_sub(capacity)..];
+        ptr::copy_nonoverlapping(recent.as_ptr(), out_stats, recent.len());
+        recent.len() as c_longlong
+    })
+}
+
+/// 一次性查询所有市场的 N_eff (按键排序，不含全局聚合键)。
+/// 第 i 个市场的键以 UTF-8 字节 (不含 NUL) 依次拼接写入 `out_keys`，长度写入 `out_key_lens[i]`，
+/// N_eff 写入 `out_volumes[i]`；条目数或键缓冲区不足时截断到已完整写入的前缀。
+/// `out_total` 接收市场总数以便调用方扩容。返回写入条数，参数非法时返回负的 EconStatus。
+///
+/// # Safety
+/// - `out_volumes` 为空或指向至少 `capacity` 个连续可写的 `c_double`
+/// - `out_key_lens` 为空或指向至少 `capacity` 个连续可写的 `usize`
+/// - `out_keys` 为空或指向至少 `keys_buf_len` 字节的可写缓冲区
+/// - `out_total` 为空或指向可写的 `c_ulonglong`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_query_neff_all_markets(
+    current_ts: c_longlong,
+    tau: c_double,
+    out_volumes: *mut c_double,
+    out_key_lens: *mut usize,
+    capacity: usize,
+    out_keys: *mut c_char,
+    keys_buf_len: usize,
+    out_total: *mut c_ulonglong,
+) -> c_longlong {
+    ffi_guard!(-(EconStatus::Panic as c_longlong), || {
+        if out_volumes.is_null() || out_key_lens.is_null() || out_keys.is_null() || out_total.is_null() {
+            return -(EconStatus::NullPointer as c_longlong);
+        }
+        if !tau.is_finite() || tau <= 0.0 { return -(EconStatus::InvalidValue as c_longlong); }
+
+        let markets = storage::query_neff_all_markets(current_ts, tau);
+        *out_total = markets.len() as c_ulonglong;
+
+        let mut written = 0usize;
+        let mut offset = 0usize;
+        for (key, volume) in markets.iter().take(capacity) {
+            let bytes = key.as_bytes();
+            if offset + bytes.len() > keys_buf_len { break; }
+            ptr::copy_nonoverlapping(bytes.as_ptr(), out_keys.add(offset) as *mut u8, bytes.len());
+            *out_key_lens.add(written) = bytes.len();
+            *out_volumes.add(written) = *volume;
+            offset += bytes.len();
+            written += 1;
+        }
+        written as c_longlong
+    })
+}
+
+/// 最近 `days` 天按本地小时 (应用 `timezone_offset_sec`) 聚合的成交量绝对值，
+/// 写入 `out_profile[0..24]` (下标 0 为本地零点)
+///
+/// # Safety
+/// `out_profile` 为空或指向至少 `24` 个连续可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_query_hourly_profile(
+    days: c_int,
+    timezone_offset_sec: c_int,
+    out_profile: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_profile.is_null() { return EconStatus::NullPointer; }
+        if days < 0 { return EconStatus::InvalidValue; }
+        let profile = storage::query_hourly_profile(days, timezone_offset_sec);
+        ptr::copy_nonoverlapping(profile.as_ptr(), out_profile, profile.len());
+        EconStatus::Ok
+    })
+}
+
+/// 单次扫描计算多个 tau 下的 N_eff，`out_volumes[i]` 对应 `taus[i]`；
+/// `market_key_ptr` 为空时查询全局聚合，非法 tau 对应结果为 0
+///
+/// # Safety
+/// - `taus_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_volumes` 为空或指向至少 `count` 个连续可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_query_neff_multi_tau(
+    current_ts: c_longlong,
+    taus_ptr: *const c_double,
+    count: usize,
+    market_key_ptr: *const c_char,
+    out_volumes: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if count == 0 { return EconStatus::Ok; }
+        if taus_ptr.is_null() || out_volumes.is_null() { return EconStatus::NullPointer; }
+        let market_key = market_key_or_global(market_key_ptr);
+        let taus = std::slice::from_raw_parts(taus_ptr, count);
+        let volumes = storage::query_neff_multi_tau(current_ts, taus, Some(&market_key));
+        std::slice::from_raw_parts_mut(out_volumes, count).copy_from_slice(&volumes);
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_result` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_query_neff_in_memory(
     current_ts: c_longlong,
@@ -169,6 +632,8 @@ pub unsafe extern "C" fn ecobridge_query_neff_in_memory(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_query_neff_global_in_memory(
     current_ts: c_longlong,
@@ -182,6 +647,9 @@ pub unsafe extern "C" fn ecobridge_query_neff_global_in_memory(
     })
 }
 
+/// # Safety
+/// - `out_total` 为空或指向可写的 `u64`
+/// - `out_dropped` 为空或指向可写的 `u64`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_get_health_stats(
     out_total: *mut u64,
@@ -191,8 +659,185 @@ pub unsafe extern "C" fn ecobridge_get_health_stats(
         if out_total.is_null() || out_dropped.is_null() {
             return EconStatus::NullPointer;
         }
-        *out_total = storage::get_total_logs() as u64;
-        *out_dropped = storage::get_dropped_logs() as u64;
+        *out_total = storage::get_total_logs();
+        *out_dropped = storage::get_dropped_logs();
+        EconStatus::Ok
+    })
+}
+
+/// 一次性汇总全部健康计数器 (各独立 getter 仍保留)
+///
+/// # Safety
+/// `out` 为空或指向可写的 `HealthReport`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_get_health_report(out: *mut HealthReport) -> c_int {
+    ffi_guard!(|| {
+        if out.is_null() {
+            return EconStatus::NullPointer;
+        }
+        *out = HealthReport {
+            total_logs: storage::get_total_logs(),
+            dropped_logs: storage::get_dropped_logs(),
+            panic_count: PANIC_COUNTER.load(Ordering::Relaxed),
+            history_len: storage::get_history_len(),
+            hydration_complete: storage::is_hydration_complete() as c_int,
+            _padding: 0,
+        };
+        EconStatus::Ok
+    })
+}
+
+/// 设置可接受的市场键最大字节数 (默认 4096)；超长键会被拒绝并计入 dropped
+#[no_mangle]
+pub extern "C" fn ecobridge_set_max_key_len(max_len: usize) -> c_int {
+    if storage::set_max_key_len(max_len) {
+        EconStatus::Ok as c_int
+    } else {
+        EconStatus::InvalidValue as c_int
+    }
+}
+
+/// 最近一笔已持久化成交写入热存储时的墙钟时间 (ms)，尚无成交时返回 0；
+/// 配合 total_logs 可发现"仍在接收但长时间无新落库"的卡死写入方
+#[no_mangle]
+pub extern "C" fn ecobridge_get_last_flush_ts() -> c_longlong {
+    storage::get_last_persisted_ts()
+}
+
+/// 热存储锁因 panic 中毒后被恢复的次数 (非 0 说明曾有写入方 panic)
+#[no_mangle]
+pub extern "C" fn ecobridge_get_lock_recovered_count() -> c_ulonglong {
+    storage::get_lock_recovered()
+}
+
+#[no_mangle]
+pub extern "C" fn ecobridge_get_panic_count() -> c_ulonglong {
+    PANIC_COUNTER.load(Ordering::Relaxed)
+}
+
+#[no_mangle]
+pub extern "C" fn ecobridge_reset_panic_count() {
+    PANIC_COUNTER.store(0, Ordering::Relaxed);
+}
+
+/// 设置 "系统不稳定" 告警阈值；0 被拒绝
+#[no_mangle]
+pub extern "C" fn ecobridge_set_panic_threshold(threshold: c_ulonglong) -> c_int {
+    if threshold == 0 {
+        return EconStatus::InvalidValue as c_int;
+    }
+    PANIC_THRESHOLD.store(threshold, Ordering::Relaxed);
+    EconStatus::Ok as c_int
+}
+
+/// 注册 panic 回调 (每次拦截到 panic 时调用)；传入 NULL 取消注册
+#[no_mangle]
+pub extern "C" fn ecobridge_set_panic_callback(callback: Option<extern "C" fn(count: u64, msg: *const c_char)>) {
+    PANIC_CALLBACK.store(callback.map_or(0, |cb| cb as usize), Ordering::Release);
+}
+
+/// 注册日志回调 `(级别 1..=5, UTF-8 消息)`，替代默认的 stderr 输出；传入 NULL 恢复 stderr
+#[no_mangle]
+pub extern "C" fn ecobridge_set_log_callback(callback: Option<extern "C" fn(level: c_int, msg: *const c_char)>) {
+    logging::set_callback(callback);
+}
+
+/// 设置原生日志的最大级别 (0 = 关闭，1 = Error … 5 = Trace，默认 3 = Info)
+#[no_mangle]
+pub extern "C" fn ecobridge_set_log_level(level: c_int) -> c_int {
+    if logging::set_level(level) {
+        EconStatus::Ok as c_int
+    } else {
+        EconStatus::InvalidValue as c_int
+    }
+}
+
+/// 设置定价 FFI 的 panic 兜底模式 (0 = 不写入输出，默认；1 = 哨兵 -1.0；2 = base)
+#[no_mangle]
+pub extern "C" fn ecobridge_set_pricing_fallback_mode(mode: c_int) -> c_int {
+    if !(PRICING_FALLBACK_NONE..=PRICING_FALLBACK_BASE).contains(&mode) {
+        return EconStatus::InvalidValue as c_int;
+    }
+    PRICING_FALLBACK_MODE.store(mode, Ordering::Relaxed);
+    EconStatus::Ok as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn ecobridge_set_future_tolerance_ms(tolerance: c_longlong) -> c_int {
+    ffi_guard!(|| {
+        if economy::summation::set_future_tolerance_ms(tolerance) {
+            EconStatus::Ok
+        } else {
+            EconStatus::InvalidValue
+        }
+    })
+}
+
+/// 设置 N_eff 读穿缓存 TTL (ms，默认 0 即关闭)；负值视为非法。
+/// 缓存不随成交追加失效，开启后同一 TTL 内的连续卖出会读到陈旧的 N_eff。
+#[no_mangle]
+pub extern "C" fn ecobridge_set_neff_cache_ttl_ms(ttl_ms: c_longlong) -> c_int {
+    ffi_guard!(|| {
+        if economy::summation::set_neff_cache_ttl_ms(ttl_ms) {
+            EconStatus::Ok
+        } else {
+            EconStatus::InvalidValue
+        }
+    })
+}
+
+/// 设置标量求和启用 rayon 并行的最小记录数 (默认 750)；0 视为非法
+#[no_mangle]
+pub extern "C" fn ecobridge_set_parallel_threshold(threshold: usize) -> c_int {
+    if economy::summation::set_parallel_threshold(threshold) {
+        EconStatus::Ok as c_int
+    } else {
+        EconStatus::InvalidValue as c_int
+    }
+}
+
+/// 校验模式：非 0 时 N_eff 改走逐条标量实现 (用于与 SIMD 路径对账)，0 恢复默认
+#[no_mangle]
+pub extern "C" fn ecobridge_set_neff_reference_mode(enabled: c_int) -> c_int {
+    ffi_guard!(|| {
+        economy::summation::set_neff_reference_mode(enabled != 0);
+        EconStatus::Ok
+    })
+}
+
+/// 诊断开关：0 时 N_eff 累计强制走标量路径 (排查 SIMD 偏差)，非 0 恢复默认的 AVX2 路径
+#[no_mangle]
+pub extern "C" fn ecobridge_set_simd_enabled(enabled: c_int) -> c_int {
+    ffi_guard!(|| {
+        economy::summation::set_simd_enabled(enabled != 0);
+        EconStatus::Ok
+    })
+}
+
+/// tau → 半衰期 (天)，供配置界面显示 "交易量每 N 天减半"
+///
+/// # Safety
+/// `out_days` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_tau_to_halflife_days(tau: c_double, out_days: *mut c_double) -> c_int {
+    ffi_guard!(|| {
+        if out_days.is_null() { return EconStatus::NullPointer; }
+        if !tau.is_finite() || tau <= 0.0 { return EconStatus::InvalidValue; }
+        *out_days = economy::tau_to_halflife_days(tau);
+        EconStatus::Ok
+    })
+}
+
+/// 半衰期 (天) → tau
+///
+/// # Safety
+/// `out_tau` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_halflife_to_tau(halflife_days: c_double, out_tau: *mut c_double) -> c_int {
+    ffi_guard!(|| {
+        if out_tau.is_null() { return EconStatus::NullPointer; }
+        if !halflife_days.is_finite() || halflife_days <= 0.0 { return EconStatus::InvalidValue; }
+        *out_tau = economy::halflife_to_tau(halflife_days);
         EconStatus::Ok
     })
 }
@@ -209,6 +854,8 @@ pub extern "C" fn inject_remote_trade(amount_micros: c_longlong) -> c_int {
     })
 }
 
+/// # Safety
+/// `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn inject_remote_trade_for_key(
     market_key_ptr: *const c_char,
@@ -218,7 +865,12 @@ pub unsafe extern "C" fn inject_remote_trade_for_key(
         if market_key_ptr.is_null() {
             return EconStatus::NullPointer;
         }
-        let market_key = match CStr::from_ptr(market_key_ptr).to_str() {
+        let raw_key = CStr::from_ptr(market_key_ptr);
+        if raw_key.to_bytes().len() > storage::max_key_len() {
+            storage::record_dropped();
+            return EconStatus::InvalidLength;
+        }
+        let market_key = match raw_key.to_str() {
             Ok(v) if !v.trim().is_empty() => v.trim().to_string(),
             _ => return EconStatus::InvalidValue,
         };
@@ -231,6 +883,8 @@ pub unsafe extern "C" fn inject_remote_trade_for_key(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_longlong`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_money_to_micros(
     value: c_double,
@@ -245,6 +899,8 @@ pub unsafe extern "C" fn ecobridge_money_to_micros(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_micros_to_money(
     value_micros: c_longlong,
@@ -259,6 +915,8 @@ pub unsafe extern "C" fn ecobridge_micros_to_money(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_volatility_from_stability(
     stability: c_double,
@@ -276,6 +934,8 @@ pub unsafe extern "C" fn ecobridge_compute_volatility_from_stability(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_velocity_decay(
     velocity: c_double,
@@ -300,6 +960,8 @@ pub unsafe extern "C" fn ecobridge_compute_velocity_decay(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_fallback_tax(
     amount: c_double,
@@ -317,6 +979,9 @@ pub unsafe extern "C" fn ecobridge_compute_fallback_tax(
     })
 }
 
+/// # Safety
+/// - `out_tax` 为空或指向可写的 `c_double`
+/// - `out_net` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_settlement(
     amount: c_double,
@@ -342,6 +1007,8 @@ pub unsafe extern "C" fn ecobridge_compute_settlement(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_query_neff_vectorized(
     current_ts: c_longlong,
@@ -361,6 +1028,9 @@ pub unsafe extern "C" fn ecobridge_query_neff_vectorized(
     })
 }
 
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_result` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_query_neff_for_key(
     current_ts: c_longlong,
@@ -394,19 +1064,104 @@ pub unsafe extern "C" fn ecobridge_query_neff_for_key(
     })
 }
 
+/// 按方向拆分的 N_eff (买入量、卖出量，均为非负)；`market_key_ptr` 为空时查询全局聚合。
+/// 不消费远程流量累加器。
+///
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_buy` 为空或指向可写的 `c_double`
+/// - `out_sell` 为空或指向可写的 `c_double`
 #[no_mangle]
-pub unsafe extern "C" fn ecobridge_compute_batch_prices(
-    count: u64,
-    neff: f64,
-    ctx_ptr: *const TradeContext,
-    cfg_ptr: *const MarketConfig,
-    hist_avgs_ptr: *const f64,
-    lambdas_ptr: *const f64,
-    results_ptr: *mut f64,
+pub unsafe extern "C" fn ecobridge_query_neff_split(
+    current_ts: c_longlong,
+    tau: c_double,
+    market_key_ptr: *const c_char,
+    out_buy: *mut c_double,
+    out_sell: *mut c_double,
 ) -> c_int {
     ffi_guard!(|| {
-        if ctx_ptr.is_null() || cfg_ptr.is_null() || hist_avgs_ptr.is_null() || 
-           lambdas_ptr.is_null() || results_ptr.is_null() {
+        if out_buy.is_null() || out_sell.is_null() {
+            return EconStatus::NullPointer;
+        }
+        if !tau.is_finite() || tau <= 0.0 {
+            return EconStatus::InvalidValue;
+        }
+
+        let market_key = if market_key_ptr.is_null() {
+            economy::summation::GLOBAL_MARKET_KEY
+        } else {
+            match CStr::from_ptr(market_key_ptr).to_str() {
+                Ok(v) if !v.trim().is_empty() => v.trim(),
+                _ => return EconStatus::InvalidValue,
+            }
+        };
+
+        let (buy, sell) = economy::summation::query_neff_split_internal(current_ts, tau, market_key);
+        *out_buy = buy;
+        *out_sell = sell;
+        EconStatus::Ok
+    })
+}
+
+/// 按指定衰减模型查询 N_eff：`model` 取 0 = 指数 (默认)、1 = 线性、2 = 幂律 (使用 `exponent`)；
+/// `market_key_ptr` 为空时查询全局聚合。不经过 N_eff 缓存，也不消费远程流量累加器。
+///
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_query_neff_model(
+    current_ts: c_longlong,
+    tau: c_double,
+    model: c_int,
+    exponent: c_double,
+    market_key_ptr: *const c_char,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() {
+            return EconStatus::NullPointer;
+        }
+        if !tau.is_finite() || tau <= 0.0 {
+            return EconStatus::InvalidValue;
+        }
+        let Some(model) = economy::summation::DecayModel::from_raw(model, exponent) else {
+            return EconStatus::InvalidValue;
+        };
+
+        let market_key = if market_key_ptr.is_null() {
+            economy::summation::GLOBAL_MARKET_KEY
+        } else {
+            match CStr::from_ptr(market_key_ptr).to_str() {
+                Ok(v) if !v.trim().is_empty() => v.trim(),
+                _ => return EconStatus::InvalidValue,
+            }
+        };
+
+        *out_result = economy::summation::query_neff_model_internal(current_ts, tau, market_key, model);
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// - `ctx_ptr` 为空或指向至少 `count` 个连续可读的 `TradeContext`
+/// - `cfg_ptr` 为空或指向至少 `count` 个连续可读的 `MarketConfig`
+/// - `hist_avgs_ptr` 为空或指向至少 `count` 个连续可读的 `f64`
+/// - `lambdas_ptr` 为空或指向至少 `count` 个连续可读的 `f64`
+/// - `results_ptr` 为空或指向至少 `count` 个连续可写的 `f64`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_batch_prices(
+    count: u64,
+    neff: f64,
+    ctx_ptr: *const TradeContext,
+    cfg_ptr: *const MarketConfig,
+    hist_avgs_ptr: *const f64,
+    lambdas_ptr: *const f64,
+    results_ptr: *mut f64,
+) -> c_int {
+    ffi_guard!(|| {
+        if ctx_ptr.is_null() || cfg_ptr.is_null() || hist_avgs_ptr.is_null() || 
+           lambdas_ptr.is_null() || results_ptr.is_null() {
             return EconStatus::NullPointer;
         }
         
@@ -429,6 +1184,8 @@ pub unsafe extern "C" fn ecobridge_compute_batch_prices(
 
 // --- 单体价格计算函数 (Fix: 适配 i64 Micros 参数) ---
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_price_final(
     base: c_double,
@@ -437,7 +1194,7 @@ pub unsafe extern "C" fn ecobridge_compute_price_final(
     epsilon: c_double,
     out_result: *mut c_double,
 ) -> c_int {
-    ffi_guard!(|| {
+    pricing_guard!(out_result, base, || {
         if out_result.is_null() { return EconStatus::NullPointer; }
         // [Precision Fix]: 将 c_double base 转换为 i64 Micros
         let base_micros = to_micros_saturating(base);
@@ -446,6 +1203,66 @@ pub unsafe extern "C" fn ecobridge_compute_price_final(
     })
 }
 
+/// 砸盘深度：每步卖出 `step_amount` 件，需多少步成交价触及有界定价底价；
+/// 已在底价返回 0，无法在步数上限内触底返回 -1 (结果写入 `out_steps`)
+///
+/// # Safety
+/// `out_steps` 为空或指向可写的 `c_longlong`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_steps_to_floor(
+    base: c_double,
+    n_eff: c_double,
+    lambda: c_double,
+    epsilon: c_double,
+    hist_avg: c_double,
+    step_amount: c_double,
+    out_steps: *mut c_longlong,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_steps.is_null() { return EconStatus::NullPointer; }
+        if !step_amount.is_finite() || step_amount <= 0.0 { return EconStatus::InvalidValue; }
+        let base_micros = to_micros_saturating(base);
+        let vol_mult = economy::volatility::garch_volatility_multiplier("__global__");
+        *out_steps = economy::pricing::steps_to_floor(base_micros, n_eff, lambda, epsilon, hist_avg, step_amount, vol_mult);
+        EconStatus::Ok
+    })
+}
+
+/// 做市双边报价：以参考价为中间价，买入价/卖出价各偏离 `spread_pct / 2`，均不低于硬底线；
+/// `spread_pct` 须位于 [0, 1)
+///
+/// # Safety
+/// - `out_buy` 为空或指向可写的 `c_double`
+/// - `out_sell` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_spread(
+    base: c_double,
+    n_eff: c_double,
+    lambda: c_double,
+    epsilon: c_double,
+    spread_pct: c_double,
+    out_buy: *mut c_double,
+    out_sell: *mut c_double,
+) -> c_int {
+    let status = pricing_guard!(out_buy, base, || {
+        if out_buy.is_null() || out_sell.is_null() { return EconStatus::NullPointer; }
+        if !economy::pricing::validate_spread_pct(spread_pct) { return EconStatus::InvalidValue; }
+        let base_micros = to_micros_saturating(base);
+        let (buy, sell) = economy::pricing::compute_spread(base_micros, n_eff, lambda, epsilon, spread_pct);
+        *out_buy = buy;
+        *out_sell = sell;
+        EconStatus::Ok
+    });
+    if status == EconStatus::Panic as c_int && !out_sell.is_null() {
+        if let Some(fallback) = pricing_panic_fallback(base) {
+            *out_sell = fallback;
+        }
+    }
+    status
+}
+
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_tier_price(
     base: c_double,
@@ -453,13 +1270,64 @@ pub unsafe extern "C" fn ecobridge_compute_tier_price(
     is_sell: c_int,
     out_result: *mut c_double,
 ) -> c_int {
-    ffi_guard!(|| {
+    pricing_guard!(out_result, base, || {
         if out_result.is_null() { return EconStatus::NullPointer; }
         *out_result = economy::pricing::compute_tier_price_internal(base, qty, is_sell != 0);
         EconStatus::Ok
     })
 }
 
+/// 任意档数的阶梯定价：`breakpoints` 为 `breakpoint_count` 个严格递增的累计件数，
+/// `rates` 须恰有 `breakpoint_count + 1` 项 (否则返回 `InvalidLength`)；其余校验失败返回 `InvalidValue`。
+///
+/// # Safety
+/// - `breakpoints_ptr` 为空或指向至少 `breakpoint_count` 个连续可读的 `c_double`
+/// - `rates_ptr` 为空或指向至少 `rate_count` 个连续可读的 `c_double`
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_tier_price_table(
+    base: c_double,
+    qty: c_double,
+    is_sell: c_int,
+    breakpoints_ptr: *const c_double,
+    breakpoint_count: usize,
+    rates_ptr: *const c_double,
+    rate_count: usize,
+    out_result: *mut c_double,
+) -> c_int {
+    pricing_guard!(out_result, base, || {
+        if out_result.is_null() || rates_ptr.is_null() { return EconStatus::NullPointer; }
+        if breakpoint_count > 0 && breakpoints_ptr.is_null() { return EconStatus::NullPointer; }
+        if rate_count != breakpoint_count + 1 { return EconStatus::InvalidLength; }
+        let breakpoints = if breakpoint_count == 0 {
+            &[][..]
+        } else {
+            std::slice::from_raw_parts(breakpoints_ptr, breakpoint_count)
+        };
+        let rates = std::slice::from_raw_parts(rates_ptr, rate_count);
+        if !economy::pricing::validate_tier_table(breakpoints, rates, is_sell != 0) {
+            return EconStatus::InvalidValue;
+        }
+        *out_result = economy::pricing::compute_tier_price_table(base, qty, is_sell != 0, breakpoints, rates);
+        EconStatus::Ok
+    })
+}
+
+/// 配置大宗买入的阶梯溢价 (第二档 501-2000 件、第三档 2000 件以上)；默认 1.0 即不溢价。
+/// 要求 1.0 <= tier2 <= tier3。
+#[no_mangle]
+pub extern "C" fn ecobridge_set_buy_tier_multipliers(tier2: c_double, tier3: c_double) -> c_int {
+    ffi_guard!(|| {
+        if economy::pricing::set_buy_tier_multipliers(tier2, tier3) {
+            EconStatus::Ok
+        } else {
+            EconStatus::InvalidValue
+        }
+    })
+}
+
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_price_humane(
     base: c_double,
@@ -469,7 +1337,7 @@ pub unsafe extern "C" fn ecobridge_compute_price_humane(
     epsilon: c_double,
     out_result: *mut c_double,
 ) -> c_int {
-    ffi_guard!(|| {
+    pricing_guard!(out_result, base, || {
         if out_result.is_null() { return EconStatus::NullPointer; }
         // [Precision Fix]: 将 base 和 trade_amount 转换为 i64 Micros
         let base_micros = to_micros_saturating(base);
@@ -479,6 +1347,74 @@ pub unsafe extern "C" fn ecobridge_compute_price_humane(
     })
 }
 
+/// 可调限幅的行为定价核心：`exponent_clamp` 为指数硬限幅的对称边界，
+/// `tanh_scale` 为软限幅尺度 (默认 100 / 10，尺度上限 300)
+///
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_price_core_ex(
+    base: c_double,
+    n_eff: c_double,
+    trade_amount: c_double,
+    lambda: c_double,
+    epsilon: c_double,
+    exponent_clamp: c_double,
+    tanh_scale: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    pricing_guard!(out_result, base, || {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if !economy::pricing::validate_exponent_clamp(exponent_clamp, tanh_scale) {
+            return EconStatus::InvalidValue;
+        }
+        *out_result = economy::pricing::compute_price_core_ex(
+            to_micros_saturating(base), n_eff, to_micros_saturating(trade_amount),
+            lambda, epsilon, exponent_clamp, tanh_scale,
+        );
+        EconStatus::Ok
+    })
+}
+
+/// 设置全局绝对硬底线 (默认 0.01)；`min` 必须为正的有限值
+#[no_mangle]
+pub extern "C" fn ecobridge_set_min_price(min: c_double) -> c_int {
+    ffi_guard!(|| {
+        if economy::pricing::set_min_price(min) {
+            EconStatus::Ok
+        } else {
+            EconStatus::InvalidValue
+        }
+    })
+}
+
+/// 预测连续交易的价格路径，`out_prices` 须至少容纳 `count` 个元素
+///
+/// # Safety
+/// - `steps_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+/// - `out_prices` 为空或指向至少 `count` 个连续可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_predict_price_path(
+    base: c_double,
+    n_eff: c_double,
+    lambda: c_double,
+    epsilon: c_double,
+    steps_ptr: *const c_double,
+    count: usize,
+    out_prices: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if steps_ptr.is_null() || out_prices.is_null() { return EconStatus::NullPointer; }
+        if count == 0 || count > 1_000_000 { return EconStatus::InvalidLength; }
+        let steps = std::slice::from_raw_parts(steps_ptr, count);
+        let path = economy::pricing::predict_price_path(to_micros_saturating(base), n_eff, lambda, epsilon, steps);
+        ptr::copy_nonoverlapping(path.as_ptr(), out_prices, count);
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_price_bounded(
     base: c_double,
@@ -489,7 +1425,7 @@ pub unsafe extern "C" fn ecobridge_compute_price_bounded(
     hist_avg: c_double,
     out_result: *mut c_double,
 ) -> c_int {
-    ffi_guard!(|| {
+    pricing_guard!(out_result, base, || {
         if out_result.is_null() { return EconStatus::NullPointer; }
         // [Precision Fix]: 将 base 和 amt 转换为 i64 Micros
         let base_micros = to_micros_saturating(base);
@@ -499,6 +1435,136 @@ pub unsafe extern "C" fn ecobridge_compute_price_bounded(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_price_banded(
+    base: c_double,
+    n_eff: c_double,
+    amt: c_double,
+    lambda: c_double,
+    eps: c_double,
+    hist_avg: c_double,
+    floor_frac: c_double,
+    ceil_mult: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    pricing_guard!(out_result, base, || {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if !floor_frac.is_finite() || !ceil_mult.is_finite() || floor_frac < 0.0 || floor_frac >= ceil_mult {
+            return EconStatus::InvalidValue;
+        }
+        let base_micros = to_micros_saturating(base);
+        let amt_micros = to_micros_saturating(amt);
+        *out_result = economy::pricing::compute_price_with_band(
+            base_micros, n_eff, amt_micros, lambda, eps, hist_avg, floor_frac, ceil_mult,
+        );
+        EconStatus::Ok
+    })
+}
+
+/// 大额抛售冷却：对 `category` 记录本次大额卖出，冷却期内的连续抛售逐次追加折扣
+///
+/// # Safety
+/// - `category_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_apply_dump_cooldown(
+    price: c_double,
+    category_ptr: *const c_char,
+    ts: c_longlong,
+    cooldown_ms: c_longlong,
+    out_result: *mut c_double,
+) -> c_int {
+    pricing_guard!(out_result, price, || {
+        if out_result.is_null() || category_ptr.is_null() { return EconStatus::NullPointer; }
+        if cooldown_ms < 0 { return EconStatus::InvalidValue; }
+        let raw = CStr::from_ptr(category_ptr);
+        if raw.to_bytes().len() > storage::max_key_len() {
+            return EconStatus::InvalidLength;
+        }
+        let category = raw.to_string_lossy();
+        *out_result = economy::pricing::apply_dump_cooldown(price, &category, ts, cooldown_ms);
+        EconStatus::Ok
+    })
+}
+
+/// 诊断：按默认限幅走一遍行为定价核心并写出每一步中间量；`out.final_price` 与
+/// ecobridge_compute_price_humane 的结果一致
+///
+/// # Safety
+/// `out` 为空或指向可写的 `PriceTrace`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_trace_price(
+    base: c_double,
+    n_eff: c_double,
+    trade_amount: c_double,
+    lambda: c_double,
+    epsilon: c_double,
+    out: *mut PriceTrace,
+) -> c_int {
+    ffi_guard!(|| {
+        if out.is_null() { return EconStatus::NullPointer; }
+        let base_micros = to_micros_saturating(base);
+        let amount_micros = to_micros_saturating(trade_amount);
+        *out = economy::pricing::trace_price(base_micros, n_eff, amount_micros, lambda, epsilon);
+        EconStatus::Ok
+    })
+}
+
+/// 开服软启动：以 ecobridge_init_threading 记录的启动时刻为起点，`ramp_ms` 内将动态价格
+/// 向 `base` 线性混合 (启动时完全为基准价，满 `ramp_ms` 后完全为动态价)。未初始化时原样返回。
+///
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_apply_soft_start(
+    price: c_double,
+    base: c_double,
+    current_ts: c_longlong,
+    ramp_ms: c_longlong,
+    out_result: *mut c_double,
+) -> c_int {
+    pricing_guard!(out_result, price, || {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if ramp_ms < 0 { return EconStatus::InvalidValue; }
+        *out_result = match economy::pricing::boot_ts() {
+            Some(boot) => economy::pricing::apply_soft_start(price, base, boot, current_ts, ramp_ms),
+            None => price,
+        };
+        EconStatus::Ok
+    })
+}
+
+/// 线性定价模式：clamp(base × (1 − λ × (n_eff + amt)), floor, ceil)；
+/// 要求 0 <= floor <= ceil，floor 实际不低于全局硬底线
+///
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_price_linear(
+    base: c_double,
+    n_eff: c_double,
+    amt: c_double,
+    lambda: c_double,
+    floor: c_double,
+    ceil: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    pricing_guard!(out_result, base, || {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if !floor.is_finite() || !ceil.is_finite() || floor < 0.0 || floor > ceil {
+            return EconStatus::InvalidValue;
+        }
+        *out_result = economy::pricing::compute_price_linear(
+            to_micros_saturating(base), n_eff, to_micros_saturating(amt), lambda, floor, ceil,
+        );
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_player_sell_price(
     base: c_double,
@@ -508,7 +1574,7 @@ pub unsafe extern "C" fn ecobridge_compute_player_sell_price(
     tau: c_double,
     out_result: *mut c_double,
 ) -> c_int {
-    ffi_guard!(|| {
+    pricing_guard!(out_result, base, || {
         if out_result.is_null() { return EconStatus::NullPointer; }
         let base_micros = to_micros_saturating(base);
         // Player sell history is accumulated per-key in Java; FFI passes empty history
@@ -518,6 +1584,8 @@ pub unsafe extern "C" fn ecobridge_compute_player_sell_price(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_logistic_decay(
     sold_count: c_double,
@@ -533,13 +1601,15 @@ pub unsafe extern "C" fn ecobridge_compute_logistic_decay(
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_system_bid(
     base: c_double,
     hist_avg: c_double,
     out_result: *mut c_double,
 ) -> c_int {
-    ffi_guard!(|| {
+    pricing_guard!(out_result, base, || {
         if out_result.is_null() { return EconStatus::NullPointer; }
         let base_micros = to_micros_saturating(base);
         *out_result = economy::pricing::compute_system_bid(base_micros, hist_avg);
@@ -551,6 +1621,8 @@ pub unsafe extern "C" fn ecobridge_compute_system_bid(
 // 4. 宏观经济指标
 // -----------------------------------------------------------------------------
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_calc_inflation(
     current_heat: c_double,
@@ -559,25 +1631,94 @@ pub unsafe extern "C" fn ecobridge_calc_inflation(
 ) -> c_int {
     ffi_guard!(|| {
         if out_result.is_null() { return EconStatus::NullPointer; }
+        sanitize_inputs!(current_heat => 0.0, m1 => 0.0);
         if m1 <= 0.0 { return EconStatus::InvalidValue; }
         *out_result = economy::macro_eco::calculate_inflation_rate(current_heat, m1);
         EconStatus::Ok
     })
 }
 
+/// 可配置钳位区间的通胀率；区间须满足 `min_rate < max_rate`，否则返回 InvalidValue
+///
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_calc_inflation_ex(
+    current_heat: c_double,
+    m1: c_double,
+    min_rate: c_double,
+    max_rate: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if !economy::macro_eco::validate_inflation_bounds(min_rate, max_rate) {
+            return EconStatus::InvalidValue;
+        }
+        sanitize_inputs!(current_heat => 0.0, m1 => 0.0);
+        if m1 <= 0.0 { return EconStatus::InvalidValue; }
+        *out_result = economy::macro_eco::calculate_inflation_rate_ex(current_heat, m1, min_rate, max_rate);
+        EconStatus::Ok
+    })
+}
+
+/// 推荐回收量：使通胀率回落到 `target_inflation` 需移除的流通热度 (>= 0)
+///
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_recommended_sink(
+    current_heat: c_double,
+    m1: c_double,
+    target_inflation: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if !target_inflation.is_finite() { return EconStatus::InvalidValue; }
+        sanitize_inputs!(current_heat => 0.0, m1 => 0.0);
+        if m1 <= 0.0 { return EconStatus::InvalidValue; }
+        *out_result = economy::macro_eco::recommended_sink(current_heat, m1, target_inflation);
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// `out_result` 须指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_calc_stability(
     last_ts: c_longlong,
     curr_ts: c_longlong,
     out_result: *mut c_double,
+) -> c_int {
+    ecobridge_calc_stability_ex(last_ts, curr_ts, economy::macro_eco::DEFAULT_RECOVERY_WINDOW_MS, out_result)
+}
+
+/// 可配置恢复窗口的稳定性因子；非正或非有限的窗口回退为默认 900000ms
+///
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_calc_stability_ex(
+    last_ts: c_longlong,
+    curr_ts: c_longlong,
+    recovery_window_ms: c_double,
+    out_result: *mut c_double,
 ) -> c_int {
     ffi_guard!(|| {
         if out_result.is_null() { return EconStatus::NullPointer; }
-        *out_result = economy::macro_eco::calculate_stability(last_ts, curr_ts, 900000.0);
+        let window = if recovery_window_ms.is_finite() && recovery_window_ms > 0.0 {
+            recovery_window_ms
+        } else {
+            economy::macro_eco::DEFAULT_RECOVERY_WINDOW_MS
+        };
+        *out_result = economy::macro_eco::calculate_stability(last_ts, curr_ts, window);
         EconStatus::Ok
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_calc_decay(
     heat: c_double,
@@ -586,11 +1727,75 @@ pub unsafe extern "C" fn ecobridge_calc_decay(
 ) -> c_int {
     ffi_guard!(|| {
         if out_result.is_null() { return EconStatus::NullPointer; }
+        sanitize_inputs!(heat => 0.0, rate => 0.0);
         *out_result = economy::macro_eco::calculate_decay(heat, rate, 48.0);
         EconStatus::Ok
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_calc_decay_ex(
+    heat: c_double,
+    rate: c_double,
+    cycles_per_day: c_double,
+    reset_threshold: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if !(cycles_per_day.is_finite() && cycles_per_day > 0.0) {
+            return EconStatus::InvalidValue;
+        }
+        *out_result = economy::macro_eco::calculate_decay_ex(heat, rate, cycles_per_day, reset_threshold);
+        EconStatus::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ecobridge_compute_macro_snapshot(
+    current_heat: c_double,
+    m1: c_double,
+    last_volatile_ts: c_longlong,
+    current_ts: c_longlong,
+    recovery_window_ms: c_double,
+    decay_rate: c_double,
+    cycles_per_day: c_double,
+) -> MacroSnapshot {
+    ffi_guard!(MacroSnapshot::default(), || {
+        economy::macro_eco::compute_macro_snapshot(
+            current_heat, m1, last_volatile_ts, current_ts,
+            recovery_window_ms, decay_rate, cycles_per_day,
+        )
+    })
+}
+
+/// 泰尔指数 (财富集中度)；`balances_ptr` 为标准化余额数组 (已由 Micros 缩放)
+///
+/// # Safety
+/// - `balances_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_calculate_theil(
+    balances_ptr: *const c_double,
+    count: usize,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if count > 0 && balances_ptr.is_null() { return EconStatus::NullPointer; }
+        if count > 10_000_000 { return EconStatus::InvalidLength; }
+        let balances = if count == 0 { &[][..] } else { std::slice::from_raw_parts(balances_ptr, count) };
+        *out_result = economy::macro_eco::calculate_theil(balances);
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// - `ctx_ptr` 为空或指向有效的 `TradeContext`
+/// - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+/// - `out_result` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_calculate_epsilon(
     ctx_ptr: *const TradeContext,
@@ -598,35 +1803,282 @@ pub unsafe extern "C" fn ecobridge_calculate_epsilon(
     out_result: *mut c_double,
 ) -> c_int {
     ffi_guard!(|| {
-        if ctx_ptr.is_null() || cfg_ptr.is_null() || out_result.is_null() {
-            return EconStatus::NullPointer;
-        }
-        *out_result = economy::environment::calculate_epsilon_internal(&*ctx_ptr, &*cfg_ptr);
+        if ctx_ptr.is_null() || cfg_ptr.is_null() || out_result.is_null() {
+            return EconStatus::NullPointer;
+        }
+        let ctx = (*ctx_ptr).sanitized();
+        let cfg = (*cfg_ptr).sanitized();
+        *out_result = economy::environment::calculate_epsilon_internal(&ctx, &cfg);
+        EconStatus::Ok
+    })
+}
+
+/// 调试：输出 epsilon 计算中使用的本地星期 (0=周一)、周末与节庆状态；`cfg_ptr` 为空时按默认周末判定
+///
+/// # Safety
+/// - `ctx_ptr` 为空或指向有效的 `TradeContext`
+/// - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+/// - `out_day_of_week` 为空或指向可写的 `c_int`
+/// - `out_is_weekend` 为空或指向可写的 `c_int`
+/// - `out_festival` 为空或指向可写的 `c_int`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_debug_environment(
+    ctx_ptr: *const TradeContext,
+    cfg_ptr: *const MarketConfig,
+    out_day_of_week: *mut c_int,
+    out_is_weekend: *mut c_int,
+    out_festival: *mut c_int,
+) -> c_int {
+    ffi_guard!(|| {
+        if ctx_ptr.is_null() || out_day_of_week.is_null() || out_is_weekend.is_null() || out_festival.is_null() {
+            return EconStatus::NullPointer;
+        }
+        // cfg 可为空，此时按默认周末 (周六、周日) 判定
+        let weekend_mask = if cfg_ptr.is_null() {
+            DEFAULT_WEEKEND_MASK
+        } else {
+            (*cfg_ptr).effective_weekend_mask()
+        };
+        let calendar = economy::environment::local_calendar(&*ctx_ptr, weekend_mask);
+        *out_day_of_week = calendar.day_of_week;
+        *out_is_weekend = calendar.is_weekend as c_int;
+        *out_festival = calendar.festival as c_int;
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// - `ctx_ptr` 为空或指向有效的 `TradeContext`
+/// - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_calculate_epsilon_jittered(
+    ctx_ptr: *const TradeContext,
+    cfg_ptr: *const MarketConfig,
+    seed: c_ulonglong,
+    jitter_pct: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if ctx_ptr.is_null() || cfg_ptr.is_null() || out_result.is_null() {
+            return EconStatus::NullPointer;
+        }
+        if !(0.0..=economy::environment::MAX_JITTER_PCT).contains(&jitter_pct) {
+            return EconStatus::InvalidValue;
+        }
+        *out_result = economy::environment::calculate_epsilon_jittered(&*ctx_ptr, &*cfg_ptr, seed, jitter_pct);
+        EconStatus::Ok
+    })
+}
+
+// -----------------------------------------------------------------------------
+// 5. 安全审计与动态限额
+// -----------------------------------------------------------------------------
+
+/// 转账审计 (纯计算，无副作用)：预览与重复检查不会写入拦截审计轨迹，
+/// 实际执行时被拒绝的转账由调用方通过 ecobridge_log_blocked_transfer 记录。
+///
+/// # Safety
+/// - `out_result` 为空或指向可写的 `TransferResult`
+/// - `ctx_ptr` 为空或指向有效的 `TransferContext`
+/// - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_transfer_check(
+    out_result: *mut TransferResult,
+    ctx_ptr: *const TransferContext,
+    cfg_ptr: *const RegulatorConfig,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() || ctx_ptr.is_null() || cfg_ptr.is_null() {
+            return EconStatus::NullPointer;
+        }
+
+        let res = security::regulator::compute_transfer_check_internal(&*ctx_ptr, &*cfg_ptr);
+        ptr::write(out_result, res);
+        EconStatus::Ok
+    })
+}
+
+/// 定点 (整分) 审计：拦截判定与 ecobridge_compute_transfer_check 相同，税额以整分写入 `out_tax`，
+/// `out_result.final_tax_micros` 恰为其 10_000 倍。精度保证见 security::regulator 定点计税一节。
+///
+/// # Safety
+/// - `out_result` 为空或指向可写的 `TransferResult`
+/// - `out_tax` 为空或指向可写的 `PriceI64`
+/// - `ctx_ptr` 为空或指向有效的 `TransferContext`
+/// - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_transfer_check_fixed(
+    out_result: *mut TransferResult,
+    out_tax: *mut PriceI64,
+    ctx_ptr: *const TransferContext,
+    cfg_ptr: *const RegulatorConfig,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() || out_tax.is_null() || ctx_ptr.is_null() || cfg_ptr.is_null() {
+            return EconStatus::NullPointer;
+        }
+
+        let (res, tax) = security::regulator::compute_transfer_check_fixed(&*ctx_ptr, &*cfg_ptr);
+        ptr::write(out_result, res);
+        ptr::write(out_tax, tax);
+        EconStatus::Ok
+    })
+}
+
+/// 定点 (整分) 计税，不做拦截判定；参数非有限时返回 `InvalidValue`
+///
+/// # Safety
+/// - `ctx_ptr` 为空或指向有效的 `TransferContext`
+/// - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+/// - `out_tax` 为空或指向可写的 `PriceI64`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_tax_fixed(
+    ctx_ptr: *const TransferContext,
+    cfg_ptr: *const RegulatorConfig,
+    out_tax: *mut PriceI64,
+) -> c_int {
+    ffi_guard!(|| {
+        if ctx_ptr.is_null() || cfg_ptr.is_null() || out_tax.is_null() {
+            return EconStatus::NullPointer;
+        }
+        match security::regulator::compute_tax_fixed(&*ctx_ptr, &*cfg_ptr) {
+            Some(tax) => {
+                *out_tax = tax;
+                EconStatus::Ok
+            }
+            None => EconStatus::InvalidValue,
+        }
+    })
+}
+
+/// 设置全局审计配置 (复制保存)，供 ecobridge_compute_transfer_check_global 使用；
+/// 传入 NULL 清除。多策略调用方继续使用显式传参的 ecobridge_compute_transfer_check。
+///
+/// # Safety
+/// `cfg_ptr` 须指向有效的 `RegulatorConfig`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_set_global_regulator_config(cfg_ptr: *const RegulatorConfig) -> c_int {
+    ffi_guard!(|| {
+        security::regulator::set_global_config(cfg_ptr.as_ref().copied());
+        EconStatus::Ok
+    })
+}
+
+/// 使用全局配置审计。未设置全局配置时返回 warning_code = -1 的拒绝结果，
+/// 空指针或 panic 时返回 warning_code = -2 的拒绝结果。
+///
+/// # Safety
+/// `ctx_ptr` 须指向有效的 `TransferContext`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_transfer_check_global(
+    ctx_ptr: *const TransferContext,
+) -> TransferResult {
+    use security::regulator::{error_result, CODE_ERROR_INVALID_CALL};
+    ffi_guard!(error_result(CODE_ERROR_INVALID_CALL), || {
+        let Some(ctx) = ctx_ptr.as_ref() else {
+            return error_result(CODE_ERROR_INVALID_CALL);
+        };
+        security::regulator::compute_transfer_check_global(ctx)
+    })
+}
+
+/// 税费构成明细 (用于玩家税单展示)；空指针或 panic 时返回全零
+///
+/// # Safety
+/// - `ctx_ptr` 为空或指向有效的 `TransferContext`
+/// - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_tax_breakdown(
+    ctx_ptr: *const TransferContext,
+    cfg_ptr: *const RegulatorConfig,
+) -> TaxBreakdown {
+    ffi_guard!(TaxBreakdown::default(), || {
+        if ctx_ptr.is_null() || cfg_ptr.is_null() {
+            return TaxBreakdown::default();
+        }
+        security::regulator::compute_tax_breakdown_internal(&*ctx_ptr, &*cfg_ptr)
+    })
+}
+
+/// 批量审计：`ctx_array_ptr` 与 `out_results_ptr` 均须包含 `count` 个元素。
+/// 返回处理的笔数，参数非法时返回负的 EconStatus。
+///
+/// # Safety
+/// - `ctx_array_ptr` 为空或指向至少 `count` 个连续可读的 `TransferContext`
+/// - `cfg_ptr` 为空或指向有效的 `RegulatorConfig`
+/// - `out_results_ptr` 为空或指向至少 `count` 个连续可写的 `TransferResult`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_transfer_check_batch(
+    ctx_array_ptr: *const TransferContext,
+    cfg_ptr: *const RegulatorConfig,
+    count: usize,
+    out_results_ptr: *mut TransferResult,
+) -> c_int {
+    ffi_guard!(-(EconStatus::Panic as c_int), || {
+        if ctx_array_ptr.is_null() || cfg_ptr.is_null() || out_results_ptr.is_null() {
+            return -(EconStatus::NullPointer as c_int);
+        }
+        if count == 0 || count > 1_000_000 { return -(EconStatus::InvalidLength as c_int); }
+
+        let ctxs = std::slice::from_raw_parts(ctx_array_ptr, count);
+        let out = std::slice::from_raw_parts_mut(out_results_ptr, count);
+        security::regulator::compute_transfer_check_batch(ctxs, &*cfg_ptr, out) as c_int
+    })
+}
+
+/// 记录一笔实际执行时被拦截的转账 (以当前时间计)，供 ecobridge_query_blocked_count 统计。
+/// 仅接受正的拦截原因码；调用错误码 (<= 0) 返回 InvalidValue 且不记录。
+#[no_mangle]
+pub extern "C" fn ecobridge_log_blocked_transfer(warning_code: c_int) -> c_int {
+    ffi_guard!(|| {
+        if warning_code <= 0 { return EconStatus::InvalidValue; }
+        storage::log_blocked_transfer_now(warning_code);
+        EconStatus::Ok
+    })
+}
+
+/// 统计最近 `days` 天内因 `code` 被拦截的转账次数 (code < 0 表示全部原因)
+///
+/// # Safety
+/// `out_count` 为空或指向可写的 `u64`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_query_blocked_count(
+    days: c_double,
+    code: c_int,
+    out_count: *mut u64,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_count.is_null() { return EconStatus::NullPointer; }
+        *out_count = storage::query_blocked_count(days, code);
         EconStatus::Ok
     })
 }
 
-// -----------------------------------------------------------------------------
-// 5. 安全审计与动态限额
-// -----------------------------------------------------------------------------
-
+/// 拆分规避评分 [0, 1]：`amounts_ptr` 为发送方近期转账金额 (标准单位)
+///
+/// # Safety
+/// - `amounts_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+/// - `out_result` 为空或指向可写的 `c_double`
 #[no_mangle]
-pub unsafe extern "C" fn ecobridge_compute_transfer_check(
-    out_result: *mut TransferResult,
-    ctx_ptr: *const TransferContext,
-    cfg_ptr: *const RegulatorConfig,
+pub unsafe extern "C" fn ecobridge_structuring_score(
+    amounts_ptr: *const c_double,
+    count: usize,
+    threshold: c_double,
+    out_result: *mut c_double,
 ) -> c_int {
     ffi_guard!(|| {
-        if out_result.is_null() || ctx_ptr.is_null() || cfg_ptr.is_null() {
-            return EconStatus::NullPointer;
-        }
-
-        let res = security::regulator::compute_transfer_check_internal(&*ctx_ptr, &*cfg_ptr);
-        ptr::write(out_result, res);
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if count > 1_000_000 { return EconStatus::InvalidLength; }
+        if count > 0 && amounts_ptr.is_null() { return EconStatus::NullPointer; }
+        let amounts = if count == 0 { &[][..] } else { std::slice::from_raw_parts(amounts_ptr, count) };
+        *out_result = security::structuring_score(amounts, threshold);
         EconStatus::Ok
     })
 }
 
+/// # Safety
+/// `out_result` 为空或指向可写的 `c_double`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_get_dynamic_limit(
     play_time_secs: c_longlong,
@@ -650,6 +2102,9 @@ pub unsafe extern "C" fn ecobridge_get_dynamic_limit(
 // 6. PID 控制
 // -----------------------------------------------------------------------------
 
+/// # Safety
+/// - `pid_ptr` 为空或指向可读写的 `PidState`
+/// - `out_result` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_compute_pid_adjustment(
     pid_ptr: *mut PidState,
@@ -670,11 +2125,104 @@ pub unsafe extern "C" fn ecobridge_compute_pid_adjustment(
             );
             EconStatus::Ok
         } else {
-            return EconStatus::NullPointer;
+            EconStatus::NullPointer
+        }
+    })
+}
+
+/// 校验并写入 PID 参数 (替代直接改写结构体字段)。
+/// 成功返回 0；参数非法时返回首个非法字段编号的负值
+/// (-1 = kp, -2 = ki, -3 = kd, -4 = lambda, -5 = integration_limit)，此时结构体不被修改；
+/// 空指针与 panic 仍以正的 EconStatus 返回。
+///
+/// # Safety
+/// `pid_ptr` 须指向可读写的 `PidState`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_configure_pid(
+    pid_ptr: *mut PidState,
+    kp: c_double,
+    ki: c_double,
+    kd: c_double,
+    lambda: c_double,
+    integration_limit: c_double,
+) -> c_int {
+    ffi_guard!(EconStatus::Panic as c_int, || {
+        let Some(pid) = pid_ptr.as_mut() else {
+            return EconStatus::NullPointer as c_int;
+        };
+        match economy::control::configure_pid(pid, kp, ki, kd, lambda, integration_limit) {
+            Ok(()) => EconStatus::Ok as c_int,
+            Err(field) => -field,
         }
     })
 }
 
+/// 配置 PID 输出平滑：单步最大变化量与误差死区 (0 表示关闭)。
+/// 返回约定同 ecobridge_configure_pid (-6 = max_delta_per_step, -7 = deadband)。
+///
+/// # Safety
+/// `pid_ptr` 须指向可读写的 `PidState`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_configure_pid_output(
+    pid_ptr: *mut PidState,
+    max_delta_per_step: c_double,
+    deadband: c_double,
+) -> c_int {
+    ffi_guard!(EconStatus::Panic as c_int, || {
+        let Some(pid) = pid_ptr.as_mut() else {
+            return EconStatus::NullPointer as c_int;
+        };
+        match economy::control::configure_pid_output(pid, max_delta_per_step, deadband) {
+            Ok(()) => EconStatus::Ok as c_int,
+            Err(field) => -field,
+        }
+    })
+}
+
+/// 配置微分低通滤波系数 (0, 1]，默认 0.3；越小滤波越重。非法时返回 -8。
+///
+/// # Safety
+/// `pid_ptr` 须指向可读写的 `PidState`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_configure_pid_derivative_filter(
+    pid_ptr: *mut PidState,
+    alpha: c_double,
+) -> c_int {
+    ffi_guard!(EconStatus::Panic as c_int, || {
+        let Some(pid) = pid_ptr.as_mut() else {
+            return EconStatus::NullPointer as c_int;
+        };
+        match economy::control::configure_pid_derivative_filter(pid, alpha) {
+            Ok(()) => EconStatus::Ok as c_int,
+            Err(field) => -field,
+        }
+    })
+}
+
+/// 手动调价结束、重新启用 PID 前调用：反算积分项使首次自动输出接近 `current_output`
+///
+/// # Safety
+/// `pid_ptr` 须指向可读写的 `PidState`。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_pid_bumpless_transfer(
+    pid_ptr: *mut PidState,
+    current_output: c_double,
+    current_vel: c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        let Some(pid) = pid_ptr.as_mut() else {
+            return EconStatus::NullPointer as c_int;
+        };
+        if economy::control::prime_for_bumpless(pid, current_output, current_vel) {
+            EconStatus::Ok as c_int
+        } else {
+            EconStatus::InvalidValue as c_int
+        }
+    })
+}
+
+/// # Safety
+/// `pid_ptr` 须指向可读写的 `PidState`。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_reset_pid_state(pid_ptr: *mut PidState) -> c_int {
     ffi_guard!(|| {
@@ -682,7 +2230,7 @@ pub unsafe extern "C" fn ecobridge_reset_pid_state(pid_ptr: *mut PidState) -> c_
             *pid = PidState::default();
             EconStatus::Ok
         } else {
-            return EconStatus::NullPointer
+            EconStatus::NullPointer
         }
     })
 }
@@ -691,6 +2239,8 @@ pub unsafe extern "C" fn ecobridge_reset_pid_state(pid_ptr: *mut PidState) -> c_
 // 7. GARCH 波动率建模
 // -----------------------------------------------------------------------------
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_garch_init(
     key_ptr: *const c_char,
@@ -708,6 +2258,9 @@ pub unsafe extern "C" fn ecobridge_garch_init(
     })
 }
 
+/// # Safety
+/// - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_vol` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_garch_update(
     key_ptr: *const c_char,
@@ -728,6 +2281,9 @@ pub unsafe extern "C" fn ecobridge_garch_update(
     })
 }
 
+/// # Safety
+/// - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_vol` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_garch_forecast(
     key_ptr: *const c_char,
@@ -748,6 +2304,9 @@ pub unsafe extern "C" fn ecobridge_garch_forecast(
     })
 }
 
+/// # Safety
+/// - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_mult` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_garch_multiplier(
     key_ptr: *const c_char,
@@ -767,6 +2326,8 @@ pub unsafe extern "C" fn ecobridge_garch_multiplier(
     })
 }
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_garch_free(
     key_ptr: *const c_char,
@@ -786,6 +2347,8 @@ pub unsafe extern "C" fn ecobridge_garch_free(
 // 8. 卡尔曼滤波
 // -----------------------------------------------------------------------------
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_kalman_init(
     key_ptr: *const c_char,
@@ -801,6 +2364,9 @@ pub unsafe extern "C" fn ecobridge_kalman_init(
     })
 }
 
+/// # Safety
+/// - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_filtered` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_kalman_filter(
     key_ptr: *const c_char,
@@ -822,6 +2388,9 @@ pub unsafe extern "C" fn ecobridge_kalman_filter(
     })
 }
 
+/// # Safety
+/// - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_vel` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_kalman_velocity(
     key_ptr: *const c_char,
@@ -841,6 +2410,8 @@ pub unsafe extern "C" fn ecobridge_kalman_velocity(
     })
 }
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_kalman_free(
     key_ptr: *const c_char,
@@ -860,6 +2431,8 @@ pub unsafe extern "C" fn ecobridge_kalman_free(
 // 9. ARIMA 时序预测
 // -----------------------------------------------------------------------------
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_arima_init(
     key_ptr: *const c_char,
@@ -877,6 +2450,8 @@ pub unsafe extern "C" fn ecobridge_arima_init(
     })
 }
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_arima_add_obs(
     key_ptr: *const c_char,
@@ -893,6 +2468,9 @@ pub unsafe extern "C" fn ecobridge_arima_add_obs(
     })
 }
 
+/// # Safety
+/// - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_pred` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_arima_predict(
     key_ptr: *const c_char,
@@ -913,6 +2491,8 @@ pub unsafe extern "C" fn ecobridge_arima_predict(
     })
 }
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_arima_free(
     key_ptr: *const c_char,
@@ -932,6 +2512,8 @@ pub unsafe extern "C" fn ecobridge_arima_free(
 // 10. MPC 模型预测控制
 // -----------------------------------------------------------------------------
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_mpc_init(
     key_ptr: *const c_char,
@@ -948,6 +2530,12 @@ pub unsafe extern "C" fn ecobridge_mpc_init(
     })
 }
 
+/// # Safety
+/// - `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_lambda` 为空或指向可写的 `c_double`
+/// - `out_sink` 为空或指向可写的 `c_double`
+/// - `out_faucet` 为空或指向可写的 `c_double`
+/// - `out_pred_m1` 为空或指向可写的 `c_double`
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_mpc_optimize(
     key_ptr: *const c_char,
@@ -984,6 +2572,8 @@ pub unsafe extern "C" fn ecobridge_mpc_optimize(
     })
 }
 
+/// # Safety
+/// `key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串。
 #[no_mangle]
 pub unsafe extern "C" fn ecobridge_mpc_free(
     key_ptr: *const c_char,
@@ -998,3 +2588,617 @@ pub unsafe extern "C" fn ecobridge_mpc_free(
         EconStatus::Ok
     })
 }
+
+// -----------------------------------------------------------------------------
+// 11. 批量结算轧差
+// -----------------------------------------------------------------------------
+
+/// 对一批玩家间应付款做轧差，将最小化后的结算转账写入 `out_ptr`，所需笔数写入 `out_count`。
+/// 若所需笔数超过 `max_out`，仅写入前 `max_out` 笔 (调用方可据 `out_count` 扩容重试)。
+/// 空输入返回 Ok 且笔数为 0；指针为空返回 NullPointer，`count` 超过 1_000_000 返回 InvalidLength。
+///
+/// # Safety
+/// - `transfers_ptr` 为空或指向至少 `count` 个连续可读的 `PairTransfer`
+/// - `out_ptr` 为空或指向至少 `max_out` 个连续可写的 `PairTransfer`
+/// - `out_count` 为空或指向可写的 `usize`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_net_settle(
+    transfers_ptr: *const PairTransfer,
+    count: usize,
+    out_ptr: *mut PairTransfer,
+    max_out: usize,
+    out_count: *mut usize,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_count.is_null() { return EconStatus::NullPointer; }
+        if count == 0 {
+            *out_count = 0;
+            return EconStatus::Ok;
+        }
+        if transfers_ptr.is_null() || (out_ptr.is_null() && max_out > 0) { return EconStatus::NullPointer; }
+        if count > 1_000_000 { return EconStatus::InvalidLength; }
+
+        let transfers = std::slice::from_raw_parts(transfers_ptr, count);
+        let settled = economy::settlement::net_settle(transfers);
+        let written = settled.len().min(max_out);
+        if written > 0 {
+            ptr::copy_nonoverlapping(settled.as_ptr(), out_ptr, written);
+        }
+        *out_count = settled.len();
+        EconStatus::Ok
+    })
+}
+
+// -----------------------------------------------------------------------------
+// 12. 持仓估值
+// -----------------------------------------------------------------------------
+
+/// # Safety
+/// - `quantities_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+/// - `prices_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_portfolio_value(
+    quantities_ptr: *const c_double,
+    prices_ptr: *const c_double,
+    count: usize,
+) -> c_double {
+    ffi_guard!(0.0, || {
+        if quantities_ptr.is_null() || prices_ptr.is_null() || count == 0 || count > 1_000_000 {
+            return 0.0;
+        }
+        let quantities = std::slice::from_raw_parts(quantities_ptr, count);
+        let prices = std::slice::from_raw_parts(prices_ptr, count);
+        economy::portfolio::portfolio_value(quantities, prices)
+    })
+}
+
+/// # Safety
+/// - `quantities_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+/// - `prices_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_portfolio_value_with_liquidation(
+    quantities_ptr: *const c_double,
+    prices_ptr: *const c_double,
+    count: usize,
+) -> c_double {
+    ffi_guard!(0.0, || {
+        if quantities_ptr.is_null() || prices_ptr.is_null() || count == 0 || count > 1_000_000 {
+            return 0.0;
+        }
+        let quantities = std::slice::from_raw_parts(quantities_ptr, count);
+        let prices = std::slice::from_raw_parts(prices_ptr, count);
+        economy::portfolio::portfolio_value_with_liquidation(quantities, prices)
+    })
+}
+
+// -----------------------------------------------------------------------------
+// 13. 市场诊断指标
+// -----------------------------------------------------------------------------
+
+/// 基于内存热历史估计价格弹性；`market_key_ptr` 为空时查询全局聚合
+///
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_estimate_elasticity(
+    market_key_ptr: *const c_char,
+    tau: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        if !tau.is_finite() || tau <= 0.0 { return EconStatus::InvalidValue; }
+        let market_key = market_key_or_global(market_key_ptr);
+        *out_result = storage::with_history(Some(&market_key), |history| {
+            economy::estimate_elasticity(history, tau)
+        });
+        EconStatus::Ok
+    })
+}
+
+/// 时间加权均价：`timestamps` 与 `prices` 为等长、按时间升序的采样，最后一个价格持续到 `end_ts`
+///
+/// # Safety
+/// - `timestamps_ptr` 为空或指向至少 `count` 个连续可读的 `c_longlong`
+/// - `prices_ptr` 为空或指向至少 `count` 个连续可读的 `c_double`
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_twap(
+    timestamps_ptr: *const c_longlong,
+    prices_ptr: *const c_double,
+    count: usize,
+    end_ts: c_longlong,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_result.is_null() || timestamps_ptr.is_null() || prices_ptr.is_null() {
+            return EconStatus::NullPointer;
+        }
+        if count == 0 || count > 1_000_000 { return EconStatus::InvalidLength; }
+        let timestamps = std::slice::from_raw_parts(timestamps_ptr, count);
+        let prices = std::slice::from_raw_parts(prices_ptr, count);
+        if timestamps.windows(2).any(|w| w[0] > w[1]) { return EconStatus::InvalidValue; }
+        let samples: Vec<(i64, f64)> = timestamps.iter().copied().zip(prices.iter().copied()).collect();
+        *out_result = economy::time_weighted_average(&samples, end_ts);
+        EconStatus::Ok
+    })
+}
+
+/// 基于内存热历史的价格趋势 (-1 下跌 / 0 平稳 / 1 上涨)；`market_key_ptr` 为空时查询全局聚合
+///
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_trend` 为空或指向可写的 `c_int`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_price_trend(
+    market_key_ptr: *const c_char,
+    short_tau: c_double,
+    long_tau: c_double,
+    out_trend: *mut c_int,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_trend.is_null() { return EconStatus::NullPointer; }
+        if !(short_tau > 0.0 && short_tau < long_tau && long_tau.is_finite()) {
+            return EconStatus::InvalidValue;
+        }
+        let market_key = market_key_or_global(market_key_ptr);
+        *out_trend = storage::with_history(Some(&market_key), |history| {
+            economy::price_trend(history, short_tau, long_tau)
+        });
+        EconStatus::Ok
+    })
+}
+
+/// 基于内存热历史的 MACD 指标 (MACD 线、信号线、柱状图)；`market_key_ptr` 为空时查询全局聚合
+///
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `out_macd` 为空或指向可写的 `c_double`
+/// - `out_signal` 为空或指向可写的 `c_double`
+/// - `out_histogram` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_compute_macd(
+    market_key_ptr: *const c_char,
+    fast_tau: c_double,
+    slow_tau: c_double,
+    signal_tau: c_double,
+    out_macd: *mut c_double,
+    out_signal: *mut c_double,
+    out_histogram: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if out_macd.is_null() || out_signal.is_null() || out_histogram.is_null() {
+            return EconStatus::NullPointer;
+        }
+        let market_key = market_key_or_global(market_key_ptr);
+        let (macd, signal, histogram) = storage::with_history(Some(&market_key), |history| {
+            economy::macro_eco::compute_macd(history, fast_tau, slow_tau, signal_tau)
+        });
+        *out_macd = macd;
+        *out_signal = signal;
+        *out_histogram = histogram;
+        EconStatus::Ok
+    })
+}
+
+// -----------------------------------------------------------------------------
+// 14. 配置校验
+// -----------------------------------------------------------------------------
+
+/// 一次性校验 MarketConfig / RegulatorConfig / PidState 的全部字段。
+/// 所有违规项以换行分隔写入 `out_buf` (可为空以仅获取数量)，返回违规项数量；
+/// 指针非法时返回负的 EconStatus。
+///
+/// # Safety
+/// - `market_ptr` 为空或指向有效的 `MarketConfig`
+/// - `regulator_ptr` 为空或指向有效的 `RegulatorConfig`
+/// - `pid_ptr` 为空或指向有效的 `PidState`
+/// - `out_buf` 须指向至少 `buf_len` 字节的可写缓冲区
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_validate_configs(
+    market_ptr: *const MarketConfig,
+    regulator_ptr: *const RegulatorConfig,
+    pid_ptr: *const PidState,
+    out_buf: *mut c_char,
+    buf_len: usize,
+) -> c_int {
+    ffi_guard!(-(EconStatus::Panic as c_int), || {
+        if market_ptr.is_null() || regulator_ptr.is_null() || pid_ptr.is_null() {
+            return -(EconStatus::NullPointer as c_int);
+        }
+        let violations = models::collect_config_violations(&*market_ptr, &*regulator_ptr, &*pid_ptr);
+        write_c_buffer(out_buf, buf_len, &violations.join("\n"));
+        violations.len() as c_int
+    })
+}
+
+// -----------------------------------------------------------------------------
+// 15. 市场状态句柄 (定价缓存)
+// -----------------------------------------------------------------------------
+
+/// 创建市场状态句柄：`ctx_ptr` 为环境因子的上下文模板 (时间戳在每次定价时替换)，
+/// `ttl_ms <= 0` 使用默认 5 秒。参数非法时返回 NULL；须以 `ecobridge_market_state_free` 释放。
+///
+/// # Safety
+/// - `market_key_ptr` 为空或指向以 NUL 结尾的有效 C 字符串
+/// - `ctx_ptr` 为空或指向有效的 `TradeContext`
+/// - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_market_state_new(
+    market_key_ptr: *const c_char,
+    ctx_ptr: *const TradeContext,
+    cfg_ptr: *const MarketConfig,
+    tau: c_double,
+    ttl_ms: c_longlong,
+) -> *mut economy::market_state::MarketState {
+    ffi_guard!(ptr::null_mut(), || {
+        if market_key_ptr.is_null() || ctx_ptr.is_null() || cfg_ptr.is_null() {
+            return ptr::null_mut();
+        }
+        if !tau.is_finite() || tau <= 0.0 {
+            return ptr::null_mut();
+        }
+        let raw_key = CStr::from_ptr(market_key_ptr);
+        if raw_key.to_bytes().len() > storage::max_key_len() {
+            return ptr::null_mut();
+        }
+        let market_key = match raw_key.to_str() {
+            Ok(v) if !v.trim().is_empty() => v.trim(),
+            _ => return ptr::null_mut(),
+        };
+        let state = economy::market_state::MarketState::new(market_key, (*ctx_ptr).sanitized(), *cfg_ptr, tau, ttl_ms);
+        Box::into_raw(Box::new(state))
+    })
+}
+
+/// 使用句柄缓存的 epsilon 计算价格 (TTL 内复用，过期自动重算)；N_eff 每次重新查询
+///
+/// # Safety
+/// - `state` 须为 `ecobridge_market_state_new` 返回且尚未释放的句柄
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_market_state_price(
+    state: *mut economy::market_state::MarketState,
+    current_ts: c_longlong,
+    base: c_double,
+    trade_amount: c_double,
+    lambda: c_double,
+    out_result: *mut c_double,
+) -> c_int {
+    pricing_guard!(out_result, base, || {
+        if out_result.is_null() { return EconStatus::NullPointer; }
+        let Some(state) = state.as_mut() else {
+            return EconStatus::NullPointer;
+        };
+        *out_result = state.price(current_ts, to_micros_saturating(base), to_micros_saturating(trade_amount), lambda);
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// `state` 为空或为 `ecobridge_market_state_new` 返回且尚未释放的句柄，调用后不得再使用。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_market_state_free(state: *mut economy::market_state::MarketState) {
+    if state.is_null() { return; }
+    ffi_guard!((), || drop(Box::from_raw(state)))
+}
+
+// -----------------------------------------------------------------------------
+// 16. 热度平滑句柄
+// -----------------------------------------------------------------------------
+
+/// 创建热度平滑句柄 (`alpha` 非法时使用默认 0.2)；须以 `ecobridge_heat_smoother_free` 释放
+#[no_mangle]
+pub extern "C" fn ecobridge_heat_smoother_new(alpha: c_double) -> *mut economy::environment::HeatSmoother {
+    ffi_guard!(ptr::null_mut(), || {
+        Box::into_raw(Box::new(economy::environment::HeatSmoother::new(alpha)))
+    })
+}
+
+/// 以平滑后的 market_heat / eco_saturation 计算 epsilon，并更新句柄状态
+///
+/// # Safety
+/// - `smoother` 须为 `ecobridge_heat_smoother_new` 返回且尚未释放的句柄
+/// - `ctx_ptr` 为空或指向有效的 `TradeContext`
+/// - `cfg_ptr` 为空或指向有效的 `MarketConfig`
+/// - `out_result` 为空或指向可写的 `c_double`
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_calculate_epsilon_smoothed(
+    smoother: *mut economy::environment::HeatSmoother,
+    ctx_ptr: *const TradeContext,
+    cfg_ptr: *const MarketConfig,
+    out_result: *mut c_double,
+) -> c_int {
+    ffi_guard!(|| {
+        if ctx_ptr.is_null() || cfg_ptr.is_null() || out_result.is_null() {
+            return EconStatus::NullPointer;
+        }
+        let Some(smoother) = smoother.as_mut() else {
+            return EconStatus::NullPointer;
+        };
+        let ctx = (*ctx_ptr).sanitized();
+        let cfg = (*cfg_ptr).sanitized();
+        *out_result = smoother.epsilon(&ctx, &cfg);
+        EconStatus::Ok
+    })
+}
+
+/// # Safety
+/// `smoother` 为空或为 `ecobridge_heat_smoother_new` 返回且尚未释放的句柄，调用后不得再使用。
+#[no_mangle]
+pub unsafe extern "C" fn ecobridge_heat_smoother_free(smoother: *mut economy::environment::HeatSmoother) {
+    if smoother.is_null() { return; }
+    ffi_guard!((), || drop(Box::from_raw(smoother)))
+}
+
+/// 测试间共享的进程级状态锁
+///
+/// 整体替换热存储、使 N_eff 缓存失效或切换 SIMD 开关等修改进程级全局状态的测试持有独占锁；
+/// 向全局热存储追加记录、或结果依赖这些状态的测试持有共享锁。二者互斥，其余测试照常并行。
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    static GLOBAL_STATE: RwLock<()> = RwLock::new(());
+
+    /// 每个测试时间区间的宽度 (约 1157 天，大于任何测试使用的 10·tau 衰减视界)
+    const ISOLATED_SPAN_MS: i64 = 100_000_000_000;
+    static NEXT_ISOLATED_SLOT: AtomicI64 = AtomicI64::new(0);
+
+    /// 分配一段测试专属时间区间的起点 (2096 年之后，远离真实时间与其他测试)
+    ///
+    /// 热存储与审计日志在进程内共享，且并行测试的写入无法撤销；按时间窗口查询的测试
+    /// 各自取一段互不重叠的区间，就不会统计到其他测试写入的记录。
+    pub fn isolated_ts() -> i64 {
+        4_000_000_000_000 + NEXT_ISOLATED_SLOT.fetch_add(1, Ordering::Relaxed) * ISOLATED_SPAN_MS
+    }
+
+    pub fn shared() -> RwLockReadGuard<'static, ()> {
+        GLOBAL_STATE.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn exclusive() -> RwLockWriteGuard<'static, ()> {
+        GLOBAL_STATE.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CALLBACK_HITS: AtomicU64 = AtomicU64::new(0);
+
+    extern "C" fn count_panics(_count: u64, msg: *const c_char) {
+        let text = unsafe { CStr::from_ptr(msg) }.to_string_lossy();
+        if text.contains("callback probe") {
+            CALLBACK_HITS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_panic_callback_invoked_and_threshold_validated() {
+        ecobridge_set_panic_callback(Some(count_panics));
+        let status = ffi_guard!(|| -> EconStatus { panic!("callback probe") });
+        ecobridge_set_panic_callback(None);
+
+        assert_eq!(status, EconStatus::Panic as c_int);
+        assert_eq!(CALLBACK_HITS.load(Ordering::SeqCst), 1);
+        assert!(ecobridge_get_panic_count() >= 1);
+        assert_eq!(ecobridge_set_panic_threshold(0), EconStatus::InvalidValue as c_int);
+    }
+
+    #[test]
+    fn test_health_report_reflects_logging_activity() {
+        let _state = test_support::shared();
+        let before = {
+            let mut r = HealthReport::default();
+            assert_eq!(unsafe { ecobridge_get_health_report(&mut r) }, EconStatus::Ok as c_int);
+            r
+        };
+        storage::append_to_memory(test_support::isolated_ts(), 1.0, "test_health_report");
+        storage::record_dropped();
+
+        let mut after = HealthReport::default();
+        assert_eq!(unsafe { ecobridge_get_health_report(&mut after) }, EconStatus::Ok as c_int);
+        assert!(after.total_logs > before.total_logs);
+        assert!(after.dropped_logs > before.dropped_logs);
+        assert!(after.history_len >= 1);
+        assert!(after.panic_count >= before.panic_count);
+        assert_eq!(unsafe { ecobridge_get_health_report(ptr::null_mut()) }, EconStatus::NullPointer as c_int);
+    }
+
+    #[test]
+    fn test_build_features_lists_compiled_in_flags() {
+        let features = unsafe { CStr::from_ptr(ecobridge_build_features()) }.to_str().unwrap();
+        let flags: Vec<&str> = features.split(',').collect();
+        assert_eq!(flags[0], std::env::consts::ARCH);
+        assert_eq!(flags.contains(&"parallel"), cfg!(feature = "parallel"));
+        assert_eq!(flags.contains(&"fast-exp"), cfg!(feature = "fast-exp"));
+    }
+
+    #[test]
+    fn test_pricing_fallback_mode() {
+        let _guard = test_support::exclusive();
+        let mut out = 0.0_f64;
+        let out_ptr: *mut f64 = &mut out;
+        let probe = |out_ptr: *mut f64| unsafe { pricing_guard!(out_ptr, 42.0, || -> EconStatus { panic!("pricing probe") }) };
+
+        assert_eq!(probe(out_ptr), EconStatus::Panic as c_int);
+        assert_eq!(out, 0.0, "default mode leaves the output untouched");
+
+        assert_eq!(ecobridge_set_pricing_fallback_mode(PRICING_FALLBACK_SENTINEL), EconStatus::Ok as c_int);
+        let status = probe(out_ptr);
+        assert_eq!(status, EconStatus::Panic as c_int);
+        assert_eq!(out, PRICING_PANIC_SENTINEL);
+
+        assert_eq!(ecobridge_set_pricing_fallback_mode(PRICING_FALLBACK_BASE), EconStatus::Ok as c_int);
+        let status = probe(out_ptr);
+        assert_eq!(ecobridge_set_pricing_fallback_mode(PRICING_FALLBACK_NONE), EconStatus::Ok as c_int);
+        assert_eq!(status, EconStatus::Panic as c_int);
+        assert_eq!(out, 42.0);
+
+        assert_eq!(ecobridge_set_pricing_fallback_mode(3), EconStatus::InvalidValue as c_int);
+    }
+
+    #[test]
+    fn test_stability_ex_window_and_fallback() {
+        let mut out = 0.0;
+        // 5 分钟后：15 分钟窗口恢复 1/3，5 分钟窗口已完全恢复
+        assert_eq!(unsafe { ecobridge_calc_stability(1_000, 301_000, &mut out) }, EconStatus::Ok as c_int);
+        assert!((out - 1.0 / 3.0).abs() < 1e-12);
+        unsafe { ecobridge_calc_stability_ex(1_000, 301_000, 300_000.0, &mut out) };
+        assert_eq!(out, 1.0);
+
+        // 非法窗口回退为默认 15 分钟
+        unsafe { ecobridge_calc_stability_ex(1_000, 301_000, 0.0, &mut out) };
+        assert!((out - 1.0 / 3.0).abs() < 1e-12);
+        unsafe { ecobridge_calc_stability_ex(1_000, 301_000, f64::NAN, &mut out) };
+        assert!((out - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_oversized_market_key_rejected() {
+        let huge = std::ffi::CString::new("k".repeat(5000)).unwrap();
+        let dropped_before = storage::get_dropped_logs();
+
+        let status = unsafe { ecobridge_append_trade_to_memory(1_000, 1.0, huge.as_ptr()) };
+        assert_eq!(status, EconStatus::InvalidLength as c_int);
+        let status = unsafe { inject_remote_trade_for_key(huge.as_ptr(), 1) };
+        assert_eq!(status, EconStatus::InvalidLength as c_int);
+
+        assert!(storage::get_dropped_logs() >= dropped_before + 2);
+        let key = huge.to_str().unwrap();
+        assert_eq!(storage::with_history(Some(key), |h| h.len()), 0, "oversized key must not be stored");
+        assert_eq!(ecobridge_set_max_key_len(0), EconStatus::InvalidValue as c_int);
+    }
+
+    #[test]
+    fn test_calc_inflation_sanitizes_nan_heat() {
+        let mut out = f64::NAN;
+        let status = unsafe { ecobridge_calc_inflation(f64::NAN, 1000.0, &mut out) };
+        assert_eq!(status, EconStatus::Ok as c_int);
+        assert_eq!(out, 0.0);
+
+        let status = unsafe { ecobridge_calc_inflation(100.0, f64::INFINITY, &mut out) };
+        assert_eq!(status, EconStatus::InvalidValue as c_int);
+    }
+
+    #[test]
+    fn test_calc_decay_sanitizes_nan_inputs() {
+        let mut out = f64::NAN;
+        let status = unsafe { ecobridge_calc_decay(f64::NAN, f64::NAN, &mut out) };
+        assert_eq!(status, EconStatus::Ok as c_int);
+        assert!(out.is_finite());
+
+        let status = unsafe { ecobridge_calc_decay(1000.0, f64::NEG_INFINITY, &mut out) };
+        assert_eq!(status, EconStatus::Ok as c_int);
+        assert!(out.is_finite());
+    }
+
+    #[test]
+    fn test_calculate_epsilon_sanitizes_nan_fields() {
+        let ctx = TradeContext {
+            current_timestamp: 1_700_000_000_000,
+            inflation_rate: f64::NAN,
+            ..Default::default()
+        };
+        let cfg = MarketConfig {
+            seasonal_amplitude: f64::INFINITY,
+            inflation_weight: f64::NAN,
+            ..MarketConfig::default()
+        };
+        let mut out = f64::NAN;
+        let status = unsafe { ecobridge_calculate_epsilon(&ctx, &cfg, &mut out) };
+        assert_eq!(status, EconStatus::Ok as c_int);
+        assert!((0.1..=10.0).contains(&out), "epsilon {} should be finite and clamped", out);
+    }
+
+    #[test]
+    fn test_transfer_check_is_side_effect_free_until_logged() {
+        use security::regulator::CODE_BLOCK_QUANTITY_LIMIT;
+        let blocked = || {
+            let mut out = 0u64;
+            assert_eq!(unsafe { ecobridge_query_blocked_count(1.0, CODE_BLOCK_QUANTITY_LIMIT, &mut out) }, EconStatus::Ok as c_int);
+            out
+        };
+        let ctx = TransferContext {
+            amount_micros: 2_000_000_000,
+            item_base_limit: 1_000_000,
+            item_max_limit: 1_000_000,
+            ..Default::default()
+        };
+        let cfg = RegulatorConfig::default();
+        let before = blocked();
+
+        let mut res = TransferResult::default();
+        assert_eq!(unsafe { ecobridge_compute_transfer_check(&mut res, &ctx, &cfg) }, EconStatus::Ok as c_int);
+        assert_eq!((res.is_blocked, res.warning_code), (1, CODE_BLOCK_QUANTITY_LIMIT));
+        assert_eq!(blocked(), before, "a preview check must not touch the audit trail");
+
+        assert_eq!(ecobridge_log_blocked_transfer(res.warning_code), EconStatus::Ok as c_int);
+        assert_eq!(blocked(), before + 1);
+        assert_eq!(ecobridge_log_blocked_transfer(0), EconStatus::InvalidValue as c_int);
+    }
+
+    #[test]
+    fn test_net_settle_reports_status_and_count() {
+        let transfers = [
+            PairTransfer { from_id: 1, to_id: 2, amount_micros: 5_000_000 },
+            PairTransfer { from_id: 2, to_id: 3, amount_micros: 5_000_000 },
+        ];
+        let mut out = [PairTransfer::default(); 4];
+        let mut n = usize::MAX;
+
+        let status = unsafe { ecobridge_net_settle(transfers.as_ptr(), transfers.len(), out.as_mut_ptr(), out.len(), &mut n) };
+        assert_eq!(status, EconStatus::Ok as c_int);
+        assert_eq!(n, 1);
+        assert_eq!((out[0].from_id, out[0].to_id, out[0].amount_micros), (1, 3, 5_000_000));
+
+        // 空输入与错误可区分
+        assert_eq!(unsafe { ecobridge_net_settle(ptr::null(), 0, ptr::null_mut(), 0, &mut n) }, EconStatus::Ok as c_int);
+        assert_eq!(n, 0);
+        assert_eq!(unsafe { ecobridge_net_settle(ptr::null(), 3, out.as_mut_ptr(), 4, &mut n) }, EconStatus::NullPointer as c_int);
+        assert_eq!(unsafe { ecobridge_net_settle(transfers.as_ptr(), 1_000_001, out.as_mut_ptr(), 4, &mut n) }, EconStatus::InvalidLength as c_int);
+        assert_eq!(unsafe { ecobridge_net_settle(transfers.as_ptr(), 2, out.as_mut_ptr(), 4, ptr::null_mut()) }, EconStatus::NullPointer as c_int);
+    }
+
+    #[test]
+    fn test_null_market_key_means_global_aggregate() {
+        let _guard = test_support::shared();
+        let t0 = test_support::isolated_ts();
+        storage::append_to_memory(t0 - 1_000, 4.0, "test_null_key_market");
+        storage::append_to_memory(t0, -1.5, "test_null_key_market");
+
+        let taus = [0.001, 0.01];
+        let global = std::ffi::CString::new(economy::summation::GLOBAL_MARKET_KEY).unwrap();
+        let (mut by_null, mut by_name) = ([0.0; 2], [0.0; 2]);
+        unsafe {
+            assert_eq!(ecobridge_query_neff_multi_tau(t0, taus.as_ptr(), 2, ptr::null(), by_null.as_mut_ptr()), EconStatus::Ok as c_int);
+            assert_eq!(ecobridge_query_neff_multi_tau(t0, taus.as_ptr(), 2, global.as_ptr(), by_name.as_mut_ptr()), EconStatus::Ok as c_int);
+        }
+        assert_eq!(by_null, by_name);
+        assert!(by_null[0] > 0.0);
+    }
+
+    #[test]
+    fn test_append_with_meta_reports_fallback_without_error() {
+        let _state = test_support::shared();
+        let ts = test_support::isolated_ts();
+        let mut fallback = -1;
+        let meta = std::ffi::CString::new(r#"{"category":"test_meta_ffi_ores"}"#).unwrap();
+        let status = unsafe { ecobridge_append_trade_with_meta(ts, 1.0, meta.as_ptr(), &mut fallback) };
+        assert_eq!((status, fallback), (EconStatus::Ok as c_int, 0));
+
+        // 缺少分类：仍然写入 (返回 Ok)，回退单独回报
+        let meta = std::ffi::CString::new("not json").unwrap();
+        let status = unsafe { ecobridge_append_trade_with_meta(ts, 1.0, meta.as_ptr(), &mut fallback) };
+        assert_eq!((status, fallback), (EconStatus::Ok as c_int, 1));
+        let status = unsafe { ecobridge_append_trade_with_meta(ts, 1.0, meta.as_ptr(), ptr::null_mut()) };
+        assert_eq!(status, EconStatus::Ok as c_int);
+        assert!(storage::with_history(Some("test_meta_ffi_ores"), |h| h.len()) >= 1);
+    }
+}
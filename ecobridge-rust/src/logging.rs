@@ -0,0 +1,123 @@
+// ==================================================
+// FILE: ecobridge-rust/src/logging.rs
+// ==================================================
+
+//! Native Logging Bridge
+//!
+//! 将 `log` 门面的记录转发给 Java 侧注册的回调；未注册时输出到 stderr。
+//! 日志器在库初始化 (`ecobridge_init_threading`) 或首次注册回调、设置级别时安装一次。
+//! 级别编号与 `log::Level` 一致：1 = Error, 2 = Warn, 3 = Info, 4 = Debug, 5 = Trace，0 表示关闭。
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::sync::{Once, RwLock};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Java 侧注册的日志回调：`(级别, UTF-8 消息)`
+pub type LogCallback = extern "C" fn(level: c_int, msg: *const c_char);
+
+/// 默认级别：Info
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// 当前注册的回调 (None 表示未注册，回退到 stderr)
+static LOG_CALLBACK: RwLock<Option<LogCallback>> = RwLock::new(None);
+
+static INSTALL: Once = Once::new();
+
+struct BridgeLogger;
+
+static LOGGER: BridgeLogger = BridgeLogger;
+
+impl Log for BridgeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let msg = format!("[{}] {}", record.target(), record.args());
+
+        // 临界区内不会 panic，中毒时直接取回内部值
+        let callback = *LOG_CALLBACK.read().unwrap_or_else(|e| e.into_inner());
+        match callback {
+            Some(callback) => {
+                let c_msg = CString::new(msg.replace('\0', " ")).unwrap_or_default();
+                callback(record.level() as c_int, c_msg.as_ptr());
+            }
+            None => eprintln!("[EcoBridge-Native] {} {}", record.level(), msg),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// 安装日志器 (幂等)；宿主进程已安装其他 `log` 实现时保留对方
+pub fn install() {
+    INSTALL.call_once(|| {
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(DEFAULT_LOG_LEVEL);
+        }
+    });
+}
+
+/// 注册或取消 (None) Java 日志回调
+pub fn set_callback(callback: Option<LogCallback>) {
+    install();
+    *LOG_CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = callback;
+}
+
+/// 将级别编号 (0..=5) 转换为过滤器；越界返回 None
+pub fn level_filter(level: c_int) -> Option<LevelFilter> {
+    match level {
+        0 => Some(LevelFilter::Off),
+        1 => Some(LevelFilter::Error),
+        2 => Some(LevelFilter::Warn),
+        3 => Some(LevelFilter::Info),
+        4 => Some(LevelFilter::Debug),
+        5 => Some(LevelFilter::Trace),
+        _ => None,
+    }
+}
+
+/// 设置最大输出级别；越界返回 false
+pub fn set_level(level: c_int) -> bool {
+    let Some(filter) = level_filter(level) else {
+        return false;
+    };
+    install();
+    log::set_max_level(filter);
+    true
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    static PROBE_LEVEL: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn capture(level: c_int, msg: *const c_char) {
+        let text = unsafe { CStr::from_ptr(msg) }.to_string_lossy();
+        if text.contains("log callback probe") {
+            PROBE_LEVEL.store(level, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_records_routed_to_callback() {
+        set_callback(Some(capture));
+        log::warn!("log callback probe");
+        set_callback(None);
+        assert_eq!(PROBE_LEVEL.load(Ordering::SeqCst), 2);
+
+        assert_eq!(level_filter(0), Some(LevelFilter::Off));
+        assert_eq!(level_filter(5), Some(LevelFilter::Trace));
+        assert!(!set_level(6));
+    }
+}
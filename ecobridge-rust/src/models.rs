@@ -9,11 +9,11 @@
 //! 2. **保持偏移**: 由于 i64 和 f64 均为 8 字节，现有 FFM VarHandle 偏移量无需修改。
 //! 3. **系数保留**: 所有的倍率、增长率、系数（如 lambda, inflation_rate）保留为 `c_double`。
 
-use libc::{c_double, c_int, c_longlong};
+use libc::{c_double, c_int, c_longlong, c_ulonglong};
 
 // ==================== 1. 物理控制器状态 (State) ====================
 
-/// 工业级 PID 控制器状态 (72 bytes)
+/// 工业级 PID 控制器状态 (136 bytes)
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct PidState {
@@ -27,8 +27,19 @@ pub struct PidState {
     pub integration_limit: c_double, // Offset 56
     pub is_saturated: c_int,         // Offset 64
     pub _padding: c_int,             // Offset 68
+    pub prev_target: c_double,       // Offset 72: 上一次设定值
+    pub setpoint_tolerance: c_double, // Offset 80: 设定值突变判定比例 (<= 0 关闭积分复位)
+    pub schedule_pivot: c_double,    // Offset 88: 增益调度的通胀拐点
+    pub schedule_steepness: c_double, // Offset 96: 增益调度陡峭度 (<= 0 时两项均取默认值)
+    pub prev_output: c_double,       // Offset 104: 上一次输出 (<= 0 表示尚无输出)
+    pub max_delta_per_step: c_double, // Offset 112: 单步输出最大变化量 (<= 0 关闭限速)
+    pub deadband: c_double,          // Offset 120: |误差| 不超过该值时保持上一次输出 (<= 0 关闭)
+    pub derivative_alpha: c_double,  // Offset 128: 微分低通滤波系数 (0, 1]，0 表示使用默认 0.3
 }
 
+/// 默认微分低通滤波系数 (越小滤波越重)
+pub const DEFAULT_DERIVATIVE_FILTER_ALPHA: c_double = 0.3;
+
 impl Default for PidState {
     fn default() -> Self {
         Self {
@@ -36,6 +47,10 @@ impl Default for PidState {
             integral: 0.0, prev_pv: 0.0, filtered_d: 0.0,
             integration_limit: 30.0, is_saturated: 0,
             _padding: 0,
+            prev_target: 0.0, setpoint_tolerance: 0.0,
+            schedule_pivot: 0.05, schedule_steepness: 20.0,
+            prev_output: 0.0, max_delta_per_step: 0.0, deadband: 0.0,
+            derivative_alpha: DEFAULT_DERIVATIVE_FILTER_ALPHA,
         }
     }
 }
@@ -89,7 +104,20 @@ pub struct TransferContext {
 
 // ==================== 4. 环境配置模型 (Configs) ====================
 
-/// 市场动态定价配置 (72 bytes)
+/// 默认周末位掩码：周六 + 周日 (bit0 = 周一 … bit6 = 周日)
+pub const DEFAULT_WEEKEND_MASK: c_int = 0b110_0000;
+/// 周末掩码的有效位 (一周七天)
+pub const WEEKEND_MASK_BITS: c_int = 0b111_1111;
+/// 默认新手优待窗口 (小时)：优待在此期间线性衰减至 0
+pub const DEFAULT_NEWBIE_PROTECTION_HOURS: c_double = 100.0;
+/// 默认节庆乘数 (newbie_mask bit1 置位时作用于季节因子)
+pub const DEFAULT_FESTIVAL_MULTIPLIER: c_double = 1.15;
+/// 默认环境因子下限
+pub const DEFAULT_EPSILON_MIN: c_double = 0.1;
+/// 默认环境因子上限
+pub const DEFAULT_EPSILON_MAX: c_double = 10.0;
+
+/// 市场动态定价配置 (128 bytes)
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct MarketConfig {
@@ -102,6 +130,14 @@ pub struct MarketConfig {
     pub weekend_weight: c_double,        // 48
     pub newbie_weight: c_double,         // 56
     pub inflation_weight: c_double,      // 64
+    pub weekend_mask: c_int,             // 72: 周末位掩码，0 表示使用默认 (周六、周日)
+    pub _padding: c_int,                 // 76
+    pub heat_weight: c_double,           // 80: 市场热度项权重，0 表示不参与
+    pub saturation_weight: c_double,     // 88: 饱和度项权重，0 表示不参与
+    pub newbie_hours: c_double,          // 96: 新手优待线性衰减窗口 (小时)，0 表示使用默认 100 小时
+    pub festival_multiplier: c_double,   // 104: 节庆模式季节因子乘数 (>= 1)，0 表示使用默认 1.15
+    pub epsilon_min: c_double,           // 112: epsilon 最终钳位下限 (> 0)，上下限均为 0 时使用默认 [0.1, 10]
+    pub epsilon_max: c_double,           // 120: epsilon 最终钳位上限 (> epsilon_min)
 }
 
 impl Default for MarketConfig {
@@ -112,11 +148,22 @@ impl Default for MarketConfig {
             newbie_protection_rate: 0.2,
             seasonal_weight: 0.25, weekend_weight: 0.25,
             newbie_weight: 0.25, inflation_weight: 0.25,
+            weekend_mask: DEFAULT_WEEKEND_MASK, _padding: 0,
+            heat_weight: 0.0, saturation_weight: 0.0,
+            newbie_hours: DEFAULT_NEWBIE_PROTECTION_HOURS,
+            festival_multiplier: DEFAULT_FESTIVAL_MULTIPLIER,
+            epsilon_min: DEFAULT_EPSILON_MIN,
+            epsilon_max: DEFAULT_EPSILON_MAX,
         }
     }
 }
 
-/// 审计监管与计税配置 (96 bytes)
+/// 默认速率预警比例：傀儡因子超过拦截阈值的 70% 即标记高风险
+pub const DEFAULT_WARNING_VELOCITY_RATIO: c_double = 0.7;
+/// 频率惩罚系数默认值 (velocity_penalty_coef 为 0 时生效)
+pub const DEFAULT_VELOCITY_PENALTY_COEF: c_double = 0.05;
+
+/// 审计监管与计税配置 (120 bytes)
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct RegulatorConfig {
@@ -132,6 +179,9 @@ pub struct RegulatorConfig {
     pub newbie_hours: c_double,        // 72
     pub veteran_hours: c_double,       // 80
     pub velocity_threshold: c_double,  // 88
+    pub max_tax_ratio: c_double,       // 96: 税额占交易额的上限比例 (0, 1]
+    pub velocity_penalty_coef: c_double, // 104: 行为惩罚系数 exp(velocity * coef)，0 表示使用默认 0.05，负数表示关闭
+    pub warning_velocity_ratio: c_double, // 112: 速率预警线占拦截阈值的比例 (0, 1)，0 表示使用默认 0.7
 }
 
 impl Default for RegulatorConfig {
@@ -147,6 +197,9 @@ impl Default for RegulatorConfig {
             warning_min_amount: 50_000_000_000,
             newbie_hours: 10.0, veteran_hours: 100.0,
             velocity_threshold: 20.0,
+            max_tax_ratio: 0.8,
+            velocity_penalty_coef: DEFAULT_VELOCITY_PENALTY_COEF,
+            warning_velocity_ratio: DEFAULT_WARNING_VELOCITY_RATIO,
         }
     }
 }
@@ -162,7 +215,317 @@ pub struct TransferResult {
     pub warning_code: c_int,         // 12
 }
 
-// ==================== 6. 静态布局一致性测试 ====================
+/// 税费构成明细 (48 bytes)：单位为标准货币单位 (非 Micros)，用于向玩家展示税单
+///
+/// 各分量为累加口径：`base + inflation + velocity + luxury + wealth_gap` 经封顶后即 `final_tax`。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TaxBreakdown {
+    pub base_tax: c_double,            // 0: 基础税 (amount × base_tax_rate)
+    pub inflation_component: c_double, // 8: 通胀调节附加
+    pub velocity_penalty: c_double,    // 16: 频率惩罚附加
+    pub luxury_tax: c_double,          // 24: 奢侈税
+    pub wealth_gap_tax: c_double,      // 32: 贫富调节税补足部分
+    pub final_tax: c_double,           // 40: 封顶后的最终税费
+}
+
+/// 宏观指标快照 (24 bytes)：一次 FFI 调用同时返回通胀、稳定性与热度衰减
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MacroSnapshot {
+    pub inflation: c_double,    // 0: 通胀率
+    pub stability: c_double,    // 8: 稳定性因子 [0, 1]
+    pub decay_amount: c_double, // 16: 本周期热度衰减量
+}
+
+/// 单日成交汇总 (32 bytes)，按 UTC 自然日聚合
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DailyStat {
+    pub day: c_longlong,          // 0: 自 1970-01-01 起的天数 (UTC)
+    pub net_micros: c_longlong,   // 8: [Precision] 成交量代数和 Micros
+    pub gross_micros: c_longlong, // 16: [Precision] 成交量绝对值之和 Micros
+    pub count: c_longlong,        // 24: 成交笔数
+}
+
+/// 玩家间单笔应付款 (24 bytes)，用于批量结算轧差
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PairTransfer {
+    pub from_id: c_longlong,       // 0: 付款方
+    pub to_id: c_longlong,         // 8: 收款方
+    pub amount_micros: c_longlong, // 16: [Precision] 金额 Micros
+}
+
+/// 整分定点金额 (8 bytes)：1 = 0.01 标准单位，用于要求精确货币运算的定点计税路径
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PriceI64 {
+    pub cents: c_longlong, // 0
+}
+
+/// 单笔定价的逐步中间量 (56 bytes)，用于排查异常报价
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PriceTrace {
+    pub epsilon: c_double,          // 0: 环境因子
+    pub n_eff: c_double,            // 8: 有效交易量
+    pub adj_lambda: c_double,       // 16: 方向调整后的灵敏度 (卖出 0.6x)
+    pub total_n: c_double,          // 24: 含本次交易的总供应冲击
+    pub raw_exponent: c_double,     // 32: 硬限幅后的指数
+    pub clamped_exponent: c_double, // 40: tanh 软限幅后的指数
+    pub final_price: c_double,      // 48: 经硬底线保护的最终价格
+}
+
+/// 运行健康报告 (40 bytes)：监控周期一次 FFI 调用取回全部计数器
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealthReport {
+    pub total_logs: c_ulonglong,   // 0: 已写入热存储的成交记录数
+    pub dropped_logs: c_ulonglong, // 8: 在 FFI 边界被拒绝的记录数
+    pub panic_count: c_ulonglong,  // 16: 捕获的 panic 次数
+    pub history_len: c_ulonglong,  // 24: 全局热存储当前记录数
+    pub hydration_complete: c_int, // 32: 1 = 历史回灌已完成
+    pub _padding: c_int,           // 36
+}
+
+// ==================== 6. 配置合法性校验 (Validation) ====================
+
+/// 校验 `min <= value <= max` 且为有限值，不满足时追加一条违规描述
+fn check_range(out: &mut Vec<String>, field: &str, value: f64, min: f64, max: f64) {
+    if !value.is_finite() || value < min || value > max {
+        out.push(format!("{} = {} is out of range [{}, {}]", field, value, min, max));
+    }
+}
+
+impl MarketConfig {
+    /// 收集全部越界字段 (不在首个错误处短路)
+    pub fn collect_violations(&self, out: &mut Vec<String>) {
+        check_range(out, "market.base_lambda", self.base_lambda, 0.0, 10.0);
+        check_range(out, "market.volatility_factor", self.volatility_factor, 0.0, 100.0);
+        check_range(out, "market.seasonal_amplitude", self.seasonal_amplitude, 0.0, 1.0);
+        check_range(out, "market.weekend_multiplier", self.weekend_multiplier, 0.01, 10.0);
+        check_range(out, "market.newbie_protection_rate", self.newbie_protection_rate, 0.0, 1.0);
+        check_range(out, "market.seasonal_weight", self.seasonal_weight, 0.0, f64::MAX);
+        check_range(out, "market.weekend_weight", self.weekend_weight, 0.0, f64::MAX);
+        check_range(out, "market.newbie_weight", self.newbie_weight, 0.0, f64::MAX);
+        check_range(out, "market.inflation_weight", self.inflation_weight, 0.0, f64::MAX);
+        // 热度 / 饱和度权重允许为负 (负权重表示压低价格)
+        check_range(out, "market.heat_weight", self.heat_weight, f64::MIN, f64::MAX);
+        check_range(out, "market.saturation_weight", self.saturation_weight, f64::MIN, f64::MAX);
+        check_range(out, "market.newbie_hours", self.newbie_hours, 0.0, f64::MAX);
+
+        // 0 表示沿用默认值 (兼容零初始化)，否则不得低于 1
+        if self.festival_multiplier != 0.0 {
+            check_range(out, "market.festival_multiplier", self.festival_multiplier, 1.0, f64::MAX);
+        }
+
+        // 上下限同时为 0 表示沿用默认钳位区间
+        if self.epsilon_min != 0.0 || self.epsilon_max != 0.0 {
+            check_range(out, "market.epsilon_min", self.epsilon_min, f64::MIN_POSITIVE, f64::MAX);
+            check_range(out, "market.epsilon_max", self.epsilon_max, f64::MIN_POSITIVE, f64::MAX);
+            if self.epsilon_min >= self.epsilon_max {
+                out.push(format!(
+                    "market.epsilon_min ({}) must be < market.epsilon_max ({})",
+                    self.epsilon_min, self.epsilon_max
+                ));
+            }
+        }
+
+        if self.weekend_mask & !WEEKEND_MASK_BITS != 0 {
+            out.push(format!("market.weekend_mask = {:#b} has bits beyond the 7 weekdays", self.weekend_mask));
+        }
+    }
+
+    /// 实际生效的新手优待窗口 (零初始化或非法值回退为默认 100 小时)
+    pub fn effective_newbie_hours(&self) -> f64 {
+        if self.newbie_hours.is_finite() && self.newbie_hours > 0.0 {
+            self.newbie_hours
+        } else {
+            DEFAULT_NEWBIE_PROTECTION_HOURS
+        }
+    }
+
+    /// 实际生效的节庆乘数 (零初始化或非法值回退为默认 1.15)
+    pub fn effective_festival_multiplier(&self) -> f64 {
+        if self.festival_multiplier.is_finite() && self.festival_multiplier >= 1.0 {
+            self.festival_multiplier
+        } else {
+            DEFAULT_FESTIVAL_MULTIPLIER
+        }
+    }
+
+    /// 实际生效的 epsilon 钳位区间 (零初始化或非法区间回退为默认 [0.1, 10])
+    pub fn effective_epsilon_bounds(&self) -> (f64, f64) {
+        let (min, max) = (self.epsilon_min, self.epsilon_max);
+        if min.is_finite() && max.is_finite() && min > 0.0 && min < max {
+            (min, max)
+        } else {
+            (DEFAULT_EPSILON_MIN, DEFAULT_EPSILON_MAX)
+        }
+    }
+
+    /// 实际生效的周末掩码 (零初始化的旧配置回退为默认周六、周日)
+    pub fn effective_weekend_mask(&self) -> c_int {
+        match self.weekend_mask & WEEKEND_MASK_BITS {
+            0 => DEFAULT_WEEKEND_MASK,
+            mask => mask,
+        }
+    }
+}
+
+impl RegulatorConfig {
+    /// 实际生效的速率预警比例 (零初始化或非法值回退为默认 0.7)
+    pub fn effective_warning_velocity_ratio(&self) -> f64 {
+        if self.warning_velocity_ratio > 0.0 && self.warning_velocity_ratio < 1.0 {
+            self.warning_velocity_ratio
+        } else {
+            DEFAULT_WARNING_VELOCITY_RATIO
+        }
+    }
+
+    /// 实际生效的频率惩罚系数 (零初始化回退为默认 0.05，负数关闭惩罚返回 0)
+    pub fn effective_velocity_penalty_coef(&self) -> f64 {
+        if self.velocity_penalty_coef == 0.0 {
+            DEFAULT_VELOCITY_PENALTY_COEF
+        } else {
+            self.velocity_penalty_coef.max(0.0)
+        }
+    }
+
+    /// 收集全部越界字段，包括阈值之间的顺序约束
+    pub fn collect_violations(&self, out: &mut Vec<String>) {
+        check_range(out, "regulator.base_tax_rate", self.base_tax_rate, 0.0, 1.0);
+        check_range(out, "regulator.luxury_tax_rate", self.luxury_tax_rate, 0.0, 1.0);
+        check_range(out, "regulator.wealth_gap_tax_rate", self.wealth_gap_tax_rate, 0.0, 1.0);
+        check_range(out, "regulator.warning_ratio", self.warning_ratio, 0.0, 1.0);
+        check_range(out, "regulator.newbie_hours", self.newbie_hours, 0.0, f64::MAX);
+        check_range(out, "regulator.veteran_hours", self.veteran_hours, 0.0, f64::MAX);
+        check_range(out, "regulator.velocity_threshold", self.velocity_threshold, f64::MIN_POSITIVE, f64::MAX);
+        // 0 表示使用默认上限 0.8 (兼容零初始化)
+        if self.max_tax_ratio != 0.0 {
+            check_range(out, "regulator.max_tax_ratio", self.max_tax_ratio, f64::MIN_POSITIVE, 1.0);
+        }
+        // 0 表示沿用默认值，负数表示关闭，只要求有限
+        check_range(out, "regulator.velocity_penalty_coef", self.velocity_penalty_coef, f64::MIN, f64::MAX);
+        // 0 表示沿用默认值 (兼容零初始化)，否则须严格位于 (0, 1)
+        if self.warning_velocity_ratio != 0.0
+            && !(self.warning_velocity_ratio > 0.0 && self.warning_velocity_ratio < 1.0)
+        {
+            out.push(format!(
+                "regulator.warning_velocity_ratio = {} out of range (0, 1)",
+                self.warning_velocity_ratio
+            ));
+        }
+
+        if self.luxury_threshold < 0 {
+            out.push(format!("regulator.luxury_threshold = {} must be non-negative", self.luxury_threshold));
+        }
+        if self.warning_min_amount < 0 {
+            out.push(format!("regulator.warning_min_amount = {} must be non-negative", self.warning_min_amount));
+        }
+        if self.poor_threshold < 0 {
+            out.push(format!("regulator.poor_threshold = {} must be non-negative", self.poor_threshold));
+        }
+        if self.poor_threshold >= self.rich_threshold {
+            out.push(format!(
+                "regulator.poor_threshold ({}) must be < regulator.rich_threshold ({})",
+                self.poor_threshold, self.rich_threshold
+            ));
+        }
+        if self.newbie_hours >= self.veteran_hours {
+            out.push(format!(
+                "regulator.newbie_hours ({}) must be < regulator.veteran_hours ({})",
+                self.newbie_hours, self.veteran_hours
+            ));
+        }
+    }
+}
+
+impl PidState {
+    /// 收集全部越界的增益参数 (integration_limit = 0 表示使用默认限幅)
+    pub fn collect_violations(&self, out: &mut Vec<String>) {
+        check_range(out, "pid.kp", self.kp, 0.0, f64::MAX);
+        check_range(out, "pid.ki", self.ki, 0.0, f64::MAX);
+        check_range(out, "pid.kd", self.kd, 0.0, f64::MAX);
+        check_range(out, "pid.lambda", self.lambda, 0.0, 1.0);
+        check_range(out, "pid.integration_limit", self.integration_limit, 0.0, f64::MAX);
+        check_range(out, "pid.setpoint_tolerance", self.setpoint_tolerance, 0.0, f64::MAX);
+        check_range(out, "pid.schedule_pivot", self.schedule_pivot, -1.0, 1.0);
+        check_range(out, "pid.schedule_steepness", self.schedule_steepness, 0.0, f64::MAX);
+        check_range(out, "pid.max_delta_per_step", self.max_delta_per_step, 0.0, f64::MAX);
+        check_range(out, "pid.deadband", self.deadband, 0.0, f64::MAX);
+        // 0 表示沿用默认值 (兼容零初始化)
+        if self.derivative_alpha != 0.0 {
+            check_range(out, "pid.derivative_alpha", self.derivative_alpha, f64::MIN_POSITIVE, 1.0);
+        }
+    }
+
+    /// 实际生效的微分滤波系数 (零初始化或非法值回退为默认 0.3)
+    pub fn effective_derivative_alpha(&self) -> f64 {
+        if self.derivative_alpha > 0.0 && self.derivative_alpha <= 1.0 {
+            self.derivative_alpha
+        } else {
+            DEFAULT_DERIVATIVE_FILTER_ALPHA
+        }
+    }
+}
+
+/// 一次性校验整套配置，返回所有违规项的可读描述
+pub fn collect_config_violations(
+    market: &MarketConfig,
+    regulator: &RegulatorConfig,
+    pid: &PidState,
+) -> Vec<String> {
+    let mut out = Vec::new();
+    market.collect_violations(&mut out);
+    regulator.collect_violations(&mut out);
+    pid.collect_violations(&mut out);
+    out
+}
+
+// ==================== 7. 输入净化 (Sanitization) ====================
+
+/// 将 Java 侧传入的 NaN/Inf 替换为安全的兜底值
+#[inline(always)]
+pub fn sanitize_f64(x: f64, fallback: f64) -> f64 {
+    if x.is_finite() { x } else { fallback }
+}
+
+impl TradeContext {
+    /// 返回浮点字段已净化的副本
+    pub fn sanitized(&self) -> Self {
+        Self {
+            inflation_rate: sanitize_f64(self.inflation_rate, 0.0),
+            market_heat: sanitize_f64(self.market_heat, 0.0),
+            eco_saturation: sanitize_f64(self.eco_saturation, 0.0),
+            ..*self
+        }
+    }
+}
+
+impl MarketConfig {
+    /// 返回浮点字段已净化的副本，非法字段回退为默认配置
+    pub fn sanitized(&self) -> Self {
+        let d = Self::default();
+        Self {
+            base_lambda: sanitize_f64(self.base_lambda, d.base_lambda),
+            volatility_factor: sanitize_f64(self.volatility_factor, d.volatility_factor),
+            seasonal_amplitude: sanitize_f64(self.seasonal_amplitude, d.seasonal_amplitude),
+            weekend_multiplier: sanitize_f64(self.weekend_multiplier, d.weekend_multiplier),
+            newbie_protection_rate: sanitize_f64(self.newbie_protection_rate, d.newbie_protection_rate),
+            seasonal_weight: sanitize_f64(self.seasonal_weight, d.seasonal_weight),
+            weekend_weight: sanitize_f64(self.weekend_weight, d.weekend_weight),
+            newbie_weight: sanitize_f64(self.newbie_weight, d.newbie_weight),
+            inflation_weight: sanitize_f64(self.inflation_weight, d.inflation_weight),
+            heat_weight: sanitize_f64(self.heat_weight, d.heat_weight),
+            saturation_weight: sanitize_f64(self.saturation_weight, d.saturation_weight),
+            ..*self
+        }
+    }
+}
+
+// ==================== 8. 静态布局一致性测试 ====================
 
 #[cfg(test)]
 mod tests {
@@ -172,16 +535,96 @@ mod tests {
     #[test]
     fn verify_precision_alignment() {
         // 验证结构体总大小 (必须与 Java 侧配置绝对一致)
-        assert_eq!(mem::size_of::<PidState>(), 72);
+        assert_eq!(mem::size_of::<PidState>(), 136);
         assert_eq!(mem::size_of::<TradeContext>(), 64);
         assert_eq!(mem::size_of::<TransferContext>(), 96);
-        assert_eq!(mem::size_of::<MarketConfig>(), 72); 
-        assert_eq!(mem::size_of::<RegulatorConfig>(), 96);
+        assert_eq!(mem::size_of::<MarketConfig>(), 128);
+        assert_eq!(mem::size_of::<RegulatorConfig>(), 120);
         assert_eq!(mem::size_of::<TransferResult>(), 16);
+        assert_eq!(mem::size_of::<PairTransfer>(), 24);
+        assert_eq!(mem::size_of::<MacroSnapshot>(), 24);
+        assert_eq!(mem::size_of::<TaxBreakdown>(), 48);
+        assert_eq!(mem::size_of::<DailyStat>(), 32);
+        assert_eq!(mem::size_of::<HealthReport>(), 40);
+        assert_eq!(mem::size_of::<PriceI64>(), 8);
+        assert_eq!(mem::size_of::<PriceTrace>(), 56);
         
         // 验证关键金额字段的偏移
         assert_eq!(mem::offset_of!(TransferContext, sender_balance), 8);
+        assert_eq!(mem::offset_of!(MarketConfig, weekend_mask), 72);
+        assert_eq!(mem::offset_of!(MarketConfig, saturation_weight), 88);
+        assert_eq!(mem::offset_of!(MarketConfig, newbie_hours), 96);
+        assert_eq!(mem::offset_of!(MarketConfig, festival_multiplier), 104);
+        assert_eq!(mem::offset_of!(MarketConfig, epsilon_min), 112);
+        assert_eq!(mem::offset_of!(MarketConfig, epsilon_max), 120);
         assert_eq!(mem::offset_of!(RegulatorConfig, rich_threshold), 40);
+        assert_eq!(mem::offset_of!(RegulatorConfig, max_tax_ratio), 96);
+        assert_eq!(mem::offset_of!(RegulatorConfig, velocity_penalty_coef), 104);
+        assert_eq!(mem::offset_of!(RegulatorConfig, warning_velocity_ratio), 112);
         assert_eq!(mem::offset_of!(TransferResult, final_tax_micros), 0);
+        assert_eq!(mem::offset_of!(TaxBreakdown, final_tax), 40);
+        assert_eq!(mem::offset_of!(HealthReport, hydration_complete), 32);
+        assert_eq!(mem::offset_of!(PidState, prev_target), 72);
+        assert_eq!(mem::offset_of!(PidState, schedule_pivot), 88);
+        assert_eq!(mem::offset_of!(PidState, prev_output), 104);
+        assert_eq!(mem::offset_of!(PidState, derivative_alpha), 128);
+    }
+
+    #[test]
+    fn test_default_configs_have_no_violations() {
+        let violations = collect_config_violations(
+            &MarketConfig::default(),
+            &RegulatorConfig::default(),
+            &PidState::default(),
+        );
+        assert!(violations.is_empty(), "defaults should validate cleanly: {:?}", violations);
+    }
+
+    #[test]
+    fn test_zeroed_default_fields_validate() {
+        // 零初始化的 "0 = 默认值" 字段不应被报告为违规
+        let regulator = RegulatorConfig {
+            max_tax_ratio: 0.0,
+            velocity_penalty_coef: 0.0,
+            warning_velocity_ratio: 0.0,
+            ..RegulatorConfig::default()
+        };
+        let violations = collect_config_violations(&MarketConfig::default(), &regulator, &PidState::default());
+        assert!(violations.is_empty(), "zero means default: {:?}", violations);
+
+        let regulator = RegulatorConfig { max_tax_ratio: 1.5, ..RegulatorConfig::default() };
+        let violations = collect_config_violations(&MarketConfig::default(), &regulator, &PidState::default());
+        assert!(violations.iter().any(|v| v.starts_with("regulator.max_tax_ratio")));
+    }
+
+    #[test]
+    fn test_all_violations_reported() {
+        let market = MarketConfig {
+            seasonal_weight: -0.5,
+            newbie_protection_rate: 1.5,
+            ..MarketConfig::default()
+        };
+        let regulator = RegulatorConfig {
+            base_tax_rate: f64::NAN,
+            poor_threshold: 2_000_000_000_000,
+            rich_threshold: 1_000_000_000_000,
+            newbie_hours: 200.0,
+            veteran_hours: 100.0,
+            ..RegulatorConfig::default()
+        };
+        let pid = PidState { kd: -1.0, ..PidState::default() };
+
+        let violations = collect_config_violations(&market, &regulator, &pid);
+        assert_eq!(violations.len(), 6, "every invalid field must be reported: {:?}", violations);
+        for field in [
+            "market.seasonal_weight",
+            "market.newbie_protection_rate",
+            "regulator.base_tax_rate",
+            "regulator.poor_threshold",
+            "regulator.newbie_hours",
+            "pid.kd",
+        ] {
+            assert!(violations.iter().any(|v| v.starts_with(field)), "missing violation for {}", field);
+        }
     }
 }
\ No newline at end of file
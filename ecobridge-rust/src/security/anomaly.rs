@@ -0,0 +1,74 @@
+// ==================================================
+// FILE: ecobridge-rust/src/security/anomaly.rs
+// ==================================================
+
+//! Structuring Detection (拆分规避检测)
+//!
+//! 识别“化整为零”行为：大量转账金额刻意落在审计阈值下方的窄带内，
+//! 以规避大额审计。与基于速率的傀儡账户识别互补。
+
+/// 视为“贴近阈值”的窄带宽度 (阈值下方 5%)
+const STRUCTURING_BAND: f64 = 0.05;
+
+/// 聚集笔数达到该值时置信度饱和
+const MIN_CLUSTER_COUNT: f64 = 5.0;
+
+/// 建议的高风险分数线：Java 侧据此将 warning_code 提升为 CODE_WARNING_HIGH_RISK
+pub const STRUCTURING_HIGH_RISK_SCORE: f64 = 0.5;
+
+/// 计算拆分规避评分 [0, 1]
+///
+/// 评分 = (落在 [0.95 × threshold, threshold) 内的比例) × 置信度，
+/// 置信度随聚集笔数线性增长，5 笔及以上饱和为 1。
+/// 非有限值与非正金额被忽略；阈值非法时返回 0。
+pub fn structuring_score(recent_amounts: &[f64], threshold: f64) -> f64 {
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return 0.0;
+    }
+
+    let band_low = threshold * (1.0 - STRUCTURING_BAND);
+    let (total, clustered) = recent_amounts.iter()
+        .filter(|a| a.is_finite() && **a > 0.0)
+        .fold((0usize, 0usize), |(total, clustered), &a| {
+            let hit = (band_low..threshold).contains(&a);
+            (total + 1, clustered + hit as usize)
+        });
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    let fraction = clustered as f64 / total as f64;
+    let confidence = (clustered as f64 / MIN_CLUSTER_COUNT).min(1.0);
+    fraction * confidence
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clustered_just_below_threshold_scores_high() {
+        let threshold = 10_000.0;
+        let amounts: Vec<f64> = (0..10).map(|i| threshold * (0.95 + 0.004 * i as f64)).collect();
+        let score = structuring_score(&amounts, threshold);
+        assert!(score >= 0.99, "structured transfers should score high, got {}", score);
+    }
+
+    #[test]
+    fn test_spread_amounts_score_low() {
+        let threshold = 10_000.0;
+        let amounts = [120.0, 3_400.0, 780.0, 15_000.0, 6_100.0, 45.0, 2_300.0, 8_800.0, 990.0, 12_500.0];
+        let score = structuring_score(&amounts, threshold);
+        assert!(score < STRUCTURING_HIGH_RISK_SCORE, "ordinary activity should score low, got {}", score);
+    }
+
+    #[test]
+    fn test_invalid_inputs_score_zero() {
+        assert_eq!(structuring_score(&[], 100.0), 0.0);
+        assert_eq!(structuring_score(&[99.0], 0.0), 0.0);
+        assert_eq!(structuring_score(&[f64::NAN, -5.0], 100.0), 0.0);
+    }
+}
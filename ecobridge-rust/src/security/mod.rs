@@ -14,6 +14,9 @@
 /// 风控核心逻辑实现 (包含动态数量限额演算、账户拆分防御与傀儡账户识别)
 pub mod regulator;
 
+/// 拆分规避 (Structuring) 异常检测
+pub mod anomaly;
+
 // ==================== 2. 跨模块重导出 ====================
 
 /// 重新导出配置结构体 (SSoT)
@@ -38,4 +41,6 @@ pub use regulator::{
     
     // 拦截动态数量限额 (物品售出数量超过基于时长的演算上限)
     CODE_BLOCK_QUANTITY_LIMIT,     // 6: 触发平方根模型数量拦截
-};
\ No newline at end of file
+};
+
+pub use anomaly::{structuring_score, STRUCTURING_HIGH_RISK_SCORE};
@@ -2,7 +2,11 @@
 // FILE: ecobridge-rust/src/security/regulator.rs
 // ==================================================
 
-use crate::models::{TransferContext, TransferResult, RegulatorConfig};
+use crate::models::{PriceI64, TaxBreakdown, TransferContext, TransferResult, RegulatorConfig};
+use std::sync::RwLock;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 // 状态码常量
 pub const CODE_NORMAL: i32 = 0;
@@ -12,10 +16,52 @@ pub const CODE_BLOCK_INJECTION: i32 = 3;
 pub const CODE_BLOCK_INSUFFICIENT_FUNDS: i32 = 4;
 pub const CODE_BLOCK_VELOCITY_LIMIT: i32 = 5; 
 pub const CODE_BLOCK_QUANTITY_LIMIT: i32 = 6;
+// 调用错误 (非审计结论)：以拒绝结果返回，确保失败时不放行
+pub const CODE_ERROR_NO_GLOBAL_CONFIG: i32 = -1;
+pub const CODE_ERROR_INVALID_CALL: i32 = -2;
+pub const CODE_ERROR_NON_FINITE_TAX: i32 = -3;
 
 /// 精度缩放常量 (1.0 = 1,000,000 Micros)
 const MICROS_SCALE: f64 = 1_000_000.0;
 
+/// 批量审计启用并行的最小笔数
+#[cfg(feature = "parallel")]
+const BATCH_PARALLEL_THRESHOLD: usize = 256;
+
+/// 频率惩罚指数上限：e^50 已远超任何封顶比例，避免极端速率下 exp 溢出为 inf
+const MAX_PENALTY_EXPONENT: f64 = 50.0;
+
+/// 默认税收封顶比例；配置值不在 (0, 1] 内时回退至此 (兼容零初始化的旧配置)
+pub const DEFAULT_MAX_TAX_RATIO: f64 = 0.8;
+
+/// 单一全局策略的服务器可在启动时设置一次，避免每次审计都跨 FFI 传递配置
+static GLOBAL_REGULATOR_CONFIG: RwLock<Option<RegulatorConfig>> = RwLock::new(None);
+
+/// 设置 (或以 None 清除) 全局审计配置
+pub fn set_global_config(cfg: Option<RegulatorConfig>) {
+    if let Ok(mut slot) = GLOBAL_REGULATOR_CONFIG.write() {
+        *slot = cfg;
+    }
+}
+
+/// 当前全局审计配置的副本
+pub fn global_config() -> Option<RegulatorConfig> {
+    GLOBAL_REGULATOR_CONFIG.read().ok().and_then(|slot| *slot)
+}
+
+/// 调用错误对应的拒绝结果
+pub fn error_result(code: i32) -> TransferResult {
+    TransferResult { final_tax_micros: 0, is_blocked: 1, warning_code: code }
+}
+
+/// 使用全局配置审计；尚未设置时返回 `CODE_ERROR_NO_GLOBAL_CONFIG` 拒绝结果
+pub fn compute_transfer_check_global(ctx: &TransferContext) -> TransferResult {
+    match global_config() {
+        Some(cfg) => compute_transfer_check_internal(ctx, &cfg),
+        None => error_result(CODE_ERROR_NO_GLOBAL_CONFIG),
+    }
+}
+
 // [v2.0] to_micros_saturating is shared from crate root (lib.rs)
 
 /// 增强型交易审计逻辑 (v1.6.0 - Precision Hardened)
@@ -25,11 +71,30 @@ pub fn compute_transfer_check_internal(
     ctx: &TransferContext,
     cfg: &RegulatorConfig,
 ) -> TransferResult {
-    // 1. 基础数据转换 (Micros i64 -> f64 用于数学运算)
-    let amount_f64 = (ctx.amount_micros as f64) / MICROS_SCALE;
-    let sender_bal_f64 = (ctx.sender_balance as f64) / MICROS_SCALE;
-    let receiver_bal_f64 = (ctx.receiver_balance as f64) / MICROS_SCALE;
-    
+    let warning_code = match audit_limits(ctx, cfg) {
+        Ok(code) => code,
+        Err(blocked) => return blocked,
+    };
+
+    // ============================================================
+    // 4. 自适应税收计算 (Adaptive Behavioral Tax)
+    // ============================================================
+    let breakdown = compute_tax_breakdown_internal(ctx, cfg);
+    // NaN 配置等导致税额非有限时拒绝放行，而不是把 0 税额传回 Java
+    if !breakdown.final_tax.is_finite() {
+        return error_result(CODE_ERROR_NON_FINITE_TAX);
+    }
+
+    TransferResult {
+        // 结果转换回 i64 Micros 传回 Java
+        final_tax_micros: crate::to_micros_saturating(breakdown.final_tax),
+        is_blocked: 0,
+        warning_code,
+    }
+}
+
+/// 数量限额与行为速率审计 (步骤 1-3)：通过时返回预警码，拦截时返回拒绝结果
+fn audit_limits(ctx: &TransferContext, cfg: &RegulatorConfig) -> Result<i32, TransferResult> {
     // ============================================================
     // 1. 动态数量限额演算 (平方根递减模型)
     // ============================================================
@@ -46,11 +111,11 @@ pub fn compute_transfer_check_internal(
     // 拦截判定：比较原始 i64 Micros 以确保绝对精确
     let final_limit_micros = crate::to_micros_saturating(final_limit);
     if ctx.amount_micros > final_limit_micros && final_limit_micros > 0 {
-        return TransferResult {
+        return Err(TransferResult {
             final_tax_micros: 0,
             is_blocked: 1,
             warning_code: CODE_BLOCK_QUANTITY_LIMIT,
-        };
+        });
     }
 
     // ============================================================
@@ -63,11 +128,11 @@ pub fn compute_transfer_check_internal(
     };
 
     if puppet_factor > cfg.velocity_threshold {
-        return TransferResult {
+        return Err(TransferResult {
             final_tax_micros: 0,
             is_blocked: 1,
             warning_code: CODE_BLOCK_VELOCITY_LIMIT,
-        };
+        });
     }
 
     // ============================================================
@@ -75,46 +140,192 @@ pub fn compute_transfer_check_internal(
     // ============================================================
     let mut warning_code = CODE_NORMAL;
     
-    if ctx.amount_micros > (final_limit_micros * 85 / 100) || puppet_factor > (cfg.velocity_threshold * 0.7) {
+    if ctx.amount_micros > (final_limit_micros * 85 / 100) || puppet_factor > (cfg.velocity_threshold * cfg.effective_warning_velocity_ratio()) {
         warning_code = CODE_WARNING_HIGH_RISK;
     }
+    Ok(warning_code)
+}
+
+/// 自适应税收的分项演算
+///
+/// 按原有顺序逐层叠加 (基础税 → 通胀调节 → 频率惩罚 → 奢侈税 → 贫富调节)，
+/// 每一项记录该层带来的增量，`final_tax` 即审计结果所用的封顶税额。
+/// 仅计算税费，不做限额与速率拦截判定。
+pub fn compute_tax_breakdown_internal(
+    ctx: &TransferContext,
+    cfg: &RegulatorConfig,
+) -> TaxBreakdown {
+    let amount_f64 = (ctx.amount_micros as f64) / MICROS_SCALE;
+    let sender_bal_f64 = (ctx.sender_balance as f64) / MICROS_SCALE;
+    let receiver_bal_f64 = (ctx.receiver_balance as f64) / MICROS_SCALE;
 
-    // ============================================================
-    // 4. 自适应税收计算 (Adaptive Behavioral Tax)
-    // ============================================================
-    let inflation_adj = 1.0 + ctx.inflation_rate.max(0.0);
-    
     // 基础税 + 通胀调节 (基于 f64 运算)
-    let mut tax_f64 = amount_f64 * cfg.base_tax_rate * inflation_adj;
+    let base_tax = amount_f64 * cfg.base_tax_rate;
+    let inflation_adj = 1.0 + ctx.inflation_rate.max(0.0);
+    let after_inflation = base_tax * inflation_adj;
 
-    // 惩罚性频率税：指数增长惩罚
-    let behavioral_penalty = (ctx.sender_velocity * 0.05).exp(); 
-    tax_f64 *= behavioral_penalty;
+    // 惩罚性频率税：指数增长惩罚 (系数为 0 时关闭)，指数限幅防止溢出
+    let behavioral_penalty = velocity_penalty_factor(ctx, cfg);
+    let after_velocity = after_inflation * behavioral_penalty;
 
     // 奢侈税叠加 (i64 Micros -> f64 转换计算)
     let luxury_threshold_f64 = (cfg.luxury_threshold as f64) / MICROS_SCALE;
-    if amount_f64 > luxury_threshold_f64 {
+    let after_luxury = if amount_f64 > luxury_threshold_f64 {
         let excess = amount_f64 - luxury_threshold_f64;
-        tax_f64 = excess.mul_add(cfg.luxury_tax_rate, tax_f64);
-    }
+        excess.mul_add(cfg.luxury_tax_rate, after_velocity)
+    } else {
+        after_velocity
+    };
 
-    // 贫富调节税
+    // 贫富调节税：取两者较大值，记录补足的差额
     let poor_threshold_f64 = (cfg.poor_threshold as f64) / MICROS_SCALE;
     let rich_threshold_f64 = (cfg.rich_threshold as f64) / MICROS_SCALE;
-    if sender_bal_f64 < poor_threshold_f64 && receiver_bal_f64 > rich_threshold_f64 {
-        let gap_tax = amount_f64 * cfg.wealth_gap_tax_rate;
-        tax_f64 = tax_f64.max(gap_tax);
+    let after_gap = if sender_bal_f64 < poor_threshold_f64 && receiver_bal_f64 > rich_threshold_f64 {
+        after_luxury.max(amount_f64 * cfg.wealth_gap_tax_rate)
+    } else {
+        after_luxury
+    };
+
+    // 税收封顶修正 (默认 80%，可由 max_tax_ratio 配置)
+    let max_tax_ratio = if cfg.max_tax_ratio > 0.0 && cfg.max_tax_ratio <= 1.0 {
+        cfg.max_tax_ratio
+    } else {
+        DEFAULT_MAX_TAX_RATIO
+    };
+
+    TaxBreakdown {
+        base_tax,
+        inflation_component: after_inflation - base_tax,
+        velocity_penalty: after_velocity - after_inflation,
+        luxury_tax: after_luxury - after_velocity,
+        wealth_gap_tax: after_gap - after_luxury,
+        // f64::min 会吞掉 NaN，这里显式保留，交由调用方拒绝
+        final_tax: if after_gap.is_nan() { f64::NAN } else { after_gap.min(amount_f64 * max_tax_ratio) },
     }
+}
 
-    // 税收封顶修正 (80%)
-    let tax_clamped = tax_f64.min(amount_f64 * 0.8);
+/// 频率惩罚因子 exp(velocity × coef)，指数 NaN 视为 0 并限幅到 ±MAX_PENALTY_EXPONENT
+fn velocity_penalty_factor(ctx: &TransferContext, cfg: &RegulatorConfig) -> f64 {
+    let penalty_exponent = ctx.sender_velocity * cfg.effective_velocity_penalty_coef();
+    let penalty_exponent = if penalty_exponent.is_nan() {
+        0.0
+    } else {
+        penalty_exponent.clamp(-MAX_PENALTY_EXPONENT, MAX_PENALTY_EXPONENT)
+    };
+    penalty_exponent.exp()
+}
 
-    TransferResult {
-        // 结果转换回 i64 Micros 传回 Java
-        final_tax_micros: crate::to_micros_saturating(tax_clamped),
+/// 批量审计：同一份 RegulatorConfig 下逐笔演算 (批量发放奖励等场景)
+///
+/// 结果按下标写入 `out`，处理 `min(ctxs.len(), out.len())` 笔并返回该数量。
+pub fn compute_transfer_check_batch(
+    ctxs: &[TransferContext],
+    cfg: &RegulatorConfig,
+    out: &mut [TransferResult],
+) -> usize {
+    let n = ctxs.len().min(out.len());
+    let (ctxs, out) = (&ctxs[..n], &mut out[..n]);
+
+    #[cfg(feature = "parallel")]
+    if n >= BATCH_PARALLEL_THRESHOLD {
+        out.par_iter_mut()
+            .zip(ctxs.par_iter())
+            .for_each(|(res, ctx)| *res = compute_transfer_check_internal(ctx, cfg));
+        return n;
+    }
+
+    for (res, ctx) in out.iter_mut().zip(ctxs) {
+        *res = compute_transfer_check_internal(ctx, cfg);
+    }
+    n
+}
+
+// ==================== 定点 (整分) 计税 ====================
+//
+// 精度保证：
+// 1. 金额在边界处由 Micros 四舍五入 (远离零) 为整分，误差 ≤ 0.5 分；阈值比较仍使用原始 Micros。
+// 2. 各费率与频率惩罚因子量化为百万分之一 (ppm) 整数，量化误差 ≤ 0.5 ppm。
+// 3. 中间量以 "分 × 1e6" 的 i128 整数累加 (溢出时饱和，随后被封顶截断)，仅在最后一步舍入到分，
+//    因此结果与按量化费率精确计算的有理数相差 ≤ 0.5 分，任意平台结果一致，逐笔累加不产生漂移。
+
+/// 每分对应的 Micros 数 (1 分 = 0.01 标准单位)
+pub const MICROS_PER_CENT: i64 = 10_000;
+const PPM: i128 = 1_000_000;
+
+/// 整数除法，四舍五入 (远离零)；`d` 必须为正
+fn div_round(n: i128, d: i128) -> i128 {
+    if n >= 0 { (n + d / 2) / d } else { -((-n + d / 2) / d) }
+}
+
+/// Micros → 整分 (四舍五入)
+pub fn micros_to_cents(micros: i64) -> i64 {
+    div_round(micros as i128, MICROS_PER_CENT as i128) as i64
+}
+
+/// 整分 → Micros (饱和)
+pub fn cents_to_micros(cents: i64) -> i64 {
+    cents.saturating_mul(MICROS_PER_CENT)
+}
+
+/// 费率量化为 ppm；非有限值返回 None
+fn to_ppm(rate: f64) -> Option<i128> {
+    rate.is_finite().then(|| (rate * PPM as f64).round() as i128)
+}
+
+/// 定点计税：返回整分税额；费率等参数非有限时返回 None
+///
+/// 叠加顺序与 `compute_tax_breakdown_internal` 一致，仅数值表示不同。
+pub fn compute_tax_fixed(ctx: &TransferContext, cfg: &RegulatorConfig) -> Option<PriceI64> {
+    let amount = micros_to_cents(ctx.amount_micros) as i128;
+
+    let base_ppm = to_ppm(cfg.base_tax_rate)?;
+    let inflation_ppm = PPM + to_ppm(ctx.inflation_rate.max(0.0))?;
+    let penalty_ppm = to_ppm(velocity_penalty_factor(ctx, cfg))?;
+
+    // 以 "分 × 1e6" 表示
+    let mut tax = amount.saturating_mul(base_ppm);
+    tax = div_round(tax.saturating_mul(inflation_ppm), PPM);
+    tax = div_round(tax.saturating_mul(penalty_ppm), PPM);
+
+    if ctx.amount_micros > cfg.luxury_threshold {
+        let excess = micros_to_cents(ctx.amount_micros.saturating_sub(cfg.luxury_threshold)) as i128;
+        tax = tax.saturating_add(excess.saturating_mul(to_ppm(cfg.luxury_tax_rate)?));
+    }
+
+    if ctx.sender_balance < cfg.poor_threshold && ctx.receiver_balance > cfg.rich_threshold {
+        tax = tax.max(amount.saturating_mul(to_ppm(cfg.wealth_gap_tax_rate)?));
+    }
+
+    let max_tax_ratio = if cfg.max_tax_ratio > 0.0 && cfg.max_tax_ratio <= 1.0 {
+        cfg.max_tax_ratio
+    } else {
+        DEFAULT_MAX_TAX_RATIO
+    };
+    tax = tax.min(amount.saturating_mul(to_ppm(max_tax_ratio)?));
+
+    let cents = div_round(tax, PPM).clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+    Some(PriceI64 { cents })
+}
+
+/// 定点审计：拦截判定同 `compute_transfer_check_internal`，税额按整分计算。
+/// 返回结果的 `final_tax_micros` 恰为 `out_tax.cents × 10_000`。
+pub fn compute_transfer_check_fixed(
+    ctx: &TransferContext,
+    cfg: &RegulatorConfig,
+) -> (TransferResult, PriceI64) {
+    let warning_code = match audit_limits(ctx, cfg) {
+        Ok(code) => code,
+        Err(blocked) => return (blocked, PriceI64::default()),
+    };
+    let Some(tax) = compute_tax_fixed(ctx, cfg) else {
+        return (error_result(CODE_ERROR_NON_FINITE_TAX), PriceI64::default());
+    };
+    let result = TransferResult {
+        final_tax_micros: cents_to_micros(tax.cents),
         is_blocked: 0,
         warning_code,
-    }
+    };
+    (result, tax)
 }
 
 /// 判断演算结果是否属于高风险或拦截交易
@@ -231,6 +442,142 @@ mod tests {
         assert!(tax_f64 <= amount_f64 * 0.8 + 1e-6, "tax must be capped at 80% of transfer amount");
     }
 
+    #[test]
+    fn test_configurable_tax_cap() {
+        let cfg = RegulatorConfig { base_tax_rate: 2.0, max_tax_ratio: 0.5, ..default_cfg() };
+        // 低频、高活跃度的老玩家：不会被拦截，税额只受封顶约束
+        let ctx = make_ctx(2_000_000_000, 10_000_000_000, 3_600_000, 1.0, 0.8);
+        let result = compute_transfer_check_internal(&ctx, &cfg);
+        assert_eq!(result.is_blocked, 0);
+        assert_eq!(result.final_tax_micros, ctx.amount_micros / 2);
+
+        // 非法比例 (如 Java 侧零初始化) 回退为默认 80%
+        let zeroed = RegulatorConfig { max_tax_ratio: 0.0, ..cfg };
+        let result = compute_transfer_check_internal(&ctx, &zeroed);
+        assert_eq!(result.final_tax_micros, ctx.amount_micros * 8 / 10);
+    }
+
+    #[test]
+    fn test_velocity_penalty_coef_softens_curve() {
+        let ctx = make_ctx(1_000_000_000, 10_000_000_000, 3_600_000, 10.0, 0.8);
+        let breakdown = |coef: f64| {
+            compute_tax_breakdown_internal(&ctx, &RegulatorConfig { velocity_penalty_coef: coef, ..default_cfg() })
+        };
+
+        let harsh = breakdown(0.05);
+        let gentle = breakdown(0.01);
+        assert!(gentle.velocity_penalty < harsh.velocity_penalty);
+        assert!(gentle.final_tax < harsh.final_tax);
+        // e^(10 * 0.01) - 1 的相对增幅
+        let after_inflation = gentle.base_tax + gentle.inflation_component;
+        assert!((gentle.velocity_penalty - after_inflation * (0.1f64.exp() - 1.0)).abs() < 1e-9);
+
+        // 0 沿用默认系数 (兼容零初始化)，负数关闭惩罚
+        assert_eq!(breakdown(0.0).velocity_penalty, harsh.velocity_penalty);
+        assert_eq!(breakdown(-1.0).velocity_penalty, 0.0);
+    }
+
+    #[test]
+    fn test_extreme_velocity_keeps_tax_finite() {
+        let cfg = RegulatorConfig { velocity_threshold: f64::MAX, ..default_cfg() };
+        let ctx = make_ctx(1_000_000_000, 10_000_000_000, 3_600_000, 100_000.0, 0.8);
+
+        let breakdown = compute_tax_breakdown_internal(&ctx, &cfg);
+        assert!(breakdown.velocity_penalty.is_finite());
+        let result = compute_transfer_check_internal(&ctx, &cfg);
+        assert_eq!(result.is_blocked, 0);
+        assert_eq!(result.final_tax_micros, ctx.amount_micros * 8 / 10);
+
+        // 非有限税额以拒绝结果返回
+        let broken = RegulatorConfig { base_tax_rate: f64::NAN, ..cfg };
+        let result = compute_transfer_check_internal(&ctx, &broken);
+        assert_eq!((result.is_blocked, result.warning_code), (1, CODE_ERROR_NON_FINITE_TAX));
+    }
+
+    #[test]
+    fn test_tax_breakdown_sums_to_final_tax() {
+        let cfg = RegulatorConfig { base_tax_rate: 0.05, luxury_threshold: 1_000_000_000, ..default_cfg() };
+        let mut ctx = make_ctx(2_000_000_000, 5_000_000_000, 3_600_000, 1.0, 0.8);
+        ctx.inflation_rate = 0.1;
+
+        for receiver_balance in [2_000_000_000, 2_000_000_000_000] {
+            let ctx = TransferContext { receiver_balance, ..ctx };
+            let b = compute_tax_breakdown_internal(&ctx, &cfg);
+            let result = compute_transfer_check_internal(&ctx, &cfg);
+            assert_eq!(result.final_tax_micros, crate::to_micros_saturating(b.final_tax));
+
+            let cap = (ctx.amount_micros as f64 / MICROS_SCALE) * cfg.max_tax_ratio;
+            let sum = b.base_tax + b.inflation_component + b.velocity_penalty + b.luxury_tax + b.wealth_gap_tax;
+            assert!((sum.min(cap) - b.final_tax).abs() < 1e-9, "{:?}", b);
+            assert!(b.inflation_component > 0.0 && b.velocity_penalty > 0.0 && b.luxury_tax > 0.0);
+        }
+
+        // 穷→富转账才产生贫富调节补足
+        let poor_to_rich = TransferContext { receiver_balance: 2_000_000_000_000, ..ctx };
+        let cfg = RegulatorConfig { wealth_gap_tax_rate: 0.6, ..cfg };
+        assert!(compute_tax_breakdown_internal(&poor_to_rich, &cfg).wealth_gap_tax > 0.0);
+        assert_eq!(compute_tax_breakdown_internal(&ctx, &cfg).wealth_gap_tax, 0.0);
+    }
+
+    #[test]
+    fn test_fixed_tax_matches_float_tax_to_the_cent() {
+        let cfg = default_cfg();
+        for &(amount, sender, velocity) in &[
+            (1_000_000_000i64, 10_000_000_000i64, 1.0),
+            (123_456_789, 5_000_000_000, 3.5),
+            (40_000_000_000, 1_000_000, 0.0),
+        ] {
+            let ctx = TransferContext {
+                item_base_limit: 0, item_max_limit: 0,
+                ..make_ctx(amount, sender, 500_000, velocity, 0.8)
+            };
+            let float_cents = compute_tax_breakdown_internal(&ctx, &cfg).final_tax * 100.0;
+            let (res, tax) = compute_transfer_check_fixed(&ctx, &cfg);
+            assert_eq!(res.is_blocked, 0);
+            assert!((tax.cents as f64 - float_cents).abs() <= 0.5 + 1e-6,
+                "fixed {} vs float {} cents", tax.cents, float_cents);
+            assert_eq!(res.final_tax_micros, tax.cents * MICROS_PER_CENT);
+        }
+
+        let bad = RegulatorConfig { base_tax_rate: f64::NAN, ..cfg };
+        let (res, _) = compute_transfer_check_fixed(&make_ctx(1_000_000, 1, 500_000, 0.0, 0.8), &bad);
+        assert_eq!(res.warning_code, CODE_ERROR_NON_FINITE_TAX);
+    }
+
+    #[test]
+    fn test_fixed_tax_accumulates_without_drift() {
+        // 7% 在二进制中不可精确表示：1.00 单位的税额 f64 为 0.07 (近似)，定点为恰好 7 分
+        let cfg = RegulatorConfig { base_tax_rate: 0.07, velocity_penalty_coef: -1.0, ..default_cfg() };
+        let ctx = TransferContext { amount_micros: 1_000_000, ..Default::default() };
+
+        let (mut fixed_total, mut float_total) = (0i64, 0.0f64);
+        for _ in 0..100_000 {
+            fixed_total += compute_tax_fixed(&ctx, &cfg).unwrap().cents;
+            float_total += compute_tax_breakdown_internal(&ctx, &cfg).final_tax;
+        }
+        assert_eq!(fixed_total, 700_000);
+        assert_ne!(float_total, 7_000.0, "f64 accumulation of 0.07 drifts");
+        assert!((float_total - 7_000.0).abs() < 1e-6);
+
+        // 定点税额在半分处四舍五入 (远离 0)：50 分 × 7% = 3.5 分 → 4 分，49 分 × 7% = 3.43 分 → 3 分
+        let cents_for = |amount_micros: i64| {
+            compute_tax_fixed(&TransferContext { amount_micros, ..Default::default() }, &cfg).unwrap().cents
+        };
+        assert_eq!(cents_for(500_000), 4);
+        assert_eq!(cents_for(490_000), 3);
+
+        assert_eq!(div_round(5, 10), 1);
+        assert_eq!(div_round(4, 10), 0);
+        assert_eq!(div_round(15, 10), 2);
+        assert_eq!(div_round(-5, 10), -1);
+        assert_eq!(div_round(-4, 10), 0);
+        assert_eq!(micros_to_cents(5_000), 1);
+        assert_eq!(micros_to_cents(4_999), 0);
+        assert_eq!(micros_to_cents(14_999), 1);
+        assert_eq!(micros_to_cents(-5_000), -1);
+        assert_eq!(micros_to_cents(-4_999), 0);
+    }
+
     #[test]
     fn test_high_risk_warning_near_limit() {
         let ctx = make_ctx(2_000_000_000, 10_000_000_000, 500_000, 1.0, 0.8);
@@ -240,6 +587,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_warning_velocity_ratio_moves_warning_line() {
+        // 傀儡因子 = velocity / activity = 12 / 1.0：低于拦截阈值 20，介于 50% 与 70% 预警线之间
+        let ctx = make_ctx(1_000_000, 10_000_000_000, 500_000, 12.0, 1.0);
+        let default = compute_transfer_check_internal(&ctx, &default_cfg());
+        assert_eq!(default.warning_code, CODE_NORMAL);
+
+        let early = RegulatorConfig { warning_velocity_ratio: 0.5, ..default_cfg() };
+        let res = compute_transfer_check_internal(&ctx, &early);
+        assert_eq!(res.is_blocked, 0);
+        assert_eq!(res.warning_code, CODE_WARNING_HIGH_RISK);
+
+        // 零初始化回退为 0.7；越界值被校验拒绝
+        let zeroed = RegulatorConfig { warning_velocity_ratio: 0.0, ..default_cfg() };
+        assert_eq!(compute_transfer_check_internal(&ctx, &zeroed).warning_code, CODE_NORMAL);
+        let mut violations = Vec::new();
+        RegulatorConfig { warning_velocity_ratio: 1.0, ..default_cfg() }.collect_violations(&mut violations);
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_batch_matches_individual_checks() {
+        let cfg = default_cfg();
+        let ctxs: Vec<TransferContext> = (0..300)
+            .map(|i| match i % 3 {
+                0 => make_ctx(1_000_000_000, 10_000_000_000, 500_000, 1.0, 0.8),
+                1 => make_ctx(100_000_000_000_000, 10_000_000_000, 500_000, 1.0, 0.8),
+                _ => make_ctx(1_000_000_000, 10_000_000_000, 500_000, 50.0, 0.05),
+            })
+            .collect();
+
+        // 覆盖串行 (< 阈值) 与并行两条路径
+        for len in [10, ctxs.len()] {
+            let mut out = vec![TransferResult::default(); len];
+            assert_eq!(compute_transfer_check_batch(&ctxs[..len], &cfg, &mut out), len);
+            for (ctx, res) in ctxs.iter().zip(&out) {
+                let single = compute_transfer_check_internal(ctx, &cfg);
+                assert_eq!((res.final_tax_micros, res.is_blocked, res.warning_code),
+                           (single.final_tax_micros, single.is_blocked, single.warning_code));
+            }
+        }
+    }
+
+    #[test]
+    fn test_global_config_matches_explicit_config() {
+        let ctx = make_ctx(1_000_000_000, 10_000_000_000, 500_000, 1.0, 0.8);
+        let cfg = RegulatorConfig { base_tax_rate: 0.1, ..default_cfg() };
+
+        set_global_config(None);
+        let unset = compute_transfer_check_global(&ctx);
+        assert_eq!(unset.is_blocked, 1);
+        assert_eq!(unset.warning_code, CODE_ERROR_NO_GLOBAL_CONFIG);
+
+        set_global_config(Some(cfg));
+        let global = compute_transfer_check_global(&ctx);
+        set_global_config(None);
+        let explicit = compute_transfer_check_internal(&ctx, &cfg);
+        assert_eq!(global.final_tax_micros, explicit.final_tax_micros);
+        assert_eq!(global.warning_code, explicit.warning_code);
+    }
+
     #[test]
     fn test_transfer_result_codes() {
         assert_eq!(CODE_NORMAL, 0);
@@ -5,10 +5,11 @@
 // the in-memory hot history layer used by summation.rs for SIMD computation.
 // All persistence is handled by the Java side via EventLogDao (H2).
 
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{RwLock, LazyLock};
-use std::collections::HashMap;
-use crate::models::HistoryRecord;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{LockResult, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard, LazyLock};
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::economy::summation::{calculate_volume_in_memory, invalidate_neff_cache, GLOBAL_MARKET_KEY};
+use crate::models::{DailyStat, HistoryRecord};
 
 // ==================== In-Memory Hot Store (SSoT for SIMD) ====================
 
@@ -19,12 +20,60 @@ static GLOBAL_HISTORY: LazyLock<RwLock<Vec<HistoryRecord>>> =
 static HOT_HISTORY_BY_KEY: LazyLock<RwLock<HashMap<String, Vec<HistoryRecord>>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
 
+/// Records ever removed from the front of GLOBAL_HISTORY (only mutated under its write lock).
+/// Lets cursors keep an absolute position across pruning.
+static GLOBAL_PRUNED: AtomicU64 = AtomicU64::new(0);
+
+/// False while a background rehydrate is loading; pricing should use the H2 path meanwhile.
+static HYDRATION_COMPLETE: AtomicBool = AtomicBool::new(true);
+
 static TOTAL_LOGS: AtomicU64 = AtomicU64::new(0);
 static DROPPED_LOGS: AtomicU64 = AtomicU64::new(0);
 
+/// Wall-clock time (ms) of the latest append to the hot store (0 = nothing yet).
+/// Java appends only after H2 persistence succeeds, so this tracks when the writer last made
+/// progress. The trade's own timestamp is not used: it comes from the caller and may be skewed.
+static LAST_PERSISTED_TS: AtomicI64 = AtomicI64::new(0);
+
+/// Upper bound on market key length accepted from Java (bytes).
+const DEFAULT_MAX_KEY_LEN: usize = 4096;
+static MAX_KEY_LEN: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_KEY_LEN);
+
 const MAX_HISTORY_SIZE: usize = 500_000;
 const PRUNE_TO_SIZE: usize = 400_000;
 
+// ==================== Lock Poisoning ====================
+
+/// Number of poisoned locks recovered instead of propagating the panic.
+static LOCK_RECOVERED: AtomicU64 = AtomicU64::new(0);
+
+/// Take the guard even if a previous holder panicked. The stores only ever
+/// hold complete records, so the data behind a poisoned lock is still usable;
+/// losing every later event to one panic would be worse. The poison flag is
+/// cleared so later callers take the normal path and the counter reflects
+/// distinct incidents rather than every access after one.
+fn recover<G>(result: LockResult<G>, clear_poison: impl FnOnce()) -> G {
+    result.unwrap_or_else(|poisoned| {
+        LOCK_RECOVERED.fetch_add(1, Ordering::Relaxed);
+        clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    recover(lock.read(), || lock.clear_poison())
+}
+
+fn write_lock<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    recover(lock.write(), || lock.clear_poison())
+}
+
+/// Mutex counterpart of `read_lock`/`write_lock`, shared with the per-key
+/// model state maps in `economy` (Kalman, GARCH, MPC, ARIMA, recovery).
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    recover(mutex.lock(), || mutex.clear_poison())
+}
+
 // ==================== Public API ====================
 
 /// Append a single trade record to the in-memory hot store.
@@ -34,108 +83,948 @@ pub fn append_to_memory(ts: i64, amount: f64, market_key: &str) {
     let record = HistoryRecord { timestamp: ts, amount_micros };
 
     // Global store
-    if let Ok(mut hist) = GLOBAL_HISTORY.write() {
+    {
+        let mut hist = write_lock(&GLOBAL_HISTORY);
         hist.push(record);
-        if hist.len() > MAX_HISTORY_SIZE {
-            let remove = hist.len() - PRUNE_TO_SIZE;
-            hist.drain(0..remove);
-        }
+        prune_global(&mut hist);
     }
 
     // Keyed store
-    if let Ok(mut map) = HOT_HISTORY_BY_KEY.write() {
+    {
+        let mut map = write_lock(&HOT_HISTORY_BY_KEY);
         let bucket = map.entry(market_key.to_string())
             .or_insert_with(|| Vec::with_capacity(4096));
         bucket.push(record);
-        if bucket.len() > MAX_HISTORY_SIZE {
-            let remove = bucket.len() - PRUNE_TO_SIZE;
-            bucket.drain(0..remove);
-        }
+        prune_keyed(bucket);
 
         // Keep global aggregate key too
-        let global = map.entry("__global__".to_string())
+        let global = map.entry(GLOBAL_MARKET_KEY.to_string())
             .or_insert_with(|| Vec::with_capacity(4096));
         global.push(record);
-        if global.len() > MAX_HISTORY_SIZE {
-            let remove = global.len() - PRUNE_TO_SIZE;
-            global.drain(0..remove);
-        }
+        prune_keyed(global);
     }
 
     TOTAL_LOGS.fetch_add(1, Ordering::Relaxed);
+    LAST_PERSISTED_TS.fetch_max(now_ms(), Ordering::Relaxed);
 }
 
 /// Bulk-load history from Java (called at startup after H2 query).
+/// Records carry no market key, so they go into the global store and the
+/// `__global__` aggregate key that pricing reads. Both stay sorted by timestamp
+/// even when the batch is older than data already loaded.
 pub fn bulk_load_history(records: &[HistoryRecord]) {
     if records.is_empty() { return; }
-    if let Ok(mut hist) = GLOBAL_HISTORY.write() {
-        for r in records {
-            hist.push(*r);
-        }
-        if hist.len() > MAX_HISTORY_SIZE {
-            let remove = hist.len() - PRUNE_TO_SIZE;
-            hist.drain(0..remove);
-        }
+    let mut sorted = records.to_vec();
+    sorted.sort_by_key(|r| r.timestamp);
+    {
+        let mut hist = write_lock(&GLOBAL_HISTORY);
+        merge_sorted(&mut hist, &sorted);
+        prune_global(&mut hist);
+    }
+    {
+        let mut map = write_lock(&HOT_HISTORY_BY_KEY);
+        let global = map.entry(GLOBAL_MARKET_KEY.to_string())
+            .or_insert_with(|| Vec::with_capacity(4096));
+        merge_sorted(global, &sorted);
+        prune_keyed(global);
     }
     TOTAL_LOGS.fetch_add(records.len() as u64, Ordering::Relaxed);
+    invalidate_neff_cache();
+}
+
+/// Merge the sorted batch `incoming` into `bucket`, keeping timestamp order even
+/// when the batch predates the current tail (e.g. a previous-season seed). The
+/// stable sort detects the existing sorted runs, so this is a linear merge in
+/// the common case.
+fn merge_sorted(bucket: &mut Vec<HistoryRecord>, incoming: &[HistoryRecord]) {
+    bucket.extend_from_slice(incoming);
+    bucket.sort_by_key(|r| r.timestamp);
+}
+
+/// Parse seed records from CSV text: one `timestamp_ms,amount` pair per line,
+/// `amount` in standard units. Blank lines, `#` comments, a header and any
+/// malformed or non-finite line are skipped. Result is sorted by timestamp.
+pub fn parse_seed_csv(text: &str) -> Vec<HistoryRecord> {
+    let mut records: Vec<HistoryRecord> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (ts, amount) = line.split_once(',')?;
+            let timestamp = ts.trim().parse::<i64>().ok()?;
+            let amount = amount.trim().parse::<f64>().ok().filter(|a| a.is_finite())?;
+            Some(HistoryRecord { timestamp, amount_micros: crate::to_micros_saturating(amount) })
+        })
+        .collect();
+    records.sort_by_key(|r| r.timestamp);
+    records
+}
+
+/// Bootstrap the global store from a seed file (a template or a previous
+/// season) so cold-start servers do not price from an empty decay curve.
+/// Seeded records feed the `__global__` N_eff used by pricing and are merged
+/// in timestamp order with any live data. Returns the number of records
+/// loaded. Cached N_eff is invalidated by `bulk_load_history`.
+pub fn seed_history_from_file(path: &str) -> std::io::Result<usize> {
+    let text = std::fs::read_to_string(path)?;
+    let records = parse_seed_csv(&text);
+    bulk_load_history(&records);
+    Ok(records.len())
+}
+
+/// Atomically replace the global history with `records` (e.g. re-queried from H2
+/// after maintenance). Records are sorted by timestamp; the swap happens under the
+/// store write locks so concurrent appends land either before or after it, never
+/// in a half-cleared store. Returns the number of records retained.
+pub fn rehydrate_history(records: &[HistoryRecord]) -> usize {
+    let mut fresh = records.to_vec();
+    fresh.sort_by_key(|r| r.timestamp);
+    replace_global_stores(fresh, false)
+}
+
+/// Swap GLOBAL_HISTORY and the keyed `__global__` aggregate that pricing reads to
+/// the sorted `fresh` records, then invalidate cached N_eff. With `keep_live_tail`
+/// the live records newer than the snapshot are carried over. The global lock is
+/// held across both swaps (appends never hold the keyed lock while taking it).
+/// Per-market buckets are left as they are: the records carry no market key and
+/// those buckets are fed by live appends.
+fn replace_global_stores(mut fresh: Vec<HistoryRecord>, keep_live_tail: bool) -> usize {
+    let mut hist = write_lock(&GLOBAL_HISTORY);
+    if keep_live_tail {
+        merge_live_tail(&mut fresh, &hist);
+    }
+    GLOBAL_PRUNED.fetch_add(hist.len() as u64, Ordering::Relaxed);
+    *hist = fresh;
+    prune_global(&mut hist);
+    let retained = hist.len();
+    write_lock(&HOT_HISTORY_BY_KEY).insert(GLOBAL_MARKET_KEY.to_string(), hist.clone());
+    drop(hist);
+    invalidate_neff_cache();
+    retained
+}
+
+/// Start rehydrating the global stores on a background thread so startup does
+/// not block on sorting a large preload. Unlike `rehydrate_history`, records
+/// appended while the load is running are kept when they are newer than the
+/// snapshot. Returns false if a background rehydrate is already in progress or
+/// the thread could not be started (callers should then load synchronously).
+pub fn rehydrate_history_async(records: Vec<HistoryRecord>) -> bool {
+    if HYDRATION_COMPLETE
+        .compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return false;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("ecobridge-hydrate".into())
+        .spawn(move || {
+            // Flag is restored even if the load panics, so polling never hangs
+            let _done = HydrationGuard;
+            hydrate_keeping_live_tail(records);
+        });
+
+    if spawned.is_err() {
+        HYDRATION_COMPLETE.store(true, Ordering::Release);
+        return false;
+    }
+    true
+}
+
+/// Whether no background rehydrate is pending.
+pub fn is_hydration_complete() -> bool {
+    HYDRATION_COMPLETE.load(Ordering::Acquire)
+}
+
+struct HydrationGuard;
+
+impl Drop for HydrationGuard {
+    fn drop(&mut self) {
+        HYDRATION_COMPLETE.store(true, Ordering::Release);
+    }
+}
+
+fn hydrate_keeping_live_tail(records: Vec<HistoryRecord>) {
+    let mut fresh = records;
+    fresh.sort_by_key(|r| r.timestamp);
+    replace_global_stores(fresh, true);
+}
+
+/// Append the live records that are newer than the sorted snapshot `fresh`
+/// (trades persisted to H2 after the snapshot was queried).
+fn merge_live_tail(fresh: &mut Vec<HistoryRecord>, live: &[HistoryRecord]) {
+    let snapshot_end = fresh.last().map_or(i64::MIN, |r| r.timestamp);
+    let start = live.partition_point(|r| r.timestamp <= snapshot_end);
+    fresh.extend_from_slice(&live[start..]);
+}
+
+/// Drop every in-memory record older than `cutoff_ts` from the global and keyed
+/// stores (retention; the H2 rows are purged by the Java side). Returns the number
+/// of records removed from the global store. Open cursors may skip ahead by the
+/// removed count.
+pub fn purge_before(cutoff_ts: i64) -> u64 {
+    let removed = {
+        let mut hist = write_lock(&GLOBAL_HISTORY);
+        let before = hist.len();
+        hist.retain(|r| r.timestamp >= cutoff_ts);
+        let removed = (before - hist.len()) as u64;
+        GLOBAL_PRUNED.fetch_add(removed, Ordering::Relaxed);
+        removed
+    };
+
+    let mut map = write_lock(&HOT_HISTORY_BY_KEY);
+    for bucket in map.values_mut() {
+        bucket.retain(|r| r.timestamp >= cutoff_ts);
+    }
+    map.retain(|_, bucket| !bucket.is_empty());
+    drop(map);
+    invalidate_neff_cache();
+
+    removed
+}
+
+/// Trim the global store back to PRUNE_TO_SIZE once it exceeds MAX_HISTORY_SIZE.
+/// Caller must hold the write lock.
+fn prune_global(hist: &mut Vec<HistoryRecord>) {
+    if hist.len() > MAX_HISTORY_SIZE {
+        let remove = hist.len() - PRUNE_TO_SIZE;
+        hist.drain(0..remove);
+        GLOBAL_PRUNED.fetch_add(remove as u64, Ordering::Relaxed);
+    }
+}
+
+/// Trim a keyed bucket back to PRUNE_TO_SIZE once it exceeds MAX_HISTORY_SIZE.
+fn prune_keyed(bucket: &mut Vec<HistoryRecord>) {
+    if bucket.len() > MAX_HISTORY_SIZE {
+        let remove = bucket.len() - PRUNE_TO_SIZE;
+        bucket.drain(0..remove);
+    }
 }
 
 /// Get a read lock on the global history.
-pub fn get_history_read() -> std::sync::RwLockReadGuard<'static, Vec<HistoryRecord>> {
-    GLOBAL_HISTORY.read().unwrap()
+pub fn get_history_read() -> RwLockReadGuard<'static, Vec<HistoryRecord>> {
+    read_lock(&GLOBAL_HISTORY)
 }
 
 /// Get a read lock on the keyed history.
-pub fn get_keyed_history_read() -> std::sync::RwLockReadGuard<'static, HashMap<String, Vec<HistoryRecord>>> {
-    HOT_HISTORY_BY_KEY.read().unwrap()
+pub fn get_keyed_history_read() -> RwLockReadGuard<'static, HashMap<String, Vec<HistoryRecord>>> {
+    read_lock(&HOT_HISTORY_BY_KEY)
+}
+
+/// Run `f` over the hot history of `market_key`, or the global history when `None`.
+/// Unknown keys are presented as an empty slice.
+pub fn with_history<R>(market_key: Option<&str>, f: impl FnOnce(&[HistoryRecord]) -> R) -> R {
+    match market_key {
+        Some(key) => {
+            let lock = read_lock(&HOT_HISTORY_BY_KEY);
+            f(lock.get(key).map(Vec::as_slice).unwrap_or(&[]))
+        }
+        None => f(&read_lock(&GLOBAL_HISTORY)),
+    }
 }
 
 /// Query N_eff from in-memory data for a specific market key.
 pub fn query_neff_in_memory(current_ts: i64, tau: f64, market_key: &str) -> f64 {
-    let lock = HOT_HISTORY_BY_KEY.read().unwrap();
+    let lock = read_lock(&HOT_HISTORY_BY_KEY);
     if let Some(history) = lock.get(market_key) {
-        return calculate_volume(history, current_ts, tau);
+        return calculate_volume_in_memory(history, current_ts, tau);
     }
     0.0
 }
 
-/// Query global N_eff from in-memory data.
-pub fn query_neff_global_in_memory(current_ts: i64, tau: f64) -> f64 {
-    let lock = GLOBAL_HISTORY.read().unwrap();
-    calculate_volume(&lock, current_ts, tau)
+/// N_eff of every market key in one pass over the keyed store (the global
+/// aggregate key is excluded), sorted by key. Uses the same SIMD summation as
+/// the pricing path, under a single read lock.
+pub fn query_neff_all_markets(current_ts: i64, tau: f64) -> Vec<(String, f64)> {
+    let lock = read_lock(&HOT_HISTORY_BY_KEY);
+    let mut out: Vec<(String, f64)> = lock
+        .iter()
+        .filter(|(key, _)| key.as_str() != GLOBAL_MARKET_KEY)
+        .map(|(key, history)| (key.clone(), calculate_volume_in_memory(history, current_ts, tau)))
+        .collect();
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out
 }
 
-fn calculate_volume(history: &[HistoryRecord], current_time: i64, tau: f64) -> f64 {
-    if history.is_empty() || tau <= 0.0 { return 0.0; }
+/// N_eff at several decay constants (e.g. 1, 7 and 30 days) from a single scan
+/// of `market_key`'s history, or the global history when `None`. Element `i`
+/// matches the single-tau query for `taus[i]`; non-finite or non-positive taus
+/// yield 0.
+pub fn query_neff_multi_tau(current_ts: i64, taus: &[f64], market_key: Option<&str>) -> Vec<f64> {
+    with_history(market_key, |history| neff_multi_tau(history, current_ts, taus))
+}
 
+fn neff_multi_tau(history: &[HistoryRecord], current_ts: i64, taus: &[f64]) -> Vec<f64> {
     const MS_PER_DAY: f64 = 86_400_000.0;
-    const MAX_FUTURE_TOLERANCE: i64 = 60_000;
     const MICROS_SCALE: f64 = 1_000_000.0;
 
-    let valid_past = current_time - (tau * MS_PER_DAY * 10.0) as i64;
-    let valid_future = current_time + MAX_FUTURE_TOLERANCE;
+    // (lambda, 最早有效时间戳)，非法 tau 为 None
+    let params: Vec<Option<(f64, i64)>> = taus
+        .iter()
+        .map(|&tau| {
+            (tau.is_finite() && tau > 0.0)
+                .then(|| (1.0 / (tau * MS_PER_DAY), current_ts.saturating_sub((tau * MS_PER_DAY * 10.0) as i64)))
+        })
+        .collect();
+    let mut sums = vec![0.0; taus.len()];
+    let Some(widest_past) = params.iter().flatten().map(|&(_, past)| past).min() else {
+        return sums;
+    };
+    let valid_future = current_ts.saturating_add(crate::economy::summation::future_tolerance_ms());
 
-    let start_idx = history.partition_point(|r| r.timestamp < valid_past);
-    let slice = &history[start_idx..];
-    if slice.is_empty() { return 0.0; }
+    let start_idx = history.partition_point(|r| r.timestamp < widest_past);
+    for r in history[start_idx..].iter().filter(|r| r.timestamp <= valid_future) {
+        let age = current_ts.saturating_sub(r.timestamp) as f64;
+        for (sum, param) in sums.iter_mut().zip(&params) {
+            if let Some((lambda, past)) = *param {
+                if r.timestamp >= past {
+                    *sum += (r.amount_micros as f64) * (-age * lambda).exp();
+                }
+            }
+        }
+    }
 
-    let t_min = slice[0].timestamp;
-    let lambda = 1.0 / (tau * MS_PER_DAY);
-    let base = (-(current_time - t_min) as f64 * lambda).exp();
+    sums.iter().map(|s| {
+        let result = s / MICROS_SCALE;
+        if result.is_finite() { result } else { 0.0 }
+    }).collect()
+}
 
-    let sum: f64 = slice.iter()
-        .filter(|r| r.timestamp <= valid_future)
-        .map(|r| {
-            let dt = (r.timestamp - t_min) as f64;
-            (r.amount_micros as f64) * (dt * lambda).exp()
-        })
-        .sum();
+/// Query global N_eff from in-memory data.
+pub fn query_neff_global_in_memory(current_ts: i64, tau: f64) -> f64 {
+    let lock = read_lock(&GLOBAL_HISTORY);
+    calculate_volume_in_memory(&lock, current_ts, tau)
+}
+
+// ==================== History Cursor ====================
+
+/// Forward-only cursor over the global hot history.
+///
+/// Holds only an absolute position, never a copy of the data, and takes the
+/// read lock per step so writers are not blocked between calls. Records pruned
+/// from the front while the cursor is open are skipped.
+pub struct HistoryCursor {
+    since_ts: i64,
+    /// Absolute index: GLOBAL_PRUNED at read time + offset into GLOBAL_HISTORY.
+    next_pos: u64,
+}
+
+impl HistoryCursor {
+    /// Open a cursor over records with `timestamp >= since_ts`.
+    pub fn open_since(since_ts: i64) -> Self {
+        let hist = read_lock(&GLOBAL_HISTORY);
+        let base = GLOBAL_PRUNED.load(Ordering::Relaxed);
+        let start = hist.partition_point(|r| r.timestamp < since_ts);
+        Self { since_ts, next_pos: base + start as u64 }
+    }
+
+    /// Open a cursor over the last `days` days of wall-clock time.
+    pub fn open_days(days: i32) -> Self {
+        Self::open_since(now_ms().saturating_sub(days as i64 * 86_400_000))
+    }
+}
+
+impl Iterator for HistoryCursor {
+    type Item = HistoryRecord;
+
+    fn next(&mut self) -> Option<HistoryRecord> {
+        let hist = read_lock(&GLOBAL_HISTORY);
+        let base = GLOBAL_PRUNED.load(Ordering::Relaxed);
+        let mut idx = self.next_pos.saturating_sub(base) as usize;
+        while let Some(record) = hist.get(idx) {
+            idx += 1;
+            if record.timestamp >= self.since_ts {
+                self.next_pos = base + idx as u64;
+                return Some(*record);
+            }
+        }
+        self.next_pos = base + idx as u64;
+        None
+    }
+}
+
+// ==================== Daily Rollup ====================
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Per-day (UTC) net / gross volume and trade count of the global store over the
+/// last `days` days, oldest day first.
+pub fn query_daily_rollup(days: i32) -> Vec<DailyStat> {
+    query_daily_rollup_at(now_ms(), days)
+}
+
+fn query_daily_rollup_at(current_ts: i64, days: i32) -> Vec<DailyStat> {
+    if days <= 0 {
+        return Vec::new();
+    }
+    let since = current_ts.saturating_sub(days as i64 * MS_PER_DAY);
+
+    let mut by_day: std::collections::BTreeMap<i64, DailyStat> = std::collections::BTreeMap::new();
+    let hist = read_lock(&GLOBAL_HISTORY);
+    for r in hist.iter().filter(|r| r.timestamp >= since && r.timestamp <= current_ts) {
+        let day = r.timestamp.div_euclid(MS_PER_DAY);
+        let stat = by_day.entry(day).or_insert(DailyStat { day, ..DailyStat::default() });
+        stat.net_micros = stat.net_micros.saturating_add(r.amount_micros);
+        stat.gross_micros = stat.gross_micros.saturating_add(r.amount_micros.saturating_abs());
+        stat.count += 1;
+    }
+    by_day.into_values().collect()
+}
+
+// ==================== Hourly Profile ====================
+
+/// Gross volume (standard units) of the global store over the last `days`,
+/// bucketed by local hour of day. `timezone_offset_sec` is applied the same way
+/// as in environment.rs, so hour 0 is local midnight.
+pub fn query_hourly_profile(days: i32, timezone_offset_sec: i32) -> [f64; 24] {
+    query_hourly_profile_at(now_ms(), days, timezone_offset_sec)
+}
+
+fn query_hourly_profile_at(current_ts: i64, days: i32, timezone_offset_sec: i32) -> [f64; 24] {
+    let mut profile = [0.0_f64; 24];
+    if days <= 0 {
+        return profile;
+    }
+    let since = current_ts.saturating_sub(days as i64 * MS_PER_DAY);
+    let offset_ms = timezone_offset_sec as i64 * 1000;
+
+    let hist = read_lock(&GLOBAL_HISTORY);
+    for r in hist.iter().filter(|r| r.timestamp >= since && r.timestamp <= current_ts) {
+        let local_ms = r.timestamp.saturating_add(offset_ms);
+        let hour = local_ms.rem_euclid(MS_PER_DAY) / 3_600_000;
+        profile[hour as usize] += r.amount_micros.unsigned_abs() as f64 / 1_000_000.0;
+    }
+    profile
+}
+
+// ==================== Blocked Transfer Audit ====================
+
+/// A transfer rejected by the regulator, kept for compliance reporting.
+#[derive(Debug, Clone, Copy)]
+struct BlockedTransfer {
+    timestamp: i64,
+    warning_code: i32,
+}
+
+static BLOCKED_TRANSFERS: LazyLock<RwLock<Vec<BlockedTransfer>>> =
+    LazyLock::new(|| RwLock::new(Vec::with_capacity(1024)));
+
+const MAX_BLOCKED_AUDIT_SIZE: usize = 100_000;
+const PRUNE_BLOCKED_TO_SIZE: usize = 80_000;
+
+pub(crate) fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Record a blocked transfer with the regulator's `warning_code` as the reason.
+pub fn log_blocked_transfer(ts: i64, warning_code: i32) {
+    {
+        let mut audit = write_lock(&BLOCKED_TRANSFERS);
+        audit.push(BlockedTransfer { timestamp: ts, warning_code });
+        if audit.len() > MAX_BLOCKED_AUDIT_SIZE {
+            let remove = audit.len() - PRUNE_BLOCKED_TO_SIZE;
+            audit.drain(0..remove);
+        }
+    }
+}
 
-    let result = (sum / MICROS_SCALE) * base;
-    if result.is_finite() { result } else { 0.0 }
+/// Count blocks within the last `days` with the given reason code.
+/// A negative `code` counts every reason.
+pub fn query_blocked_count(days: f64, code: i32) -> u64 {
+    query_blocked_count_at(now_ms(), days, code)
+}
+
+fn query_blocked_count_at(current_ts: i64, days: f64, code: i32) -> u64 {
+    if !days.is_finite() || days <= 0.0 { return 0; }
+    let since = current_ts.saturating_sub((days * 86_400_000.0) as i64);
+    let audit = read_lock(&BLOCKED_TRANSFERS);
+    audit.iter()
+        .filter(|b| b.timestamp >= since && b.timestamp <= current_ts)
+        .filter(|b| code < 0 || b.warning_code == code)
+        .count() as u64
+}
+
+/// Record a blocked transfer at the current wall-clock time.
+pub fn log_blocked_transfer_now(warning_code: i32) {
+    log_blocked_transfer(now_ms(), warning_code);
+}
+
+// ==================== Replay Protection ====================
+
+/// Default number of recent dedup keys remembered.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 65_536;
+
+/// Recently seen dedup keys: set for lookup, queue for oldest-first eviction.
+struct DedupWindow {
+    seen: HashSet<u64>,
+    order: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl DedupWindow {
+    fn evict_to_capacity(&mut self) {
+        while self.order.len() > self.capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.seen.remove(&old);
+            }
+        }
+    }
+}
+
+static DEDUP_WINDOW: LazyLock<Mutex<DedupWindow>> = LazyLock::new(|| {
+    Mutex::new(DedupWindow { seen: HashSet::new(), order: VecDeque::new(), capacity: DEFAULT_DEDUP_CAPACITY })
+});
+
+/// Resize the dedup window (zero is rejected); shrinking forgets the oldest keys.
+pub fn set_dedup_capacity(capacity: usize) -> bool {
+    if capacity == 0 { return false; }
+    let mut window = lock(&DEDUP_WINDOW);
+    window.capacity = capacity;
+    window.evict_to_capacity();
+    true
+}
+
+/// Remember `dedup_key`; returns false if it was already within the window.
+fn remember_dedup_key(dedup_key: u64) -> bool {
+    let mut window = lock(&DEDUP_WINDOW);
+    if !window.seen.insert(dedup_key) {
+        return false;
+    }
+    window.order.push_back(dedup_key);
+    window.evict_to_capacity();
+    true
+}
+
+/// Append like `append_to_memory`, but skip records whose `dedup_key` (e.g. a
+/// hash of uuid + ts + amount computed by Java) was seen recently, so FFI
+/// retries cannot double-count volume. Returns false for a replay.
+pub fn append_to_memory_idempotent(ts: i64, amount: f64, market_key: &str, dedup_key: u64) -> bool {
+    if !remember_dedup_key(dedup_key) {
+        return false;
+    }
+    append_to_memory(ts, amount, market_key);
+    true
+}
+
+// ==================== Metadata Parsing ====================
+
+/// Market key used when trade metadata carries no usable category.
+pub const UNCATEGORIZED_MARKET_KEY: &str = "uncategorized";
+
+/// Extract the `category` field from JSON trade metadata such as
+/// `{"category": "ores", "source": "shop"}`. Malformed JSON, a missing or
+/// non-string field, an empty value or one longer than `max_key_len()` all
+/// yield None; this never panics on arbitrary input.
+pub fn parse_meta_category(meta: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(meta).ok()?;
+    let category = value.get("category")?.as_str()?;
+    if category.is_empty() || category.len() > max_key_len() || category == GLOBAL_MARKET_KEY {
+        return None;
+    }
+    Some(category.to_string())
+}
+
+/// Append a trade keyed by the category found in its JSON metadata, falling
+/// back to `UNCATEGORIZED_MARKET_KEY`. Returns whether a category was found.
+pub fn append_to_memory_with_meta(ts: i64, amount: f64, meta: &str) -> bool {
+    match parse_meta_category(meta) {
+        Some(category) => {
+            append_to_memory(ts, amount, &category);
+            true
+        }
+        None => {
+            append_to_memory(ts, amount, UNCATEGORIZED_MARKET_KEY);
+            false
+        }
+    }
+}
+
+// ==================== Input Bounds ====================
+
+/// Set the maximum accepted market key length in bytes; zero is rejected.
+pub fn set_max_key_len(len: usize) -> bool {
+    if len == 0 { return false; }
+    MAX_KEY_LEN.store(len, Ordering::Relaxed);
+    true
+}
+
+pub fn max_key_len() -> usize { MAX_KEY_LEN.load(Ordering::Relaxed) }
+
+/// Count a record rejected at the FFI boundary.
+pub fn record_dropped() {
+    DROPPED_LOGS.fetch_add(1, Ordering::Relaxed);
 }
 
 // ==================== Health Stats ====================
 
 pub fn get_total_logs() -> u64 { TOTAL_LOGS.load(Ordering::Relaxed) }
 pub fn get_dropped_logs() -> u64 { DROPPED_LOGS.load(Ordering::Relaxed) }
+pub fn get_lock_recovered() -> u64 { LOCK_RECOVERED.load(Ordering::Relaxed) }
+pub fn get_last_persisted_ts() -> i64 { LAST_PERSISTED_TS.load(Ordering::Relaxed) }
+pub fn get_history_len() -> u64 { read_lock(&GLOBAL_HISTORY).len() as u64 }
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::isolated_ts;
+
+    #[test]
+    fn test_history_cursor_pages_through_window() {
+        let _state = crate::test_support::exclusive();
+        let base = isolated_ts();
+        bulk_load_history(&[
+            HistoryRecord { timestamp: base, amount_micros: 1 },
+            HistoryRecord { timestamp: base + 1, amount_micros: 2 },
+            HistoryRecord { timestamp: base + 2, amount_micros: 3 },
+        ]);
+
+        let amounts: Vec<i64> = HistoryCursor::open_since(base + 1)
+            .filter(|r| (base..base + 3).contains(&r.timestamp))
+            .map(|r| r.amount_micros)
+            .collect();
+        assert_eq!(amounts, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_seed_history_round_trips_file() {
+        let _state = crate::test_support::exclusive();
+        let base = isolated_ts();
+        let path = std::env::temp_dir().join(format!("ecobridge_seed_{}.csv", std::process::id()));
+        let csv = format!(
+            "timestamp,amount\n# previous season\n{},2.5\n{},-1\nnot,a,record\n{},NaN\n\n{},0.25\n",
+            base + 2, base, base + 3, base + 1
+        );
+        std::fs::write(&path, csv).unwrap();
+
+        // 已有的实时记录晚于种子数据：种子需按时间戳插入其之前
+        append_to_memory(base + 10, 4.0, "test_seed_market");
+
+        let loaded = seed_history_from_file(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded, 3, "header, comment, malformed and NaN lines are skipped");
+
+        // 全局存储与定价读取的 __global__ 聚合键均可见，且保持时间升序
+        for key in [None, Some(GLOBAL_MARKET_KEY)] {
+            let seeded: Vec<(i64, i64)> = with_history(key, |h| {
+                h.iter()
+                    .filter(|r| (base..=base + 10).contains(&r.timestamp))
+                    .map(|r| (r.timestamp, r.amount_micros))
+                    .collect()
+            });
+            assert_eq!(seeded, vec![
+                (base, -1_000_000), (base + 1, 250_000), (base + 2, 2_500_000), (base + 10, 4_000_000),
+            ], "store {:?}", key);
+        }
+
+        assert!(seed_history_from_file("/nonexistent/ecobridge/seed.csv").is_err());
+    }
+
+    #[test]
+    fn test_purge_before_trims_global_and_keyed_stores() {
+        let _state = crate::test_support::exclusive();
+        // 极早的时间戳，清理不会影响其他测试写入的记录
+        append_to_memory(1_000, 1.0, "test_purge_market");
+        append_to_memory(3_000, 2.0, "test_purge_market");
+
+        assert!(purge_before(2_000) >= 1);
+
+        let remaining: Vec<i64> = with_history(Some("test_purge_market"), |h| {
+            h.iter().map(|r| r.timestamp).collect()
+        });
+        assert_eq!(remaining, vec![3_000]);
+        assert!(with_history(None, |h| h.iter().all(|r| r.timestamp >= 2_000)));
+    }
+
+    #[test]
+    fn test_rehydrate_replaces_both_global_stores() {
+        let _state = crate::test_support::exclusive();
+        // 以当前全局历史为基础重建，不丢失其他测试写入的记录
+        let ts = isolated_ts();
+        let mut records = get_history_read().clone();
+        records.push(HistoryRecord { timestamp: ts, amount_micros: 3_000_000 });
+        records.push(HistoryRecord { timestamp: ts - 1_000, amount_micros: 1_000_000 });
+
+        assert_eq!(rehydrate_history(&records), records.len());
+        for key in [None, Some(GLOBAL_MARKET_KEY)] {
+            assert!(with_history(key, |h| h.iter().any(|r| r.timestamp == ts)), "store {:?}", key);
+        }
+
+        let global = query_neff_in_memory(ts, 7.0, GLOBAL_MARKET_KEY);
+        assert!((global - query_neff_global_in_memory(ts, 7.0)).abs() < 1e-9);
+        assert!((global - 4.0).abs() < 1e-4, "rehydrated records must be visible to pricing: {}", global);
+    }
+
+    #[test]
+    fn test_async_rehydrate_is_visible_to_pricing() {
+        let _state = crate::test_support::exclusive();
+        let ts = isolated_ts();
+        let mut records = get_history_read().clone();
+        records.push(HistoryRecord { timestamp: ts - 1_000, amount_micros: 2_000_000 });
+
+        assert!(rehydrate_history_async(records));
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !is_hydration_complete() {
+            assert!(std::time::Instant::now() < deadline, "background hydration did not finish");
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        // 完成标志置位时定价读取的 __global__ 聚合键已包含重建数据
+        let neff = crate::economy::summation::query_neff_global_internal(ts, 3.3);
+        assert!((neff - 2.0).abs() < 1e-4, "pricing must see hydrated records: {}", neff);
+        assert!(with_history(None, |h| h.iter().any(|r| r.timestamp == ts - 1_000)));
+    }
+
+    #[test]
+    fn test_merge_live_tail_keeps_only_newer_records() {
+        let rec = |timestamp| HistoryRecord { timestamp, amount_micros: 1 };
+        let mut fresh = vec![rec(10), rec(20), rec(30)];
+        merge_live_tail(&mut fresh, &[rec(5), rec(30), rec(31), rec(40)]);
+        let ts: Vec<i64> = fresh.iter().map(|r| r.timestamp).collect();
+        assert_eq!(ts, vec![10, 20, 30, 31, 40]);
+
+        // 空快照时保留全部实时记录
+        let mut empty = Vec::new();
+        merge_live_tail(&mut empty, &[rec(1), rec(2)]);
+        assert_eq!(empty.len(), 2);
+    }
+
+    #[test]
+    fn test_daily_rollup_sums_per_day() {
+        let _state = crate::test_support::exclusive();
+        let day0 = isolated_ts().div_euclid(MS_PER_DAY) * MS_PER_DAY;
+        bulk_load_history(&[
+            HistoryRecord { timestamp: day0 + 1_000, amount_micros: 5_000_000 },
+            HistoryRecord { timestamp: day0 + 2_000, amount_micros: -2_000_000 },
+            HistoryRecord { timestamp: day0 + MS_PER_DAY + 1, amount_micros: 3_000_000 },
+            HistoryRecord { timestamp: day0 - 10 * MS_PER_DAY, amount_micros: 9_000_000 }, // outside window
+        ]);
+
+        let now = day0 + MS_PER_DAY + 10_000;
+        let stats = query_daily_rollup_at(now, 2);
+        assert_eq!(stats, vec![
+            DailyStat { day: day0 / MS_PER_DAY, net_micros: 3_000_000, gross_micros: 7_000_000, count: 2 },
+            DailyStat { day: day0 / MS_PER_DAY + 1, net_micros: 3_000_000, gross_micros: 3_000_000, count: 1 },
+        ]);
+        assert!(query_daily_rollup_at(now, 0).is_empty());
+    }
+
+    #[test]
+    fn test_neff_all_markets_matches_per_key_queries() {
+        let _state = crate::test_support::shared();
+        let t0 = isolated_ts();
+        append_to_memory(t0 - 1_000, 4.0, "test_all_markets_a");
+        append_to_memory(t0 - 2_000, -1.5, "test_all_markets_b");
+        append_to_memory(t0 - 3_000, 2.0, "test_all_markets_b");
+
+        let all = query_neff_all_markets(t0, 7.0);
+        assert!(all.iter().all(|(key, _)| key != GLOBAL_MARKET_KEY));
+        assert!(all.windows(2).all(|w| w[0].0 < w[1].0), "results should be sorted by key");
+        for key in ["test_all_markets_a", "test_all_markets_b"] {
+            let (_, volume) = all.iter().find(|(k, _)| k == key).expect("market present");
+            assert!((volume - query_neff_in_memory(t0, 7.0, key)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_neff_multi_tau_matches_single_tau_queries() {
+        let _state = crate::test_support::shared();
+        let key = "test_neff_multi_tau";
+        let t0 = isolated_ts();
+        for (i, amount) in [5.0, -2.0, 8.0, 1.5, 3.0].iter().enumerate() {
+            append_to_memory(t0 - (5 - i as i64) * 6 * MS_PER_DAY, *amount, key);
+        }
+        append_to_memory(t0 - 1_000, 4.0, key);
+
+        let taus = [1.0, 7.0, 30.0, 0.0, f64::NAN];
+        let multi = query_neff_multi_tau(t0, &taus, Some(key));
+        assert_eq!(multi.len(), taus.len());
+        for (tau, volume) in taus[..3].iter().zip(&multi) {
+            let single = query_neff_in_memory(t0, *tau, key);
+            assert!((volume - single).abs() <= 1e-9 * single.abs().max(1.0),
+                "tau {}: multi {} vs single {}", tau, volume, single);
+        }
+        assert_eq!(&multi[3..], &[0.0, 0.0]);
+        assert!(query_neff_multi_tau(t0, &[], Some(key)).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_tau_yields_zero_on_both_paths() {
+        let _state = crate::test_support::shared();
+        let key = "test_invalid_tau";
+        let t0 = isolated_ts();
+        append_to_memory(t0 - 1_000, 5.0, key);
+
+        for tau in [0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(query_neff_in_memory(t0, tau, key), 0.0, "reference path, tau = {}", tau);
+            let simd = with_history(Some(key), |h| calculate_volume_in_memory(h, t0, tau));
+            assert_eq!(simd, 0.0, "SIMD path, tau = {}", tau);
+        }
+        assert!(query_neff_in_memory(t0, 7.0, key) > 0.0);
+    }
+
+    #[test]
+    fn test_hourly_profile_uses_local_hours() {
+        let _state = crate::test_support::exclusive();
+        let midnight = isolated_ts().div_euclid(MS_PER_DAY) * MS_PER_DAY;
+        let hour = 3_600_000i64;
+        bulk_load_history(&[
+            HistoryRecord { timestamp: midnight + 2 * hour, amount_micros: 4_000_000 },
+            HistoryRecord { timestamp: midnight + 2 * hour + 59_000, amount_micros: -1_000_000 },
+            HistoryRecord { timestamp: midnight + 20 * hour, amount_micros: 2_500_000 },
+        ]);
+        let now = midnight + 23 * hour;
+
+        let utc = query_hourly_profile_at(now, 1, 0);
+        assert_eq!(utc[2], 5.0, "both directions count towards activity");
+        assert_eq!(utc[20], 2.5);
+
+        // UTC+8：02:00 UTC 为本地 10 点，20:00 UTC 跨日为本地 4 点
+        let cn = query_hourly_profile_at(now, 1, 8 * 3600);
+        assert_eq!(cn[10], 5.0);
+        assert_eq!(cn[4], 2.5);
+        assert_eq!(cn[2], 0.0);
+
+        assert_eq!(query_hourly_profile_at(now, 0, 0), [0.0; 24]);
+    }
+
+    #[test]
+    fn test_last_persisted_ts_tracks_wall_clock() {
+        let _state = crate::test_support::shared();
+        // 调用方给出的远未来时间戳不会被当作落库时刻
+        let ts = isolated_ts();
+        let before = now_ms();
+        append_to_memory(ts, 1.0, "test_last_persisted_market");
+        let recorded = get_last_persisted_ts();
+        assert!(recorded >= before && recorded <= now_ms());
+
+        // 乱序的旧记录也不会让时间戳回退
+        append_to_memory(ts - 1_000, 1.0, "test_last_persisted_market");
+        assert!(get_last_persisted_ts() >= recorded);
+    }
+
+    #[test]
+    fn test_idempotent_append_skips_replays() {
+        let _state = crate::test_support::shared();
+        let key = "test_dedup_market";
+        let ts = isolated_ts();
+        assert!(append_to_memory_idempotent(ts, 1.0, key, 0xDEAD_0001));
+        assert!(!append_to_memory_idempotent(ts, 1.0, key, 0xDEAD_0001), "replay must be ignored");
+        assert!(append_to_memory_idempotent(ts, 1.0, key, 0xDEAD_0002));
+        assert_eq!(with_history(Some(key), |h| h.len()), 2);
+
+        // 窗口收缩后最旧的键被遗忘
+        assert!(!set_dedup_capacity(0));
+        assert!(set_dedup_capacity(1));
+        assert!(remember_dedup_key(0xDEAD_0001), "evicted key is accepted again");
+        assert!(set_dedup_capacity(DEFAULT_DEDUP_CAPACITY));
+    }
+
+    #[test]
+    fn test_parse_meta_category_extracts_field() {
+        assert_eq!(parse_meta_category(r#"{"category":"ores","source":"shop"}"#).as_deref(), Some("ores"));
+        assert_eq!(parse_meta_category(r#"{"source":"shop"}"#), None);
+        assert_eq!(parse_meta_category(r#"{"category":42}"#), None);
+        assert_eq!(parse_meta_category(r#"{"category":""}"#), None);
+        assert_eq!(parse_meta_category(r#"{"category":"__global__"}"#), None);
+        assert_eq!(parse_meta_category(r#"["category","ores"]"#), None);
+        assert_eq!(parse_meta_category("ores"), None);
+
+        let _state = crate::test_support::shared();
+        let t0 = isolated_ts();
+        assert!(append_to_memory_with_meta(t0, 3.0, r#"{"category":"test_meta_category"}"#));
+        assert!(query_neff_in_memory(t0, 7.0, "test_meta_category") > 0.0);
+    }
+
+    /// 伪随机模糊输入：随机字节串、截断/篡改的合法 JSON 与深层嵌套均不得 panic
+    #[test]
+    fn test_parse_meta_category_fuzz_never_panics() {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move || {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        const ALPHABET: &[u8] = br#"{}[]":,\ categoryores0123456789.-+eEtrufalsn"#;
+        let valid = r#"{"category":"ores","nested":{"a":[1,2.5e3,true,null]}}"#;
+
+        for _ in 0..5_000 {
+            let len = (next() % 64) as usize;
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| {
+                    let r = next();
+                    if r % 4 == 0 { (r >> 8) as u8 } else { ALPHABET[(r >> 8) as usize % ALPHABET.len()] }
+                })
+                .collect();
+            let _ = parse_meta_category(&String::from_utf8_lossy(&bytes));
+
+            let cut = (next() as usize) % (valid.len() + 1);
+            let _ = parse_meta_category(&valid[..cut]);
+
+            let mut mutated = valid.as_bytes().to_vec();
+            let at = (next() as usize) % mutated.len();
+            mutated[at] = ALPHABET[(next() as usize) % ALPHABET.len()];
+            let _ = parse_meta_category(&String::from_utf8_lossy(&mutated));
+        }
+
+        assert_eq!(parse_meta_category(&"[".repeat(100_000)), None);
+        assert_eq!(parse_meta_category(&format!("{}{}", "{\"a\":".repeat(10_000), "1")), None);
+    }
+
+    #[test]
+    fn test_poisoned_history_lock_is_recovered() {
+        let _state = crate::test_support::exclusive();
+        let _ = std::thread::spawn(|| {
+            let _guard = GLOBAL_HISTORY.write().unwrap();
+            panic!("poison the global history lock");
+        })
+        .join();
+        assert!(GLOBAL_HISTORY.is_poisoned());
+
+        let recovered_before = get_lock_recovered();
+        let ts = isolated_ts();
+        append_to_memory(ts, 2.0, "test_poisoned_lock");
+        assert!(get_lock_recovered() > recovered_before);
+        assert!(!GLOBAL_HISTORY.is_poisoned(), "recovery must clear the poison flag");
+        assert!(get_history_read().iter().any(|r| r.timestamp == ts));
+        assert!(query_neff_global_in_memory(ts, 7.0).is_finite());
+    }
+
+    #[test]
+    fn test_poisoned_mutex_is_recovered_and_cleared() {
+        let states = std::sync::Arc::new(Mutex::new(vec![1u32]));
+        let poisoner = std::sync::Arc::clone(&states);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison the state map");
+        })
+        .join();
+        assert!(states.is_poisoned());
+
+        lock(&states).push(2);
+        assert!(!states.is_poisoned());
+        assert_eq!(*states.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_blocked_count_by_reason_and_window() {
+        let now = isolated_ts();
+        let day = 86_400_000i64;
+        log_blocked_transfer(now - 1_000, 5);
+        log_blocked_transfer(now - 2_000, 5);
+        log_blocked_transfer(now - 3_000, 6);
+        log_blocked_transfer(now - 3 * day, 5); // outside a 1-day window
+
+        assert_eq!(query_blocked_count_at(now, 1.0, 5), 2);
+        assert_eq!(query_blocked_count_at(now, 1.0, 6), 1);
+        assert_eq!(query_blocked_count_at(now, 1.0, -1), 3);
+        assert_eq!(query_blocked_count_at(now, 7.0, 5), 3);
+        assert_eq!(query_blocked_count_at(now, 0.0, -1), 0);
+    }
+}
@@ -0,0 +1,32 @@
+//! 绝对硬底线是进程级全局值：单独成一个测试二进制，
+//! 修改它不会干扰库内并行运行的单元测试。
+
+use ecobridge_rust::economy::pricing::*;
+
+#[test]
+fn test_min_price_floor_applies_everywhere() {
+    assert_eq!(min_price(), DEFAULT_MIN_PRICE);
+    assert!(set_min_price(5.0));
+    assert_eq!(min_price(), 5.0);
+
+    let prices = [
+        compute_price_final_internal(1_000_000, 1_000_000.0, 0.01, 1.0),
+        compute_price_humane_internal(1_000_000, 1_000_000.0, 1_000_000, 0.01, 1.0),
+        compute_price_humane_internal(1_000_000, f64::NAN, 0, 0.01, 1.0),
+        compute_price_bounded_internal(1_000_000, 1_000_000.0, 0, 0.01, 1.0, 0.001),
+        compute_price_with_band(1_000_000, 1_000_000.0, 0, 0.01, 1.0, 1.0, 0.5, 3.0),
+        compute_price_linear(1_000_000, 1_000_000.0, 0, 0.01, 0.0, 10.0),
+        compute_player_sell_price(1_000_000, 1.0, 0.01, &[(1_000_000.0, 0.0)], 0.5, 7.0),
+        compute_player_sell_price(0, 1.0, 0.01, &[], 0.5, 7.0),
+        compute_system_bid(1_000_000, 0.0),
+    ];
+
+    // 非法值被拒绝，保持当前底线
+    assert!(!set_min_price(0.0));
+    assert_eq!(min_price(), 5.0);
+    assert!(set_min_price(DEFAULT_MIN_PRICE));
+
+    for (i, p) in prices.iter().enumerate() {
+        assert!(*p >= 5.0, "pricing function #{} returned {} below the configured floor", i, p);
+    }
+}
@@ -0,0 +1,41 @@
+//! 极端时间戳经热存储进入 N_eff 查询 FFI：写入会污染进程级全局热存储，
+//! 因此单独成一个测试二进制，不干扰库内并行运行的单元测试。
+
+use std::ffi::CString;
+
+use ecobridge_rust::storage::append_to_memory;
+use ecobridge_rust::{ecobridge_query_neff_global_in_memory, ecobridge_query_neff_in_memory};
+
+#[test]
+fn test_storage_neff_ffi_survives_i64_extremes() {
+    let key = "neff_extremes_market";
+    let c_key = CString::new(key).unwrap();
+    let now = 2_000_000_000i64;
+    append_to_memory(now - 1_000, 1.0, key);
+    append_to_memory(now, 2.0, key);
+
+    let query = |current: i64, tau: f64| {
+        let (mut keyed, mut global) = (f64::NAN, f64::NAN);
+        unsafe {
+            assert_eq!(ecobridge_query_neff_in_memory(current, tau, c_key.as_ptr(), &mut keyed), 0);
+            assert_eq!(ecobridge_query_neff_global_in_memory(current, tau, &mut global), 0);
+        }
+        (keyed, global)
+    };
+    let (expected, _) = query(now, 7.0);
+    assert!(expected > 0.0);
+
+    // 乱序的极端时间戳不会溢出，也不改变正常窗口内的结果
+    for ts in [i64::MAX, i64::MIN, i64::MAX - 1] {
+        append_to_memory(ts, 5.0, key);
+    }
+    let (keyed, _) = query(now, 7.0);
+    assert!((keyed - expected).abs() < 1e-9, "outliers must be ignored: {} vs {}", keyed, expected);
+
+    for current in [i64::MIN, i64::MAX, 0] {
+        for tau in [1e-9, 7.0, 1e9] {
+            let (keyed, global) = query(current, tau);
+            assert!(keyed.is_finite() && global.is_finite(), "current={} tau={}: {} / {}", current, tau, keyed, global);
+        }
+    }
+}